@@ -0,0 +1,216 @@
+use crate::store::{Error, Ingredient, Link, Recipe, Store};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+    // Whether the store was modified and should be saved
+    pub modified: bool,
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => out.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                match (hex_value(bytes[index + 1]), hex_value(bytes[index + 2])) {
+                    (Some(high), Some(low)) => {
+                        out.push(high << 4 | low);
+                        index += 2;
+                    }
+                    _ => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn parse_form(input: &str) -> HashMap<String, String> {
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (percent_decode(key), percent_decode(value)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect()
+}
+
+// Request parameters, from a JSON object or a urlencoded form
+fn parse_body(content_type: &str, body: &str) -> Result<HashMap<String, String>, Error> {
+    if !content_type.starts_with("application/json") {
+        return Ok(parse_form(body));
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Object(fields)) => Ok(fields
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect()),
+        Ok(Value::Null) => Ok(HashMap::new()),
+        _ if body.trim().is_empty() => Ok(HashMap::new()),
+        _ => Err(Error::Invalid(String::from("Expected a JSON object"))),
+    }
+}
+
+struct Params(HashMap<String, String>);
+
+impl Params {
+    fn text(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+
+    fn required(&self, key: &str) -> Result<String, Error> {
+        self.text(key)
+            .ok_or_else(|| Error::Invalid(format!("Missing parameter {}", key)))
+    }
+
+    fn flag(&self, key: &str) -> Option<bool> {
+        self.0
+            .get(key)
+            .map(|value| matches!(value.as_str(), "true" | "1" | "on"))
+    }
+
+    fn link(&self) -> Link {
+        Link {
+            quantity: self.text("quantity").unwrap_or_default(),
+            optional: self.flag("optional").unwrap_or_default(),
+        }
+    }
+}
+
+fn route(
+    store: &mut Store,
+    method: &str,
+    path: &[&str],
+    params: &Params,
+) -> Result<(Value, bool), Error> {
+    let pattern = params.text("name").unwrap_or_default();
+
+    let read = |value: Result<Value, Error>| value.map(|value| (value, false));
+    let write = |value: Result<Value, Error>| value.map(|value| (value, true));
+
+    match (method, path) {
+        ("GET", ["recipes"]) => Ok((store.list_recipes(&pattern), false)),
+        ("POST", ["recipes", "new"]) => write(store.create_recipe(Recipe {
+            name: params.required("name")?,
+            author: params.text("author").unwrap_or_default(),
+            directions: params.text("directions").unwrap_or_default(),
+            information: params.text("information").unwrap_or_default(),
+            ..Recipe::default()
+        })),
+        ("GET", ["recipes", id]) => read(store.get_recipe(id)),
+        ("PUT", ["recipes", id]) => write(store.update_recipe(
+            id,
+            params.text("name"),
+            params.text("author"),
+            params.text("directions"),
+            params.text("information"),
+        )),
+        ("DELETE", ["recipes", id]) => write(store.delete_recipe(id)),
+
+        ("POST", ["recipes", id, "requirements", "add"]) => {
+            write(store.add_requirement(id, &params.required("ingredient_id")?, params.link()))
+        }
+        ("DELETE", ["recipes", id, "requirements", ingredient_id]) => {
+            write(store.delete_requirement(id, ingredient_id))
+        }
+
+        ("POST", ["recipes", id, "dependencies", "add"]) => {
+            write(store.add_dependency(id, &params.required("required_id")?, params.link()))
+        }
+        ("DELETE", ["recipes", id, "dependencies", required_id]) => {
+            write(store.delete_dependency(id, required_id))
+        }
+
+        ("POST", ["recipes", id, "tag", "add"]) => {
+            write(store.tag_recipe(id, &params.required("name")?))
+        }
+        ("DELETE", ["recipes", id, "tag", label_id]) => write(store.untag_recipe(id, label_id)),
+
+        ("GET", ["ingredients"]) => Ok((store.list_ingredients(&pattern), false)),
+        ("POST", ["ingredients", "new"]) => write(store.create_ingredient(Ingredient {
+            name: params.required("name")?,
+            dairy: params.flag("dairy").unwrap_or_default(),
+            meat: params.flag("meat").unwrap_or_default(),
+            gluten: params.flag("gluten").unwrap_or_default(),
+            animal_product: params.flag("animal_product").unwrap_or_default(),
+        })),
+        ("GET", ["ingredients", id]) => read(store.get_ingredient(id)),
+        ("PUT", ["ingredients", id]) => write(store.update_ingredient(
+            id,
+            params.text("name"),
+            [
+                params.flag("dairy"),
+                params.flag("meat"),
+                params.flag("gluten"),
+                params.flag("animal_product"),
+            ],
+        )),
+        ("DELETE", ["ingredients", id]) => write(store.delete_ingredient(id)),
+
+        ("GET", ["labels"]) => Ok((store.list_labels(&pattern), false)),
+        ("GET", ["labels", id]) => read(store.get_label(id)),
+
+        _ => Err(Error::NotFound(format!(
+            "No route for {} /{}",
+            method,
+            path.join("/")
+        ))),
+    }
+}
+
+// Answer a request the way knife does: a JSON envelope with `accept` telling
+// whether the request succeeded, and either `data` or `error`
+pub fn handle(
+    store: &mut Store,
+    method: &str,
+    url: &str,
+    content_type: &str,
+    body: &str,
+) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let path: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect();
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+
+    let result = parse_body(content_type, body).and_then(|mut params| {
+        params.extend(parse_form(query));
+        route(store, method, &path, &Params(params))
+    });
+
+    match result {
+        Ok((data, modified)) => Response {
+            status: 200,
+            body: json!({"accept": true, "data": data}),
+            modified,
+        },
+        Err(error) => Response {
+            status: match error {
+                Error::NotFound(_) => 404,
+                Error::Invalid(_) => 400,
+            },
+            body: json!({"accept": false, "error": error.message()}),
+            modified: false,
+        },
+    }
+}
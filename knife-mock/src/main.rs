@@ -0,0 +1,153 @@
+use knife_mock::api::handle;
+use knife_mock::store::Store;
+use std::path::PathBuf;
+use tiny_http::{Header, Method, Response, Server};
+
+static USAGE: &str = "Usage: knife-mock [--port PORT] [--store FILE] [--empty]
+
+Serve the knife API from memory, seeded with fixture recipes.
+
+  --port PORT   Port to listen on (default 8000)
+  --store FILE  Load the data from FILE, and save every change to it
+  --empty       Start without the fixtures";
+
+struct Options {
+    port: u16,
+    store: Option<PathBuf>,
+    empty: bool,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        port: 8000,
+        store: None,
+        empty: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                options.port = args
+                    .next()
+                    .and_then(|port| port.parse().ok())
+                    .ok_or("--port expects a port number")?
+            }
+            "--store" => options.store = Some(args.next().ok_or("--store expects a path")?.into()),
+            "--empty" => options.empty = true,
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn load(options: &Options) -> Result<Store, String> {
+    if let Some(path) = options.store.as_ref().filter(|path| path.exists()) {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        return serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+
+    Ok(match options.empty {
+        true => Store::default(),
+        false => Store::fixtures(),
+    })
+}
+
+fn save(path: &PathBuf, store: &Store) {
+    let result = serde_json::to_string_pretty(store)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| std::fs::write(path, contents).map_err(|e| e.to_string()));
+
+    if let Err(message) = result {
+        eprintln!("Failed to save {}: {}", path.display(), message);
+    }
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).expect("Invalid header")
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let mut store = match load(&options) {
+        Ok(store) => store,
+        Err(message) => {
+            eprintln!("Failed to load the store: {}", message);
+            std::process::exit(1);
+        }
+    };
+
+    let server = match Server::http(("127.0.0.1", options.port)) {
+        Ok(server) => server,
+        Err(error) => {
+            eprintln!("Failed to listen on port {}: {}", options.port, error);
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "Mock knife server listening on http://127.0.0.1:{}",
+        options.port
+    );
+
+    for mut request in server.incoming_requests() {
+        // spoon is served from another origin by trunk
+        let cors = [
+            header("Access-Control-Allow-Origin", "*"),
+            header(
+                "Access-Control-Allow-Methods",
+                "GET, POST, PUT, DELETE, OPTIONS",
+            ),
+            header(
+                "Access-Control-Allow-Headers",
+                "Authorization, Content-Type",
+            ),
+        ];
+
+        if request.method() == &Method::Options {
+            let mut response = Response::empty(204);
+            for header in cors {
+                response.add_header(header);
+            }
+            let _ = request.respond(response);
+            continue;
+        }
+
+        let content_type = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Content-Type"))
+            .map(|header| header.value.as_str().to_string())
+            .unwrap_or_default();
+
+        let mut body = String::new();
+        if let Err(error) = request.as_reader().read_to_string(&mut body) {
+            eprintln!("Failed to read request body: {}", error);
+        }
+
+        let method = request.method().to_string();
+        let answer = handle(&mut store, &method, request.url(), &content_type, &body);
+        println!("{} {} -> {}", method, request.url(), answer.status);
+
+        if answer.modified {
+            if let Some(path) = &options.store {
+                save(path, &store);
+            }
+        }
+
+        let mut response = Response::from_string(answer.body.to_string())
+            .with_status_code(answer.status)
+            .with_header(header("Content-Type", "application/json"));
+        for header in cors {
+            response.add_header(header);
+        }
+        let _ = request.respond(response);
+    }
+}
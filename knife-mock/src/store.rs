@@ -0,0 +1,510 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Ingredient {
+    pub name: String,
+    #[serde(default)]
+    pub dairy: bool,
+    #[serde(default)]
+    pub meat: bool,
+    #[serde(default)]
+    pub gluten: bool,
+    #[serde(default)]
+    pub animal_product: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Link {
+    #[serde(default)]
+    pub quantity: String,
+    #[serde(default)]
+    pub optional: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Recipe {
+    pub name: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub directions: String,
+    #[serde(default)]
+    pub information: String,
+    // Ingredient id -> requirement
+    #[serde(default)]
+    pub requirements: BTreeMap<String, Link>,
+    // Recipe id -> dependency
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Link>,
+    // Label ids
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+}
+
+// Everything a knife server stores. This is also the layout of the JSON
+// store and fixture files.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Store {
+    #[serde(default)]
+    pub next_id: u64,
+    #[serde(default)]
+    pub ingredients: BTreeMap<String, Ingredient>,
+    #[serde(default)]
+    pub recipes: BTreeMap<String, Recipe>,
+    // Label id -> name
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    NotFound(String),
+    Invalid(String),
+}
+
+impl Error {
+    pub fn message(&self) -> &str {
+        match self {
+            Error::NotFound(message) | Error::Invalid(message) => message,
+        }
+    }
+}
+
+fn not_found(kind: &str, id: &str) -> Error {
+    Error::NotFound(format!("No {} with id {}", kind, id))
+}
+
+fn matches(name: &str, pattern: &str) -> bool {
+    name.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+impl Store {
+    pub fn fixtures() -> Store {
+        serde_json::from_str(include_str!("../fixtures.json")).expect("Invalid fixture file")
+    }
+
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("mock-{}", self.next_id)
+    }
+
+    fn ingredient(&self, id: &str) -> Result<&Ingredient, Error> {
+        self.ingredients
+            .get(id)
+            .ok_or_else(|| not_found("ingredient", id))
+    }
+
+    fn recipe(&self, id: &str) -> Result<&Recipe, Error> {
+        self.recipes.get(id).ok_or_else(|| not_found("recipe", id))
+    }
+
+    fn recipe_mut(&mut self, id: &str) -> Result<&mut Recipe, Error> {
+        self.recipes
+            .get_mut(id)
+            .ok_or_else(|| not_found("recipe", id))
+    }
+
+    fn ingredient_index(&self, id: &str) -> Value {
+        let name = self.ingredients.get(id).map(|i| i.name.as_str());
+        json!({"id": id, "name": name.unwrap_or_default()})
+    }
+
+    fn recipe_index(&self, id: &str) -> Value {
+        let name = self.recipes.get(id).map(|r| r.name.as_str());
+        json!({"id": id, "name": name.unwrap_or_default()})
+    }
+
+    fn label_index(&self, id: &str) -> Value {
+        let name = self.labels.get(id).map(String::as_str);
+        json!({"id": id, "name": name.unwrap_or_default()})
+    }
+
+    // Classifications of a recipe, from its ingredients and the ones of its
+    // dependencies
+    fn classifications(&self, id: &str, visited: &mut HashSet<String>) -> Ingredient {
+        let mut out = Ingredient::default();
+        if !visited.insert(id.to_string()) {
+            return out;
+        }
+
+        if let Some(recipe) = self.recipes.get(id) {
+            let ingredients = recipe
+                .requirements
+                .keys()
+                .filter_map(|id| self.ingredients.get(id))
+                .cloned();
+            let dependencies = recipe
+                .dependencies
+                .keys()
+                .map(|id| self.classifications(id, visited))
+                .collect::<Vec<_>>();
+
+            for other in ingredients.chain(dependencies) {
+                out.dairy |= other.dairy;
+                out.meat |= other.meat;
+                out.gluten |= other.gluten;
+                out.animal_product |= other.animal_product;
+            }
+        }
+
+        out
+    }
+
+    // Whether `id` can be reached from `from` through dependencies
+    fn depends_on(&self, from: &str, id: &str) -> bool {
+        let mut stack = vec![from.to_string()];
+        let mut visited = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if current == id {
+                return true;
+            }
+            if visited.insert(current.clone()) {
+                if let Some(recipe) = self.recipes.get(&current) {
+                    stack.extend(recipe.dependencies.keys().cloned());
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn list_recipes(&self, pattern: &str) -> Value {
+        self.recipes
+            .iter()
+            .filter(|(_, recipe)| matches(&recipe.name, pattern))
+            .map(|(id, _)| self.recipe_index(id))
+            .collect()
+    }
+
+    pub fn get_recipe(&self, id: &str) -> Result<Value, Error> {
+        let recipe = self.recipe(id)?;
+        let classifications = self.classifications(id, &mut HashSet::new());
+
+        let requirements: Vec<Value> = recipe
+            .requirements
+            .iter()
+            .map(|(ingredient, link)| {
+                json!({
+                    "ingredient": self.ingredient_index(ingredient),
+                    "quantity": link.quantity,
+                    "optional": link.optional,
+                })
+            })
+            .collect();
+
+        let dependencies: Vec<Value> = recipe
+            .dependencies
+            .iter()
+            .map(|(required, link)| {
+                json!({
+                    "recipe": self.recipe_index(required),
+                    "quantity": link.quantity,
+                    "optional": link.optional,
+                })
+            })
+            .collect();
+
+        let tags: Vec<Value> = recipe.tags.iter().map(|id| self.label_index(id)).collect();
+
+        Ok(json!({
+            "id": id,
+            "name": recipe.name,
+            "author": recipe.author,
+            "directions": recipe.directions,
+            "information": recipe.information,
+            "requirements": requirements,
+            "dependencies": dependencies,
+            "tags": tags,
+            "classifications": {
+                "dairy": classifications.dairy,
+                "meat": classifications.meat,
+                "gluten": classifications.gluten,
+                "animal_product": classifications.animal_product,
+            },
+        }))
+    }
+
+    pub fn create_recipe(&mut self, recipe: Recipe) -> Result<Value, Error> {
+        if recipe.name.is_empty() {
+            return Err(Error::Invalid(String::from("Recipe name is empty")));
+        }
+        if self.recipes.values().any(|other| other.name == recipe.name) {
+            return Err(Error::Invalid(format!(
+                "A recipe named {} already exists",
+                recipe.name
+            )));
+        }
+
+        let id = self.new_id();
+        self.recipes.insert(
+            id.clone(),
+            Recipe {
+                requirements: BTreeMap::new(),
+                dependencies: BTreeMap::new(),
+                tags: BTreeSet::new(),
+                ..recipe
+            },
+        );
+        Ok(self.recipe_index(&id))
+    }
+
+    pub fn update_recipe(
+        &mut self,
+        id: &str,
+        name: Option<String>,
+        author: Option<String>,
+        directions: Option<String>,
+        information: Option<String>,
+    ) -> Result<Value, Error> {
+        if let Some(name) = &name {
+            if self
+                .recipes
+                .iter()
+                .any(|(other, recipe)| other != id && &recipe.name == name)
+            {
+                return Err(Error::Invalid(format!(
+                    "A recipe named {} already exists",
+                    name
+                )));
+            }
+        }
+
+        let recipe = self.recipe_mut(id)?;
+        recipe.name = name.unwrap_or(recipe.name.clone());
+        recipe.author = author.unwrap_or(recipe.author.clone());
+        recipe.directions = directions.unwrap_or(recipe.directions.clone());
+        recipe.information = information.unwrap_or(recipe.information.clone());
+        Ok(Value::Null)
+    }
+
+    pub fn delete_recipe(&mut self, id: &str) -> Result<Value, Error> {
+        self.recipes
+            .remove(id)
+            .ok_or_else(|| not_found("recipe", id))?;
+
+        for recipe in self.recipes.values_mut() {
+            recipe.dependencies.remove(id);
+        }
+        Ok(Value::Null)
+    }
+
+    pub fn tag_recipe(&mut self, id: &str, label_name: &str) -> Result<Value, Error> {
+        self.recipe(id)?;
+        if label_name.is_empty() {
+            return Err(Error::Invalid(String::from("Label name is empty")));
+        }
+
+        let label = match self.labels.iter().find(|(_, name)| *name == label_name) {
+            Some((label, _)) => label.clone(),
+            None => {
+                let label = self.new_id();
+                self.labels.insert(label.clone(), label_name.to_string());
+                label
+            }
+        };
+
+        self.recipe_mut(id)?.tags.insert(label.clone());
+        Ok(self.label_index(&label))
+    }
+
+    pub fn untag_recipe(&mut self, id: &str, label_id: &str) -> Result<Value, Error> {
+        match self.recipe_mut(id)?.tags.remove(label_id) {
+            true => Ok(Value::Null),
+            false => Err(not_found("tag", label_id)),
+        }
+    }
+
+    pub fn list_ingredients(&self, pattern: &str) -> Value {
+        self.ingredients
+            .iter()
+            .filter(|(_, ingredient)| matches(&ingredient.name, pattern))
+            .map(|(id, _)| self.ingredient_index(id))
+            .collect()
+    }
+
+    pub fn get_ingredient(&self, id: &str) -> Result<Value, Error> {
+        let ingredient = self.ingredient(id)?;
+
+        let used_in: Vec<Value> = self
+            .recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.requirements.contains_key(id))
+            .map(|(recipe, _)| self.recipe_index(recipe))
+            .collect();
+
+        Ok(json!({
+            "id": id,
+            "name": ingredient.name,
+            "classifications": {
+                "dairy": ingredient.dairy,
+                "meat": ingredient.meat,
+                "gluten": ingredient.gluten,
+                "animal_product": ingredient.animal_product,
+            },
+            "used_in": used_in,
+        }))
+    }
+
+    pub fn create_ingredient(&mut self, ingredient: Ingredient) -> Result<Value, Error> {
+        if ingredient.name.is_empty() {
+            return Err(Error::Invalid(String::from("Ingredient name is empty")));
+        }
+        if self.ingredients.values().any(|i| i.name == ingredient.name) {
+            return Err(Error::Invalid(format!(
+                "An ingredient named {} already exists",
+                ingredient.name
+            )));
+        }
+
+        let id = self.new_id();
+        self.ingredients.insert(id.clone(), ingredient);
+        Ok(self.ingredient_index(&id))
+    }
+
+    pub fn update_ingredient(
+        &mut self,
+        id: &str,
+        name: Option<String>,
+        classifications: [Option<bool>; 4],
+    ) -> Result<Value, Error> {
+        if let Some(name) = &name {
+            if self
+                .ingredients
+                .iter()
+                .any(|(other, ingredient)| other != id && &ingredient.name == name)
+            {
+                return Err(Error::Invalid(format!(
+                    "An ingredient named {} already exists",
+                    name
+                )));
+            }
+        }
+
+        let ingredient = self
+            .ingredients
+            .get_mut(id)
+            .ok_or_else(|| not_found("ingredient", id))?;
+        let [dairy, meat, gluten, animal_product] = classifications;
+        ingredient.name = name.unwrap_or(ingredient.name.clone());
+        ingredient.dairy = dairy.unwrap_or(ingredient.dairy);
+        ingredient.meat = meat.unwrap_or(ingredient.meat);
+        ingredient.gluten = gluten.unwrap_or(ingredient.gluten);
+        ingredient.animal_product = animal_product.unwrap_or(ingredient.animal_product);
+        Ok(Value::Null)
+    }
+
+    pub fn delete_ingredient(&mut self, id: &str) -> Result<Value, Error> {
+        self.ingredient(id)?;
+
+        if self
+            .recipes
+            .values()
+            .any(|recipe| recipe.requirements.contains_key(id))
+        {
+            return Err(Error::Invalid(String::from(
+                "This ingredient is used in recipes and cannot be deleted",
+            )));
+        }
+
+        self.ingredients.remove(id);
+        Ok(Value::Null)
+    }
+
+    pub fn add_requirement(
+        &mut self,
+        recipe_id: &str,
+        ingredient_id: &str,
+        link: Link,
+    ) -> Result<Value, Error> {
+        self.ingredient(ingredient_id)?;
+
+        let recipe = self.recipe_mut(recipe_id)?;
+        if recipe.requirements.contains_key(ingredient_id) {
+            return Err(Error::Invalid(String::from(
+                "This ingredient is already required",
+            )));
+        }
+
+        recipe.requirements.insert(ingredient_id.to_string(), link);
+        Ok(Value::Null)
+    }
+
+    pub fn delete_requirement(
+        &mut self,
+        recipe_id: &str,
+        ingredient_id: &str,
+    ) -> Result<Value, Error> {
+        match self
+            .recipe_mut(recipe_id)?
+            .requirements
+            .remove(ingredient_id)
+        {
+            Some(_) => Ok(Value::Null),
+            None => Err(not_found("requirement", ingredient_id)),
+        }
+    }
+
+    pub fn add_dependency(
+        &mut self,
+        recipe_id: &str,
+        required_id: &str,
+        link: Link,
+    ) -> Result<Value, Error> {
+        self.recipe(required_id)?;
+
+        if self.depends_on(required_id, recipe_id) {
+            return Err(Error::Invalid(String::from(
+                "This dependency would create a cycle between recipes",
+            )));
+        }
+
+        let recipe = self.recipe_mut(recipe_id)?;
+        if recipe.dependencies.contains_key(required_id) {
+            return Err(Error::Invalid(String::from(
+                "This recipe is already required",
+            )));
+        }
+
+        recipe.dependencies.insert(required_id.to_string(), link);
+        Ok(Value::Null)
+    }
+
+    pub fn delete_dependency(
+        &mut self,
+        recipe_id: &str,
+        required_id: &str,
+    ) -> Result<Value, Error> {
+        match self.recipe_mut(recipe_id)?.dependencies.remove(required_id) {
+            Some(_) => Ok(Value::Null),
+            None => Err(not_found("dependency", required_id)),
+        }
+    }
+
+    pub fn list_labels(&self, pattern: &str) -> Value {
+        self.labels
+            .iter()
+            .filter(|(_, name)| matches(name, pattern))
+            .map(|(id, _)| self.label_index(id))
+            .collect()
+    }
+
+    pub fn get_label(&self, id: &str) -> Result<Value, Error> {
+        let name = self.labels.get(id).ok_or_else(|| not_found("label", id))?;
+
+        let tagged_recipes: Vec<Value> = self
+            .recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.tags.contains(id))
+            .map(|(recipe, _)| self.recipe_index(recipe))
+            .collect();
+
+        Ok(json!({
+            "id": id,
+            "name": name,
+            "tagged_recipes": tagged_recipes,
+        }))
+    }
+}
@@ -0,0 +1,116 @@
+use knife_mock::api::handle;
+use knife_mock::store::Store;
+use serde_json::Value;
+
+fn get(store: &mut Store, url: &str) -> Value {
+    let response = handle(store, "GET", url, "", "");
+    assert_eq!(response.status, 200, "GET {}: {}", url, response.body);
+    response.body["data"].clone()
+}
+
+fn send(store: &mut Store, method: &str, url: &str, body: &str) -> (u16, Value) {
+    let response = handle(
+        store,
+        method,
+        url,
+        "application/x-www-form-urlencoded",
+        body,
+    );
+    (response.status, response.body)
+}
+
+#[test]
+fn fixtures_compute_classifications() {
+    let mut store = Store::fixtures();
+
+    let pizza = get(&mut store, "/recipes/pizza");
+    assert_eq!(pizza["dependencies"].as_array().unwrap().len(), 2);
+    // Gluten comes from the dough, meat from the recipe itself
+    assert_eq!(pizza["classifications"]["gluten"], true);
+    assert_eq!(pizza["classifications"]["meat"], true);
+    assert_eq!(pizza["classifications"]["dairy"], false);
+
+    let flour = get(&mut store, "/ingredients/flour");
+    assert_eq!(flour["used_in"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn recipe_lifecycle() {
+    let mut store = Store::fixtures();
+
+    let (status, body) = send(
+        &mut store,
+        "POST",
+        "/recipes/new",
+        "name=Cr%C3%AApes&author=me",
+    );
+    assert_eq!(status, 200);
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+    assert_eq!(body["data"]["name"], "Crêpes");
+
+    let (status, _) = send(
+        &mut store,
+        "POST",
+        &format!("/recipes/{}/requirements/add", id),
+        "ingredient_id=milk&quantity=1+l",
+    );
+    assert_eq!(status, 200);
+
+    let (status, _) = send(
+        &mut store,
+        "POST",
+        &format!("/recipes/{}/tag/add", id),
+        "name=dessert",
+    );
+    assert_eq!(status, 200);
+
+    let recipe = get(&mut store, &format!("/recipes/{}", id));
+    assert_eq!(recipe["requirements"][0]["quantity"], "1 l");
+    assert_eq!(recipe["classifications"]["dairy"], true);
+    assert_eq!(recipe["tags"][0]["name"], "dessert");
+
+    let found = get(&mut store, "/recipes?name=cr%C3%AA");
+    assert_eq!(found.as_array().unwrap().len(), 1);
+
+    // Used ingredients cannot be deleted
+    let (status, body) = send(&mut store, "DELETE", "/ingredients/milk", "");
+    assert_eq!(status, 400);
+    assert_eq!(body["accept"], false);
+
+    let (status, _) = send(&mut store, "DELETE", &format!("/recipes/{}", id), "");
+    assert_eq!(status, 200);
+    let (status, _) = send(&mut store, "GET", &format!("/recipes/{}", id), "");
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn dependency_cycles_are_refused() {
+    let mut store = Store::fixtures();
+
+    let (status, _) = send(
+        &mut store,
+        "POST",
+        "/recipes/pizza-dough/dependencies/add",
+        "required_id=pizza",
+    );
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn json_bodies_are_accepted() {
+    let mut store = Store::default();
+
+    let response = handle(
+        &mut store,
+        "POST",
+        "/ingredients/new",
+        "application/json",
+        r#"{"name": "Cream", "dairy": true}"#,
+    );
+    assert_eq!(response.status, 200);
+    assert!(response.modified);
+
+    let id = response.body["data"]["id"].as_str().unwrap().to_string();
+    let cream = get(&mut store, &format!("/ingredients/{}", id));
+    assert_eq!(cream["classifications"]["dairy"], true);
+}
@@ -0,0 +1,410 @@
+use crate::app::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// ladle does not let us set headers, so requests are intercepted at the fetch
+// level to add the credentials of each server and report refusals of the
+// active one
+#[wasm_bindgen(inline_js = "
+const nativeFetch = window.fetch.bind(window);
+const authorizations = new Map();
+let deniedOrigin = null;
+let onDenied = null;
+
+window.fetch = async function (input, init) {
+    const url = new URL(input instanceof Request ? input.url : input, window.location.href);
+    const authorization = authorizations.get(url.origin);
+    if (authorization === undefined && url.origin !== deniedOrigin) {
+        return nativeFetch(input, init);
+    }
+
+    const request = new Request(input, init);
+    if (authorization !== undefined) {
+        request.headers.set('Authorization', authorization);
+    }
+
+    const response = await nativeFetch(request);
+    if ((response.status === 401 || response.status === 403)
+        && url.origin === deniedOrigin && onDenied !== null) {
+        onDenied(response.status);
+    }
+    return response;
+};
+
+export function set_authorization(origin, header) {
+    if (header === undefined) {
+        authorizations.delete(origin);
+    } else {
+        authorizations.set(origin, header);
+    }
+}
+
+export function clear_authorizations() {
+    authorizations.clear();
+}
+
+export function set_denied(origin, denied) {
+    deniedOrigin = origin === undefined ? null : origin;
+    onDenied = denied === undefined ? null : denied;
+}
+")]
+extern "C" {
+    fn set_authorization(origin: String, header: Option<String>);
+    fn clear_authorizations();
+    fn set_denied(origin: Option<String>, denied: Option<&js_sys::Function>);
+}
+
+fn origin(server_url: &str) -> Option<String> {
+    web_sys::Url::new(server_url).ok().map(|url| url.origin())
+}
+
+#[derive(Default, Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum Credentials {
+    #[default]
+    None,
+    Bearer(String),
+    Basic {
+        username: String,
+        password: String,
+    },
+}
+
+// Credentials of the Basic scheme as a string `btoa` accepts: it only takes
+// Latin-1, so each byte of the UTF-8 encoding becomes the character of the
+// same code
+fn basic_payload(username: &str, password: &str) -> String {
+    format!("{}:{}", username, password)
+        .bytes()
+        .map(char::from)
+        .collect()
+}
+
+impl Credentials {
+    // Value of the Authorization header to send
+    pub fn header(&self) -> Option<String> {
+        match self {
+            Credentials::None => None,
+            Credentials::Bearer(token) => Some(format!("Bearer {}", token.trim())),
+            Credentials::Basic { username, password } => web_sys::window()
+                .and_then(|window| window.btoa(&basic_payload(username, password)).ok())
+                .map(|encoded| format!("Basic {}", encoded)),
+        }
+    }
+}
+
+// Send `credentials` along with every request to the server at `server_url`,
+// such as one being tested before it is saved
+pub fn grant(server_url: &str, credentials: &Credentials) {
+    if let Some(origin) = origin(server_url) {
+        set_authorization(origin, credentials.header());
+    }
+}
+
+// Reports the refusals of the active server as long as it is kept
+pub struct DeniedListener {
+    _denied: Closure<dyn Fn(u16)>,
+}
+
+impl Drop for DeniedListener {
+    fn drop(&mut self) {
+        set_denied(None, None);
+    }
+}
+
+// Send the stored credentials of each server along with the requests to it,
+// and call `denied` with the status code when the active server refuses one
+pub fn authorize(settings: &AppSettings, denied: Callback<u16>) -> DeniedListener {
+    clear_authorizations();
+    for (server_url, credentials) in settings.credentials.iter() {
+        grant(server_url, credentials);
+    }
+
+    let denied = Closure::<dyn Fn(u16)>::new(move |status| denied.emit(status));
+    set_denied(
+        origin(settings.data_source()),
+        Some(denied.as_ref().unchecked_ref()),
+    );
+    DeniedListener { _denied: denied }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CredentialsFormProps {
+    pub current: AppSettings,
+    pub update_settings: Callback<AppSettings>,
+    #[prop_or_default]
+    pub on_saved: Callback<()>,
+    // Server whose credentials are edited, the saved one if none
+    #[prop_or_default]
+    pub server_url: Option<String>,
+}
+
+impl CredentialsFormProps {
+    fn server_url(&self) -> String {
+        self.server_url
+            .clone()
+            .unwrap_or_else(|| self.current.server_url.clone())
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Method {
+    None,
+    Bearer,
+    Basic,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+struct CredentialsFormState {
+    method: Method,
+    token: String,
+    username: String,
+    password: String,
+    session_only: bool,
+}
+
+impl CredentialsFormState {
+    fn from_settings(settings: &AppSettings, server_url: &str) -> Self {
+        let mut state = CredentialsFormState {
+            method: Method::None,
+            token: String::new(),
+            username: String::new(),
+            password: String::new(),
+            session_only: settings.session_only,
+        };
+
+        match settings
+            .credentials
+            .get(server_url)
+            .cloned()
+            .unwrap_or_default()
+        {
+            Credentials::None => (),
+            Credentials::Bearer(token) => {
+                state.method = Method::Bearer;
+                state.token = token;
+            }
+            Credentials::Basic { username, password } => {
+                state.method = Method::Basic;
+                state.username = username;
+                state.password = password;
+            }
+        }
+
+        state
+    }
+
+    fn credentials(&self) -> Credentials {
+        match self.method {
+            Method::None => Credentials::None,
+            Method::Bearer => Credentials::Bearer(self.token.clone()),
+            Method::Basic => Credentials::Basic {
+                username: self.username.clone(),
+                password: self.password.clone(),
+            },
+        }
+    }
+}
+
+#[function_component(CredentialsForm)]
+pub fn credentials_form(props: &CredentialsFormProps) -> Html {
+    let state =
+        use_state(|| CredentialsFormState::from_settings(&props.current, &props.server_url()));
+
+    let input_value = |e: Event| {
+        e.target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value()
+    };
+
+    let state_cloned = state.clone();
+    let on_method_select = Callback::from(move |e: Event| {
+        let method = match input_value(e).as_str() {
+            "bearer" => Method::Bearer,
+            "basic" => Method::Basic,
+            _ => Method::None,
+        };
+        state_cloned.set(CredentialsFormState {
+            method,
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let on_token_edit = Callback::from(move |e: Event| {
+        state_cloned.set(CredentialsFormState {
+            token: input_value(e),
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let on_username_edit = Callback::from(move |e: Event| {
+        state_cloned.set(CredentialsFormState {
+            username: input_value(e),
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let on_password_edit = Callback::from(move |e: Event| {
+        state_cloned.set(CredentialsFormState {
+            password: input_value(e),
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let on_session_toggle = Callback::from(move |_| {
+        state_cloned.set(CredentialsFormState {
+            session_only: !state_cloned.session_only,
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let props_cloned = props.clone();
+    let on_save = Callback::from(move |_: MouseEvent| {
+        let server_url = props_cloned.server_url();
+        let mut settings = props_cloned.current.clone();
+        settings.session_only = state_cloned.session_only;
+        match state_cloned.credentials() {
+            Credentials::None => settings.credentials.remove(&server_url),
+            credentials => settings.credentials.insert(server_url, credentials),
+        };
+
+        props_cloned.update_settings.emit(settings);
+        props_cloned.on_saved.emit(());
+    });
+
+    let language = props.current.language;
+    let fields = match state.method {
+        Method::None => html! {},
+        Method::Bearer => html! {
+            <tr>
+                <td><label for="token">{language.t("auth.token")}</label></td>
+                <td>
+                    <input type="password"
+                        name="token"
+                        value={state.token.clone()}
+                        onchange={on_token_edit}
+                    />
+                </td>
+            </tr>
+        },
+        Method::Basic => html! {
+            <>
+                <tr>
+                    <td><label for="username">{language.t("auth.username")}</label></td>
+                    <td>
+                        <input type="text"
+                            name="username"
+                            value={state.username.clone()}
+                            onchange={on_username_edit}
+                        />
+                    </td>
+                </tr>
+                <tr>
+                    <td><label for="password">{language.t("auth.password")}</label></td>
+                    <td>
+                        <input type="password"
+                            name="password"
+                            value={state.password.clone()}
+                            onchange={on_password_edit}
+                        />
+                    </td>
+                </tr>
+            </>
+        },
+    };
+
+    html! {
+        <table class="items credentials">
+            <tr>
+                <td><label for="auth_method">{language.t("auth.method")}</label></td>
+                <td>
+                    <select name="auth_method" autocomplete="off" onchange={on_method_select}>
+                        <option selected={state.method == Method::None} value="none">
+                            {language.t("auth.none")}
+                        </option>
+                        <option selected={state.method == Method::Bearer} value="bearer">
+                            {language.t("auth.bearer")}
+                        </option>
+                        <option selected={state.method == Method::Basic} value="basic">
+                            {language.t("auth.basic")}
+                        </option>
+                    </select>
+                </td>
+            </tr>
+            {fields}
+            <tr>
+                <td>
+                    <input type="checkbox"
+                        name="session_only"
+                        checked={state.session_only}
+                        onclick={on_session_toggle}
+                    />
+                </td>
+                <td>
+                    <label for="session_only">{language.t("auth.session_only")}</label>
+                </td>
+            </tr>
+            <tr>
+                <td>
+                    <button name="credentials_submit" onclick={on_save}>{language.t("action.save")}</button>
+                </td>
+            </tr>
+        </table>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CredentialsPromptProps {
+    pub status: u16,
+    pub current: AppSettings,
+    pub update_settings: Callback<AppSettings>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(CredentialsPrompt)]
+pub fn credentials_prompt(props: &CredentialsPromptProps) -> Html {
+    let language = props.current.language;
+    let message = match props.status {
+        403 => language.t("auth.refused"),
+        _ => language.t("auth.required"),
+    };
+
+    let on_close = props.on_close.clone();
+    html! {
+        <div class="import-dialog">
+            <div class="import-dialog-content">
+                <h3>{props.current.server_url.as_str()}</h3>
+                <span>{message}</span>
+                <CredentialsForm
+                    current={props.current.clone()}
+                    update_settings={props.update_settings.clone()}
+                    on_saved={props.on_close.clone()}
+                />
+                <button onclick={Callback::from(move |_| on_close.emit(()))}>{language.t("action.cancel")}</button>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_payload_is_latin1() {
+        assert_eq!(basic_payload("user", "pass"), "user:pass");
+        // "é" is 0xC3 0xA9 in UTF-8
+        assert_eq!(basic_payload("josé", "pw"), "jos\u{c3}\u{a9}:pw");
+        assert!(basic_payload("用户", "пароль")
+            .chars()
+            .all(|c| (c as u32) < 0x100));
+    }
+}
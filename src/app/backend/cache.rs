@@ -0,0 +1,509 @@
+use crate::app::backend::{Backend, BackendFuture, Error};
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use ladle::models::{Ingredient, IngredientIndex, Label, LabelIndex, Recipe, RecipeIndex};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Milliseconds since the epoch
+#[cfg(not(test))]
+fn now() -> f64 {
+    js_sys::Date::now()
+}
+
+// The JavaScript clock is not available to native tests
+#[cfg(test)]
+fn now() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_millis() as f64)
+}
+
+type Response<T> = Shared<LocalBoxFuture<'static, Result<T, Error>>>;
+
+struct Entry<T> {
+    created: f64,
+    response: Response<T>,
+}
+
+// Responses of one endpoint, by argument. A pending request is stored as
+// well, so that concurrent callers share it instead of sending duplicates.
+struct Memo<T>(Rc<RefCell<HashMap<String, Entry<T>>>>);
+
+impl<T: Clone + 'static> Memo<T> {
+    fn new() -> Self {
+        Memo(Rc::new(RefCell::new(HashMap::new())))
+    }
+
+    fn get(
+        &self,
+        key: &str,
+        ttl_ms: u32,
+        fetch: impl FnOnce() -> LocalBoxFuture<'static, Result<T, Error>>,
+    ) -> LocalBoxFuture<'static, Result<T, Error>> {
+        let now = now();
+
+        let response = {
+            let mut entries = self.0.borrow_mut();
+            match entries.get(key) {
+                Some(entry) if now - entry.created < ttl_ms as f64 => entry.response.clone(),
+                _ => {
+                    let response = fetch().shared();
+                    entries.insert(
+                        key.to_string(),
+                        Entry {
+                            created: now,
+                            response: response.clone(),
+                        },
+                    );
+                    response
+                }
+            }
+        };
+
+        // Do not keep failures around
+        let entries = self.0.clone();
+        let key = key.to_string();
+        async move {
+            let result = response.await;
+            if result.is_err() {
+                let mut entries = entries.borrow_mut();
+                if entries
+                    .get(&key)
+                    .map_or(false, |entry| entry.created == now)
+                {
+                    entries.remove(&key);
+                }
+            }
+            result
+        }
+        .boxed_local()
+    }
+
+    fn invalidate(&self, key: &str) {
+        self.0.borrow_mut().remove(key);
+    }
+
+    fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+// Wrap a backend to reuse its answers for `ttl_ms`. Changes made through this
+// backend drop the answers they may have made outdated.
+pub struct CacheBackend<B: Backend> {
+    inner: Rc<B>,
+    ttl_ms: u32,
+    recipe_indexes: Memo<Vec<RecipeIndex>>,
+    recipes: Memo<Recipe>,
+    ingredient_indexes: Memo<Vec<IngredientIndex>>,
+    ingredients: Memo<Ingredient>,
+    label_indexes: Memo<Vec<LabelIndex>>,
+    labels: Memo<Label>,
+}
+
+impl<B: Backend + 'static> CacheBackend<B> {
+    pub fn new(inner: B, ttl_ms: u32) -> Self {
+        CacheBackend {
+            inner: Rc::new(inner),
+            ttl_ms,
+            recipe_indexes: Memo::new(),
+            recipes: Memo::new(),
+            ingredient_indexes: Memo::new(),
+            ingredients: Memo::new(),
+            label_indexes: Memo::new(),
+            labels: Memo::new(),
+        }
+    }
+
+    fn cached<'a, T: Clone + 'static>(
+        &'a self,
+        memo: &Memo<T>,
+        key: &str,
+        request: impl FnOnce(Rc<B>, String) -> LocalBoxFuture<'static, Result<T, Error>>,
+    ) -> BackendFuture<'a, T> {
+        let inner = self.inner.clone();
+        let argument = key.to_string();
+        memo.get(key, self.ttl_ms, move || request(inner, argument))
+    }
+
+    // Run a change, then drop what it affected
+    fn change<'a, T: 'a>(
+        &'a self,
+        request: BackendFuture<'a, T>,
+        invalidate: impl FnOnce(&Self) + 'a,
+    ) -> BackendFuture<'a, T> {
+        request.inspect(move |_| invalidate(self)).boxed_local()
+    }
+}
+
+impl<B: Backend + 'static> Backend for CacheBackend<B> {
+    fn location(&self) -> String {
+        self.inner.location()
+    }
+
+    fn refresh(&self) {
+        self.recipe_indexes.clear();
+        self.recipes.clear();
+        self.ingredient_indexes.clear();
+        self.ingredients.clear();
+        self.label_indexes.clear();
+        self.labels.clear();
+        self.inner.refresh();
+    }
+
+    fn recipe_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<RecipeIndex>> {
+        self.cached(&self.recipe_indexes, pattern, |inner, pattern| {
+            async move { inner.recipe_index(&pattern).await }.boxed_local()
+        })
+    }
+
+    fn recipe_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Recipe> {
+        self.cached(&self.recipes, id, |inner, id| {
+            async move { inner.recipe_get(&id).await }.boxed_local()
+        })
+    }
+
+    fn recipe_create<'a>(
+        &'a self,
+        name: &'a str,
+        author: &'a str,
+        directions: &'a str,
+        information: &'a str,
+    ) -> BackendFuture<'a, RecipeIndex> {
+        self.change(
+            self.inner
+                .recipe_create(name, author, directions, information),
+            |cache| cache.recipe_indexes.clear(),
+        )
+    }
+
+    fn recipe_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        author: Option<&'a str>,
+        directions: Option<&'a str>,
+        information: Option<&'a str>,
+    ) -> BackendFuture<'a, ()> {
+        self.change(
+            self.inner
+                .recipe_update(id, name, author, directions, information),
+            move |cache| match name {
+                // The name appears in dependents, labels and ingredients
+                Some(_) => cache.refresh(),
+                None => cache.recipes.invalidate(id),
+            },
+        )
+    }
+
+    fn recipe_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.change(self.inner.recipe_delete(id), |cache| cache.refresh())
+    }
+
+    fn recipe_tag<'a>(&'a self, id: &'a str, label_name: &'a str) -> BackendFuture<'a, ()> {
+        self.change(self.inner.recipe_tag(id, label_name), move |cache| {
+            cache.recipes.invalidate(id);
+            cache.label_indexes.clear();
+            cache.labels.clear();
+        })
+    }
+
+    fn recipe_untag<'a>(&'a self, id: &'a str, label_id: &'a str) -> BackendFuture<'a, ()> {
+        self.change(self.inner.recipe_untag(id, label_id), move |cache| {
+            cache.recipes.invalidate(id);
+            cache.label_indexes.clear();
+            cache.labels.invalidate(label_id);
+        })
+    }
+
+    fn ingredient_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<IngredientIndex>> {
+        self.cached(&self.ingredient_indexes, pattern, |inner, pattern| {
+            async move { inner.ingredient_index(&pattern).await }.boxed_local()
+        })
+    }
+
+    fn ingredient_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Ingredient> {
+        self.cached(&self.ingredients, id, |inner, id| {
+            async move { inner.ingredient_get(&id).await }.boxed_local()
+        })
+    }
+
+    fn ingredient_create<'a>(
+        &'a self,
+        name: &'a str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> BackendFuture<'a, IngredientIndex> {
+        self.change(
+            self.inner
+                .ingredient_create(name, dairy, meat, gluten, animal_product),
+            |cache| cache.ingredient_indexes.clear(),
+        )
+    }
+
+    fn ingredient_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> BackendFuture<'a, ()> {
+        self.change(
+            self.inner
+                .ingredient_update(id, name, dairy, meat, gluten, animal_product),
+            move |cache| {
+                cache.ingredients.invalidate(id);
+                cache.ingredient_indexes.clear();
+                // Recipes show the name and inherit the classifications
+                cache.recipes.clear();
+            },
+        )
+    }
+
+    fn ingredient_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.change(self.inner.ingredient_delete(id), move |cache| {
+            cache.ingredients.invalidate(id);
+            cache.ingredient_indexes.clear();
+        })
+    }
+
+    fn requirement_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.change(
+            self.inner
+                .requirement_create(recipe_id, ingredient_id, quantity, optional),
+            move |cache| {
+                // Classifications of dependents may change too
+                cache.recipes.clear();
+                cache.ingredients.invalidate(ingredient_id);
+            },
+        )
+    }
+
+    fn requirement_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.change(
+            self.inner.requirement_delete(recipe_id, ingredient_id),
+            move |cache| {
+                cache.recipes.clear();
+                cache.ingredients.invalidate(ingredient_id);
+            },
+        )
+    }
+
+    fn dependency_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.change(
+            self.inner
+                .dependency_create(recipe_id, required_id, quantity, optional),
+            |cache| cache.recipes.clear(),
+        )
+    }
+
+    fn dependency_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.change(
+            self.inner.dependency_delete(recipe_id, required_id),
+            |cache| cache.recipes.clear(),
+        )
+    }
+
+    fn label_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<LabelIndex>> {
+        self.cached(&self.label_indexes, pattern, |inner, pattern| {
+            async move { inner.label_index(&pattern).await }.boxed_local()
+        })
+    }
+
+    fn label_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Label> {
+        self.cached(&self.labels, id, |inner, id| {
+            async move { inner.label_get(&id).await }.boxed_local()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backend::memory::MemoryBackend;
+    use futures::executor::block_on;
+
+    // Cache over a memory backend holding a recipe made with one ingredient,
+    // and the log of the requests reaching the memory backend
+    fn setup() -> (
+        CacheBackend<MemoryBackend>,
+        Rc<RefCell<Vec<String>>>,
+        String,
+        String,
+    ) {
+        let memory = MemoryBackend::default();
+        let requests = memory.requests();
+        let cache = CacheBackend::new(memory, 60_000);
+
+        let recipe = block_on(cache.recipe_create("Crêpes", "", "", "")).unwrap();
+        let ingredient =
+            block_on(cache.ingredient_create("Milk", true, false, false, true)).unwrap();
+        block_on(cache.requirement_create(&recipe.id, &ingredient.id, "1 l", false)).unwrap();
+        requests.borrow_mut().clear();
+
+        (cache, requests, recipe.id, ingredient.id)
+    }
+
+    fn count(requests: &Rc<RefCell<Vec<String>>>, request: &str) -> usize {
+        requests.borrow().iter().filter(|r| *r == request).count()
+    }
+
+    #[test]
+    fn answers_are_reused() {
+        let (cache, requests, recipe, _) = setup();
+        block_on(cache.recipe_get(&recipe)).unwrap();
+        block_on(cache.recipe_get(&recipe)).unwrap();
+        block_on(cache.recipe_index("")).unwrap();
+        block_on(cache.recipe_index("")).unwrap();
+
+        assert_eq!(count(&requests, &format!("recipe_get {}", recipe)), 1);
+        assert_eq!(count(&requests, "recipe_index "), 1);
+    }
+
+    #[test]
+    fn failures_are_not_kept() {
+        let (cache, requests, _, _) = setup();
+        assert!(block_on(cache.recipe_get("missing")).is_err());
+        assert!(block_on(cache.recipe_get("missing")).is_err());
+
+        assert_eq!(count(&requests, "recipe_get missing"), 2);
+    }
+
+    #[test]
+    fn creating_a_recipe_drops_the_indexes() {
+        let (cache, _, _, _) = setup();
+        assert_eq!(block_on(cache.recipe_index("")).unwrap().len(), 1);
+        block_on(cache.recipe_create("Gaufres", "", "", "")).unwrap();
+
+        assert_eq!(block_on(cache.recipe_index("")).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn renaming_a_recipe_drops_everything() {
+        let (cache, _, recipe, ingredient) = setup();
+        block_on(cache.ingredient_get(&ingredient)).unwrap();
+        block_on(cache.recipe_update(&recipe, Some("Galettes"), None, None, None)).unwrap();
+
+        let ingredient = block_on(cache.ingredient_get(&ingredient)).unwrap();
+        let names: Vec<_> = ingredient.used_in.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Galettes"]);
+    }
+
+    #[test]
+    fn updating_a_recipe_keeps_the_other_answers() {
+        let (cache, requests, recipe, ingredient) = setup();
+        block_on(cache.recipe_get(&recipe)).unwrap();
+        block_on(cache.ingredient_get(&ingredient)).unwrap();
+        block_on(cache.recipe_update(&recipe, None, Some("Me"), None, None)).unwrap();
+
+        assert_eq!(block_on(cache.recipe_get(&recipe)).unwrap().author, "Me");
+        block_on(cache.ingredient_get(&ingredient)).unwrap();
+        assert_eq!(
+            count(&requests, &format!("ingredient_get {}", ingredient)),
+            1
+        );
+    }
+
+    #[test]
+    fn updating_an_ingredient_drops_the_recipes() {
+        let (cache, _, recipe, ingredient) = setup();
+        assert!(
+            block_on(cache.recipe_get(&recipe))
+                .unwrap()
+                .classifications
+                .dairy
+        );
+        block_on(cache.ingredient_update(&ingredient, None, Some(false), None, None, None))
+            .unwrap();
+
+        assert!(
+            !block_on(cache.recipe_get(&recipe))
+                .unwrap()
+                .classifications
+                .dairy
+        );
+    }
+
+    #[test]
+    fn requirements_drop_the_dependents() {
+        let (cache, _, recipe, _) = setup();
+        let dependent = block_on(cache.recipe_create("Crêpes party", "", "", "")).unwrap();
+        block_on(cache.dependency_create(&dependent.id, &recipe, "", false)).unwrap();
+        let meat = block_on(cache.ingredient_create("Ham", false, true, false, true)).unwrap();
+
+        assert!(
+            !block_on(cache.recipe_get(&dependent.id))
+                .unwrap()
+                .classifications
+                .meat
+        );
+        block_on(cache.requirement_create(&recipe, &meat.id, "1 slice", false)).unwrap();
+        assert!(
+            block_on(cache.recipe_get(&dependent.id))
+                .unwrap()
+                .classifications
+                .meat
+        );
+    }
+
+    #[test]
+    fn tagging_drops_the_labels() {
+        let (cache, _, recipe, _) = setup();
+        assert!(block_on(cache.label_index("")).unwrap().is_empty());
+        block_on(cache.recipe_tag(&recipe, "Breakfast")).unwrap();
+
+        let labels = block_on(cache.label_index("")).unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(
+            block_on(cache.label_get(&labels[0].id))
+                .unwrap()
+                .tagged_recipes
+                .len(),
+            1
+        );
+        assert_eq!(block_on(cache.recipe_get(&recipe)).unwrap().tags.len(), 1);
+
+        block_on(cache.recipe_untag(&recipe, &labels[0].id)).unwrap();
+        assert!(block_on(cache.label_get(&labels[0].id))
+            .unwrap()
+            .tagged_recipes
+            .is_empty());
+        assert!(block_on(cache.recipe_get(&recipe)).unwrap().tags.is_empty());
+    }
+
+    #[test]
+    fn refresh_drops_everything() {
+        let (cache, requests, recipe, _) = setup();
+        block_on(cache.recipe_get(&recipe)).unwrap();
+        cache.refresh();
+        block_on(cache.recipe_get(&recipe)).unwrap();
+
+        assert_eq!(count(&requests, &format!("recipe_get {}", recipe)), 2);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::app::backend::{Backend, BackendFuture, Error};
+use futures::future::FutureExt;
+use ladle::models::{Ingredient, IngredientIndex, Label, LabelIndex, Recipe, RecipeIndex};
+
+// Backend talking to a knife server over HTTP using ladle
+pub struct HttpBackend {
+    server_url: String,
+}
+
+impl HttpBackend {
+    pub fn new(server_url: &str) -> Self {
+        HttpBackend {
+            server_url: server_url.to_string(),
+        }
+    }
+}
+
+// Status code of a failed response, only read right after "status" or
+// "HTTP" as in "HTTP status client error (404 Not Found)", so that ids and
+// ports elsewhere in the text are not taken for one
+fn status(lowercase: &str) -> Option<u16> {
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| matches!(**word, "status" | "http"))
+        .find_map(|(index, _)| {
+            words[index + 1..]
+                .iter()
+                .find(|word| !matches!(**word, "status" | "client" | "server" | "error" | "code"))
+                .and_then(|word| word.parse::<u16>().ok())
+        })
+        .filter(|code| (400..600).contains(code))
+}
+
+// ladle only reports errors as text, sort them from their wording
+fn error(e: impl ToString) -> Error {
+    let message = e.to_string();
+    let lowercase = message.to_lowercase();
+    let status = status(&lowercase);
+
+    if status == Some(404) || lowercase.contains("not found") {
+        Error::NotFound(message)
+    } else if matches!(status, Some(401 | 403)) || lowercase.contains("unauthorized") {
+        Error::Unauthorized(message)
+    } else if matches!(status, Some(500..=599)) || lowercase.contains("internal server error") {
+        Error::Server(message)
+    } else if ["fetch", "network", "connect", "sending request"]
+        .iter()
+        .any(|hint| lowercase.contains(hint))
+    {
+        Error::Network(message)
+    } else {
+        Error::Invalid(message)
+    }
+}
+
+impl Backend for HttpBackend {
+    fn location(&self) -> String {
+        self.server_url.clone()
+    }
+
+    fn recipe_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<RecipeIndex>> {
+        ladle::recipe_index(&self.server_url, pattern)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn recipe_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Recipe> {
+        ladle::recipe_get(&self.server_url, id)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn recipe_create<'a>(
+        &'a self,
+        name: &'a str,
+        author: &'a str,
+        directions: &'a str,
+        information: &'a str,
+    ) -> BackendFuture<'a, RecipeIndex> {
+        ladle::recipe_create(&self.server_url, name, author, directions, information)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn recipe_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        author: Option<&'a str>,
+        directions: Option<&'a str>,
+        information: Option<&'a str>,
+    ) -> BackendFuture<'a, ()> {
+        ladle::recipe_update(&self.server_url, id, name, author, directions, information)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn recipe_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        ladle::recipe_delete(&self.server_url, id)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn recipe_tag<'a>(&'a self, id: &'a str, label_name: &'a str) -> BackendFuture<'a, ()> {
+        ladle::recipe_tag(&self.server_url, id, label_name)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn recipe_untag<'a>(&'a self, id: &'a str, label_id: &'a str) -> BackendFuture<'a, ()> {
+        ladle::recipe_untag(&self.server_url, id, label_id)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn ingredient_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<IngredientIndex>> {
+        ladle::ingredient_index(&self.server_url, pattern)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn ingredient_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Ingredient> {
+        ladle::ingredient_get(&self.server_url, id)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn ingredient_create<'a>(
+        &'a self,
+        name: &'a str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> BackendFuture<'a, IngredientIndex> {
+        ladle::ingredient_create(&self.server_url, name, dairy, meat, gluten, animal_product)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn ingredient_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> BackendFuture<'a, ()> {
+        ladle::ingredient_update(
+            &self.server_url,
+            id,
+            name,
+            dairy,
+            meat,
+            gluten,
+            animal_product,
+        )
+        .map(|r| r.map(drop).map_err(error))
+        .boxed_local()
+    }
+
+    fn ingredient_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        ladle::ingredient_delete(&self.server_url, id)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn requirement_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        ladle::requirement_create(
+            &self.server_url,
+            recipe_id,
+            ingredient_id,
+            quantity,
+            optional,
+        )
+        .map(|r| r.map(drop).map_err(error))
+        .boxed_local()
+    }
+
+    fn requirement_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        ladle::requirement_delete(&self.server_url, recipe_id, ingredient_id)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn dependency_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        ladle::dependency_create(&self.server_url, recipe_id, required_id, quantity, optional)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn dependency_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        ladle::dependency_delete(&self.server_url, recipe_id, required_id)
+            .map(|r| r.map(drop).map_err(error))
+            .boxed_local()
+    }
+
+    fn label_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<LabelIndex>> {
+        ladle::label_index(&self.server_url, pattern)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+
+    fn label_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Label> {
+        ladle::label_get(&self.server_url, id)
+            .map(|r| r.map_err(error))
+            .boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_pick_the_error() {
+        let not_found =
+            "HTTP status client error (404 Not Found) for url (http://knife/recipes/12)";
+        assert_eq!(error(not_found), Error::NotFound(not_found.to_string()));
+
+        let unauthorized =
+            "HTTP status client error (401 Unauthorized) for url (http://knife/recipes)";
+        assert_eq!(
+            error(unauthorized),
+            Error::Unauthorized(unauthorized.to_string())
+        );
+
+        let forbidden = "HTTP status client error (403 Forbidden) for url (http://knife/recipes)";
+        assert_eq!(error(forbidden), Error::Unauthorized(forbidden.to_string()));
+
+        let server = "HTTP status server error (502 Bad Gateway) for url (http://knife/recipes)";
+        assert_eq!(error(server), Error::Server(server.to_string()));
+    }
+
+    #[test]
+    fn first_status_code_wins() {
+        let message = "HTTP status server error (500 Internal Server Error) for url (https://knife:443/labels)";
+        assert_eq!(error(message), Error::Server(message.to_string()));
+    }
+
+    #[test]
+    fn numbers_outside_status_codes_are_ignored() {
+        let message = "error sending request for url (http://knife:8080/recipes/1234)";
+        assert_eq!(error(message), Error::Network(message.to_string()));
+    }
+
+    #[test]
+    fn numbers_in_messages_are_not_status_codes() {
+        let message = "Recipe 404 is used by recipe 500";
+        assert_eq!(error(message), Error::Invalid(message.to_string()));
+
+        let message = "error sending request for url (http://knife:503/recipes)";
+        assert_eq!(error(message), Error::Network(message.to_string()));
+
+        assert_eq!(status("http 503 service unavailable"), Some(503));
+        assert_eq!(status("status code 401"), Some(401));
+    }
+
+    #[test]
+    fn wording_without_status_code() {
+        assert_eq!(
+            error("Failed to fetch"),
+            Error::Network(String::from("Failed to fetch"))
+        );
+        assert_eq!(
+            error("Recipe not found"),
+            Error::NotFound(String::from("Recipe not found"))
+        );
+        assert_eq!(
+            error("Name already taken"),
+            Error::Invalid(String::from("Name already taken"))
+        );
+    }
+}
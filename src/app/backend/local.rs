@@ -0,0 +1,908 @@
+use crate::app::backend::{Backend, BackendFuture, Error};
+use futures::future::FutureExt;
+use ladle::models::{Ingredient, IngredientIndex, Label, LabelIndex, Recipe, RecipeIndex};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+static DATABASE: &str = "spoon";
+static OBJECT_STORE: &str = "data";
+static KEY: &str = "store";
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct StoredIngredient {
+    name: String,
+    dairy: bool,
+    meat: bool,
+    gluten: bool,
+    animal_product: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct StoredLink {
+    quantity: String,
+    optional: bool,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct StoredRecipe {
+    name: String,
+    author: String,
+    directions: String,
+    information: String,
+    // Ingredient id -> requirement
+    requirements: BTreeMap<String, StoredLink>,
+    // Recipe id -> dependency
+    dependencies: BTreeMap<String, StoredLink>,
+    // Label ids
+    tags: BTreeSet<String>,
+}
+
+// Every object of the local database, mirroring what a knife server stores
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct LocalStore {
+    next_id: u64,
+    ingredients: BTreeMap<String, StoredIngredient>,
+    recipes: BTreeMap<String, StoredRecipe>,
+    // Label id -> name
+    labels: BTreeMap<String, String>,
+}
+
+fn not_found(kind: &str, id: &str) -> Error {
+    Error::NotFound(format!("No {} with id {}", kind, id))
+}
+
+fn matches(name: &str, pattern: &str) -> bool {
+    name.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+// Build a ladle model from its JSON representation
+fn model<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    serde_json::from_value(value).map_err(|e| Error::Invalid(e.to_string()))
+}
+
+impl LocalStore {
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        format!("local-{}", self.next_id)
+    }
+
+    fn ingredient(&self, id: &str) -> Result<&StoredIngredient, Error> {
+        self.ingredients
+            .get(id)
+            .ok_or_else(|| not_found("ingredient", id))
+    }
+
+    fn recipe(&self, id: &str) -> Result<&StoredRecipe, Error> {
+        self.recipes.get(id).ok_or_else(|| not_found("recipe", id))
+    }
+
+    fn recipe_mut(&mut self, id: &str) -> Result<&mut StoredRecipe, Error> {
+        self.recipes
+            .get_mut(id)
+            .ok_or_else(|| not_found("recipe", id))
+    }
+
+    fn ingredient_json(&self, id: &str) -> Value {
+        let name = self.ingredients.get(id).map(|i| i.name.as_str());
+        json!({"id": id, "name": name.unwrap_or_default()})
+    }
+
+    fn recipe_json(&self, id: &str) -> Value {
+        let name = self.recipes.get(id).map(|r| r.name.as_str());
+        json!({"id": id, "name": name.unwrap_or_default()})
+    }
+
+    fn label_json(&self, id: &str) -> Value {
+        let name = self.labels.get(id).map(String::as_str);
+        json!({"id": id, "name": name.unwrap_or_default()})
+    }
+
+    // Classifications of a recipe, from its ingredients and the ones of its
+    // dependencies
+    fn classifications(&self, id: &str, visited: &mut HashSet<String>) -> [bool; 4] {
+        let mut out = [false; 4];
+        if !visited.insert(id.to_string()) {
+            return out;
+        }
+
+        if let Some(recipe) = self.recipes.get(id) {
+            for ingredient in recipe
+                .requirements
+                .keys()
+                .filter_map(|id| self.ingredients.get(id))
+            {
+                out[0] |= ingredient.dairy;
+                out[1] |= ingredient.meat;
+                out[2] |= ingredient.gluten;
+                out[3] |= ingredient.animal_product;
+            }
+
+            for dependency in recipe.dependencies.keys() {
+                let nested = self.classifications(dependency, visited);
+                out.iter_mut()
+                    .zip(nested)
+                    .for_each(|(lhs, rhs)| *lhs |= rhs);
+            }
+        }
+
+        out
+    }
+
+    // Whether `id` can be reached from `from` through dependencies
+    fn depends_on(&self, from: &str, id: &str) -> bool {
+        let mut stack = vec![from.to_string()];
+        let mut visited = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if current == id {
+                return true;
+            }
+            if visited.insert(current.clone()) {
+                if let Some(recipe) = self.recipes.get(&current) {
+                    stack.extend(recipe.dependencies.keys().cloned());
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn recipe_index(&self, pattern: &str) -> Result<Vec<RecipeIndex>, Error> {
+        self.recipes
+            .iter()
+            .filter(|(_, recipe)| matches(&recipe.name, pattern))
+            .map(|(id, _)| model(self.recipe_json(id)))
+            .collect()
+    }
+
+    pub fn recipe_get(&self, id: &str) -> Result<Recipe, Error> {
+        let recipe = self.recipe(id)?;
+        let [dairy, meat, gluten, animal_product] = self.classifications(id, &mut HashSet::new());
+
+        let requirements: Vec<Value> = recipe
+            .requirements
+            .iter()
+            .map(|(ingredient, link)| {
+                json!({
+                    "ingredient": self.ingredient_json(ingredient),
+                    "quantity": link.quantity,
+                    "optional": link.optional,
+                })
+            })
+            .collect();
+
+        let dependencies: Vec<Value> = recipe
+            .dependencies
+            .iter()
+            .map(|(required, link)| {
+                json!({
+                    "recipe": self.recipe_json(required),
+                    "quantity": link.quantity,
+                    "optional": link.optional,
+                })
+            })
+            .collect();
+
+        let tags: Vec<Value> = recipe.tags.iter().map(|id| self.label_json(id)).collect();
+
+        model(json!({
+            "id": id,
+            "name": recipe.name,
+            "author": recipe.author,
+            "directions": recipe.directions,
+            "information": recipe.information,
+            "requirements": requirements,
+            "dependencies": dependencies,
+            "tags": tags,
+            "classifications": {
+                "dairy": dairy,
+                "meat": meat,
+                "gluten": gluten,
+                "animal_product": animal_product,
+            },
+        }))
+    }
+
+    pub fn recipe_create(
+        &mut self,
+        name: &str,
+        author: &str,
+        directions: &str,
+        information: &str,
+    ) -> Result<RecipeIndex, Error> {
+        if self.recipes.values().any(|recipe| recipe.name == name) {
+            return Err(Error::Invalid(format!(
+                "A recipe named {} already exists",
+                name
+            )));
+        }
+
+        let id = self.new_id();
+        self.recipes.insert(
+            id.clone(),
+            StoredRecipe {
+                name: name.to_string(),
+                author: author.to_string(),
+                directions: directions.to_string(),
+                information: information.to_string(),
+                ..StoredRecipe::default()
+            },
+        );
+
+        model(self.recipe_json(&id))
+    }
+
+    pub fn recipe_update(
+        &mut self,
+        id: &str,
+        name: Option<&str>,
+        author: Option<&str>,
+        directions: Option<&str>,
+        information: Option<&str>,
+    ) -> Result<(), Error> {
+        if let Some(name) = name {
+            if self
+                .recipes
+                .iter()
+                .any(|(other, recipe)| other != id && recipe.name == name)
+            {
+                return Err(Error::Invalid(format!(
+                    "A recipe named {} already exists",
+                    name
+                )));
+            }
+        }
+
+        let recipe = self.recipe_mut(id)?;
+        if let Some(name) = name {
+            recipe.name = name.to_string();
+        }
+        if let Some(author) = author {
+            recipe.author = author.to_string();
+        }
+        if let Some(directions) = directions {
+            recipe.directions = directions.to_string();
+        }
+        if let Some(information) = information {
+            recipe.information = information.to_string();
+        }
+
+        Ok(())
+    }
+
+    pub fn recipe_delete(&mut self, id: &str) -> Result<(), Error> {
+        self.recipes
+            .remove(id)
+            .ok_or_else(|| not_found("recipe", id))?;
+
+        for recipe in self.recipes.values_mut() {
+            recipe.dependencies.remove(id);
+        }
+
+        Ok(())
+    }
+
+    pub fn recipe_tag(&mut self, id: &str, label_name: &str) -> Result<(), Error> {
+        self.recipe(id)?;
+
+        let label = match self.labels.iter().find(|(_, name)| *name == label_name) {
+            Some((label, _)) => label.clone(),
+            None => {
+                let label = self.new_id();
+                self.labels.insert(label.clone(), label_name.to_string());
+                label
+            }
+        };
+
+        self.recipe_mut(id)?.tags.insert(label);
+        Ok(())
+    }
+
+    pub fn recipe_untag(&mut self, id: &str, label_id: &str) -> Result<(), Error> {
+        match self.recipe_mut(id)?.tags.remove(label_id) {
+            true => Ok(()),
+            false => Err(not_found("tag", label_id)),
+        }
+    }
+
+    pub fn ingredient_index(&self, pattern: &str) -> Result<Vec<IngredientIndex>, Error> {
+        self.ingredients
+            .iter()
+            .filter(|(_, ingredient)| matches(&ingredient.name, pattern))
+            .map(|(id, _)| model(self.ingredient_json(id)))
+            .collect()
+    }
+
+    pub fn ingredient_get(&self, id: &str) -> Result<Ingredient, Error> {
+        let ingredient = self.ingredient(id)?;
+
+        let used_in: Vec<Value> = self
+            .recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.requirements.contains_key(id))
+            .map(|(recipe, _)| self.recipe_json(recipe))
+            .collect();
+
+        model(json!({
+            "id": id,
+            "name": ingredient.name,
+            "classifications": {
+                "dairy": ingredient.dairy,
+                "meat": ingredient.meat,
+                "gluten": ingredient.gluten,
+                "animal_product": ingredient.animal_product,
+            },
+            "used_in": used_in,
+        }))
+    }
+
+    pub fn ingredient_create(
+        &mut self,
+        name: &str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> Result<IngredientIndex, Error> {
+        if self.ingredients.values().any(|i| i.name == name) {
+            return Err(Error::Invalid(format!(
+                "An ingredient named {} already exists",
+                name
+            )));
+        }
+
+        let id = self.new_id();
+        self.ingredients.insert(
+            id.clone(),
+            StoredIngredient {
+                name: name.to_string(),
+                dairy,
+                meat,
+                gluten,
+                animal_product,
+            },
+        );
+
+        model(self.ingredient_json(&id))
+    }
+
+    pub fn ingredient_update(
+        &mut self,
+        id: &str,
+        name: Option<&str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> Result<(), Error> {
+        if let Some(name) = name {
+            if self
+                .ingredients
+                .iter()
+                .any(|(other, ingredient)| other != id && ingredient.name == name)
+            {
+                return Err(Error::Invalid(format!(
+                    "An ingredient named {} already exists",
+                    name
+                )));
+            }
+        }
+
+        let ingredient = self
+            .ingredients
+            .get_mut(id)
+            .ok_or_else(|| not_found("ingredient", id))?;
+        if let Some(name) = name {
+            ingredient.name = name.to_string();
+        }
+        ingredient.dairy = dairy.unwrap_or(ingredient.dairy);
+        ingredient.meat = meat.unwrap_or(ingredient.meat);
+        ingredient.gluten = gluten.unwrap_or(ingredient.gluten);
+        ingredient.animal_product = animal_product.unwrap_or(ingredient.animal_product);
+
+        Ok(())
+    }
+
+    pub fn ingredient_delete(&mut self, id: &str) -> Result<(), Error> {
+        self.ingredient(id)?;
+
+        if self
+            .recipes
+            .values()
+            .any(|recipe| recipe.requirements.contains_key(id))
+        {
+            return Err(Error::Invalid(String::from(
+                "This ingredient is used in recipes and cannot be deleted",
+            )));
+        }
+
+        self.ingredients.remove(id);
+        Ok(())
+    }
+
+    pub fn requirement_create(
+        &mut self,
+        recipe_id: &str,
+        ingredient_id: &str,
+        quantity: &str,
+        optional: bool,
+    ) -> Result<(), Error> {
+        self.ingredient(ingredient_id)?;
+
+        let recipe = self.recipe_mut(recipe_id)?;
+        if recipe.requirements.contains_key(ingredient_id) {
+            return Err(Error::Invalid(String::from(
+                "This ingredient is already required",
+            )));
+        }
+
+        recipe.requirements.insert(
+            ingredient_id.to_string(),
+            StoredLink {
+                quantity: quantity.to_string(),
+                optional,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn requirement_delete(
+        &mut self,
+        recipe_id: &str,
+        ingredient_id: &str,
+    ) -> Result<(), Error> {
+        match self
+            .recipe_mut(recipe_id)?
+            .requirements
+            .remove(ingredient_id)
+        {
+            Some(_) => Ok(()),
+            None => Err(not_found("requirement", ingredient_id)),
+        }
+    }
+
+    pub fn dependency_create(
+        &mut self,
+        recipe_id: &str,
+        required_id: &str,
+        quantity: &str,
+        optional: bool,
+    ) -> Result<(), Error> {
+        self.recipe(required_id)?;
+
+        if self.depends_on(required_id, recipe_id) {
+            return Err(Error::Invalid(String::from(
+                "This dependency would create a cycle between recipes",
+            )));
+        }
+
+        let recipe = self.recipe_mut(recipe_id)?;
+        if recipe.dependencies.contains_key(required_id) {
+            return Err(Error::Invalid(String::from(
+                "This recipe is already required",
+            )));
+        }
+
+        recipe.dependencies.insert(
+            required_id.to_string(),
+            StoredLink {
+                quantity: quantity.to_string(),
+                optional,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn dependency_delete(&mut self, recipe_id: &str, required_id: &str) -> Result<(), Error> {
+        match self.recipe_mut(recipe_id)?.dependencies.remove(required_id) {
+            Some(_) => Ok(()),
+            None => Err(not_found("dependency", required_id)),
+        }
+    }
+
+    pub fn label_index(&self, pattern: &str) -> Result<Vec<LabelIndex>, Error> {
+        self.labels
+            .iter()
+            .filter(|(_, name)| matches(name, pattern))
+            .map(|(id, _)| model(self.label_json(id)))
+            .collect()
+    }
+
+    pub fn label_get(&self, id: &str) -> Result<Label, Error> {
+        let name = self.labels.get(id).ok_or_else(|| not_found("label", id))?;
+
+        let tagged_recipes: Vec<Value> = self
+            .recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.tags.contains(id))
+            .map(|(recipe, _)| self.recipe_json(recipe))
+            .collect();
+
+        model(json!({
+            "id": id,
+            "name": name,
+            "tagged_recipes": tagged_recipes,
+        }))
+    }
+}
+
+fn js_error(value: JsValue) -> Error {
+    Error::Invalid(
+        value
+            .as_string()
+            .unwrap_or(String::from("IndexedDB request failed")),
+    )
+}
+
+// Wait for an IndexedDB request to complete and return its result
+async fn complete(request: &IdbRequest) -> Result<JsValue, Error> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await.map_err(js_error)?;
+    request.result().map_err(js_error)
+}
+
+async fn open_database() -> Result<IdbDatabase, Error> {
+    let factory = web_sys::window()
+        .and_then(|window| window.indexed_db().ok().flatten())
+        .ok_or(Error::Invalid(String::from("IndexedDB is not available")))?;
+    let request: IdbOpenDbRequest = factory.open_with_u32(DATABASE, 1).map_err(js_error)?;
+
+    let upgrade = Closure::once_into_js(move |event: web_sys::Event| {
+        let database = event
+            .target()
+            .and_then(|target| target.unchecked_into::<IdbRequest>().result().ok())
+            .map(|result| result.unchecked_into::<IdbDatabase>());
+        if let Some(database) = database {
+            if let Err(e) = database.create_object_store(OBJECT_STORE) {
+                log::error!("{:?}", e);
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(upgrade.unchecked_ref()));
+
+    Ok(complete(&request).await?.unchecked_into::<IdbDatabase>())
+}
+
+async fn load(database: &IdbDatabase) -> Result<LocalStore, Error> {
+    let store = database
+        .transaction_with_str(OBJECT_STORE)
+        .and_then(|transaction| transaction.object_store(OBJECT_STORE))
+        .map_err(js_error)?;
+
+    let value = complete(&store.get(&JsValue::from_str(KEY)).map_err(js_error)?).await?;
+    match value.as_string() {
+        Some(contents) => {
+            serde_json::from_str(&contents).map_err(|e| Error::Invalid(e.to_string()))
+        }
+        None => Ok(LocalStore::default()),
+    }
+}
+
+async fn save(database: &IdbDatabase, contents: String) -> Result<(), Error> {
+    let store = database
+        .transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)
+        .and_then(|transaction| transaction.object_store(OBJECT_STORE))
+        .map_err(js_error)?;
+
+    let request = store
+        .put_with_key(&JsValue::from_str(&contents), &JsValue::from_str(KEY))
+        .map_err(js_error)?;
+    complete(&request).await.map(drop)
+}
+
+// Backend keeping everything in the browser's IndexedDB. The database is read
+// once, then kept in memory and written back after every change.
+#[derive(Default)]
+pub struct LocalBackend {
+    store: RefCell<Option<LocalStore>>,
+    // Connection opened on first use and kept for the following requests
+    database: RefCell<Option<IdbDatabase>>,
+}
+
+impl LocalBackend {
+    async fn database(&self) -> Result<IdbDatabase, Error> {
+        if let Some(database) = self.database.borrow().as_ref() {
+            return Ok(database.clone());
+        }
+        let database = open_database().await?;
+        Ok(self.database.borrow_mut().get_or_insert(database).clone())
+    }
+
+    async fn ensure_loaded(&self) -> Result<(), Error> {
+        if self.store.borrow().is_none() {
+            let loaded = load(&self.database().await?).await?;
+            // Another request may have loaded it in the meantime
+            self.store.borrow_mut().get_or_insert(loaded);
+        }
+        Ok(())
+    }
+
+    fn read<'a, T: 'a>(
+        &'a self,
+        operation: impl FnOnce(&LocalStore) -> Result<T, Error> + 'a,
+    ) -> BackendFuture<'a, T> {
+        async move {
+            self.ensure_loaded().await?;
+            let store = self.store.borrow();
+            operation(store.as_ref().unwrap())
+        }
+        .boxed_local()
+    }
+
+    fn write<'a, T: 'a>(
+        &'a self,
+        operation: impl FnOnce(&mut LocalStore) -> Result<T, Error> + 'a,
+    ) -> BackendFuture<'a, T> {
+        async move {
+            self.ensure_loaded().await?;
+            let database = self.database().await?;
+
+            // Change a copy, kept only once it is on disk so that a failed
+            // write leaves memory as the database is
+            let mut store = self.store.borrow().clone().unwrap();
+            let result = operation(&mut store)?;
+            let contents =
+                serde_json::to_string(&store).map_err(|e| Error::Invalid(e.to_string()))?;
+            save(&database, contents).await?;
+
+            *self.store.borrow_mut() = Some(store);
+            Ok(result)
+        }
+        .boxed_local()
+    }
+}
+
+impl Backend for LocalBackend {
+    fn location(&self) -> String {
+        String::from("browser storage")
+    }
+
+    fn recipe_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<RecipeIndex>> {
+        self.read(move |store| store.recipe_index(pattern))
+    }
+
+    fn recipe_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Recipe> {
+        self.read(move |store| store.recipe_get(id))
+    }
+
+    fn recipe_create<'a>(
+        &'a self,
+        name: &'a str,
+        author: &'a str,
+        directions: &'a str,
+        information: &'a str,
+    ) -> BackendFuture<'a, RecipeIndex> {
+        self.write(move |store| store.recipe_create(name, author, directions, information))
+    }
+
+    fn recipe_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        author: Option<&'a str>,
+        directions: Option<&'a str>,
+        information: Option<&'a str>,
+    ) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.recipe_update(id, name, author, directions, information))
+    }
+
+    fn recipe_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.recipe_delete(id))
+    }
+
+    fn recipe_tag<'a>(&'a self, id: &'a str, label_name: &'a str) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.recipe_tag(id, label_name))
+    }
+
+    fn recipe_untag<'a>(&'a self, id: &'a str, label_id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.recipe_untag(id, label_id))
+    }
+
+    fn ingredient_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<IngredientIndex>> {
+        self.read(move |store| store.ingredient_index(pattern))
+    }
+
+    fn ingredient_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Ingredient> {
+        self.read(move |store| store.ingredient_get(id))
+    }
+
+    fn ingredient_create<'a>(
+        &'a self,
+        name: &'a str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> BackendFuture<'a, IngredientIndex> {
+        self.write(move |store| store.ingredient_create(name, dairy, meat, gluten, animal_product))
+    }
+
+    fn ingredient_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> BackendFuture<'a, ()> {
+        self.write(move |store| {
+            store.ingredient_update(id, name, dairy, meat, gluten, animal_product)
+        })
+    }
+
+    fn ingredient_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.ingredient_delete(id))
+    }
+
+    fn requirement_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.write(move |store| {
+            store.requirement_create(recipe_id, ingredient_id, quantity, optional)
+        })
+    }
+
+    fn requirement_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.requirement_delete(recipe_id, ingredient_id))
+    }
+
+    fn dependency_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.dependency_create(recipe_id, required_id, quantity, optional))
+    }
+
+    fn dependency_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.write(move |store| store.dependency_delete(recipe_id, required_id))
+    }
+
+    fn label_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<LabelIndex>> {
+        self.read(move |store| store.label_index(pattern))
+    }
+
+    fn label_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Label> {
+        self.read(move |store| store.label_get(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe(store: &mut LocalStore, name: &str) -> String {
+        store.recipe_create(name, "", "", "").unwrap().id
+    }
+
+    fn classifications(store: &LocalStore, id: &str) -> [bool; 4] {
+        let recipe = store.recipe_get(id).unwrap();
+        let c = recipe.classifications;
+        [c.dairy, c.meat, c.gluten, c.animal_product]
+    }
+
+    #[test]
+    fn dependencies_cannot_form_a_cycle() {
+        let mut store = LocalStore::default();
+        let dough = recipe(&mut store, "Dough");
+        let pie = recipe(&mut store, "Pie");
+        let dinner = recipe(&mut store, "Dinner");
+        store.dependency_create(&pie, &dough, "", false).unwrap();
+        store.dependency_create(&dinner, &pie, "", false).unwrap();
+
+        assert!(store.dependency_create(&dough, &dough, "", false).is_err());
+        assert!(store.dependency_create(&dough, &pie, "", false).is_err());
+        assert!(store.dependency_create(&dough, &dinner, "", false).is_err());
+        // Sharing a dependency is not a cycle
+        assert!(store.dependency_create(&dinner, &dough, "", false).is_ok());
+    }
+
+    #[test]
+    fn dependencies_are_unique() {
+        let mut store = LocalStore::default();
+        let dough = recipe(&mut store, "Dough");
+        let pie = recipe(&mut store, "Pie");
+        store.dependency_create(&pie, &dough, "", false).unwrap();
+
+        assert!(store.dependency_create(&pie, &dough, "", false).is_err());
+        assert!(store.dependency_create(&pie, "missing", "", false).is_err());
+    }
+
+    #[test]
+    fn classifications_come_from_the_dependencies() {
+        let mut store = LocalStore::default();
+        let butter = store
+            .ingredient_create("Butter", true, false, false, true)
+            .unwrap()
+            .id;
+        let flour = store
+            .ingredient_create("Flour", false, false, true, false)
+            .unwrap()
+            .id;
+        let dough = recipe(&mut store, "Dough");
+        let pie = recipe(&mut store, "Pie");
+        let dinner = recipe(&mut store, "Dinner");
+        store.requirement_create(&dough, &flour, "", false).unwrap();
+        store.requirement_create(&pie, &butter, "", false).unwrap();
+        store.dependency_create(&pie, &dough, "", false).unwrap();
+        store.dependency_create(&dinner, &pie, "", false).unwrap();
+
+        assert_eq!(classifications(&store, &dough), [false, false, true, false]);
+        assert_eq!(classifications(&store, &pie), [true, false, true, true]);
+        assert_eq!(classifications(&store, &dinner), [true, false, true, true]);
+    }
+
+    #[test]
+    fn classifications_follow_changes() {
+        let mut store = LocalStore::default();
+        let ham = store
+            .ingredient_create("Ham", false, false, false, false)
+            .unwrap()
+            .id;
+        let filling = recipe(&mut store, "Filling");
+        let pie = recipe(&mut store, "Pie");
+        store.requirement_create(&filling, &ham, "", false).unwrap();
+        store.dependency_create(&pie, &filling, "", false).unwrap();
+        assert_eq!(classifications(&store, &pie), [false; 4]);
+
+        store
+            .ingredient_update(&ham, None, None, Some(true), None, Some(true))
+            .unwrap();
+        assert_eq!(classifications(&store, &pie), [false, true, false, true]);
+
+        store.dependency_delete(&pie, &filling).unwrap();
+        assert_eq!(classifications(&store, &pie), [false; 4]);
+    }
+
+    #[test]
+    fn deleting_a_recipe_drops_its_dependents_links() {
+        let mut store = LocalStore::default();
+        let dough = recipe(&mut store, "Dough");
+        let pie = recipe(&mut store, "Pie");
+        store.dependency_create(&pie, &dough, "", false).unwrap();
+        store.recipe_delete(&dough).unwrap();
+
+        assert!(store.recipe_get(&pie).unwrap().dependencies.is_empty());
+        assert!(store.recipe_get(&dough).is_err());
+    }
+
+    #[test]
+    fn ingredients_in_use_are_kept() {
+        let mut store = LocalStore::default();
+        let flour = store
+            .ingredient_create("Flour", false, false, true, false)
+            .unwrap()
+            .id;
+        let dough = recipe(&mut store, "Dough");
+        store.requirement_create(&dough, &flour, "", false).unwrap();
+
+        assert!(store.ingredient_delete(&flour).is_err());
+        store.requirement_delete(&dough, &flour).unwrap();
+        assert!(store.ingredient_delete(&flour).is_ok());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::app::backend::local::LocalStore;
+use crate::app::backend::{Backend, BackendFuture, Error};
+use futures::future::FutureExt;
+use ladle::models::{Ingredient, IngredientIndex, Label, LabelIndex, Recipe, RecipeIndex};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Backend for tests, keeping a local store in memory only. Every request is
+// logged by name and argument so that tests can tell which ones reached it.
+#[derive(Default)]
+pub struct MemoryBackend {
+    store: RefCell<LocalStore>,
+    requests: Rc<RefCell<Vec<String>>>,
+}
+
+impl MemoryBackend {
+    // Log of the requests, still readable once the backend is wrapped
+    pub fn requests(&self) -> Rc<RefCell<Vec<String>>> {
+        self.requests.clone()
+    }
+
+    fn read<'a, T: 'a>(
+        &'a self,
+        request: String,
+        operation: impl FnOnce(&LocalStore) -> Result<T, Error> + 'a,
+    ) -> BackendFuture<'a, T> {
+        self.requests.borrow_mut().push(request);
+        async move { operation(&self.store.borrow()) }.boxed_local()
+    }
+
+    fn write<'a, T: 'a>(
+        &'a self,
+        request: String,
+        operation: impl FnOnce(&mut LocalStore) -> Result<T, Error> + 'a,
+    ) -> BackendFuture<'a, T> {
+        self.requests.borrow_mut().push(request);
+        async move { operation(&mut self.store.borrow_mut()) }.boxed_local()
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn location(&self) -> String {
+        String::from("memory")
+    }
+
+    fn recipe_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<RecipeIndex>> {
+        self.read(format!("recipe_index {}", pattern), move |store| {
+            store.recipe_index(pattern)
+        })
+    }
+
+    fn recipe_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Recipe> {
+        self.read(format!("recipe_get {}", id), move |store| {
+            store.recipe_get(id)
+        })
+    }
+
+    fn recipe_create<'a>(
+        &'a self,
+        name: &'a str,
+        author: &'a str,
+        directions: &'a str,
+        information: &'a str,
+    ) -> BackendFuture<'a, RecipeIndex> {
+        self.write(format!("recipe_create {}", name), move |store| {
+            store.recipe_create(name, author, directions, information)
+        })
+    }
+
+    fn recipe_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        author: Option<&'a str>,
+        directions: Option<&'a str>,
+        information: Option<&'a str>,
+    ) -> BackendFuture<'a, ()> {
+        self.write(format!("recipe_update {}", id), move |store| {
+            store.recipe_update(id, name, author, directions, information)
+        })
+    }
+
+    fn recipe_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(format!("recipe_delete {}", id), move |store| {
+            store.recipe_delete(id)
+        })
+    }
+
+    fn recipe_tag<'a>(&'a self, id: &'a str, label_name: &'a str) -> BackendFuture<'a, ()> {
+        self.write(format!("recipe_tag {}", id), move |store| {
+            store.recipe_tag(id, label_name)
+        })
+    }
+
+    fn recipe_untag<'a>(&'a self, id: &'a str, label_id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(format!("recipe_untag {}", id), move |store| {
+            store.recipe_untag(id, label_id)
+        })
+    }
+
+    fn ingredient_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<IngredientIndex>> {
+        self.read(format!("ingredient_index {}", pattern), move |store| {
+            store.ingredient_index(pattern)
+        })
+    }
+
+    fn ingredient_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Ingredient> {
+        self.read(format!("ingredient_get {}", id), move |store| {
+            store.ingredient_get(id)
+        })
+    }
+
+    fn ingredient_create<'a>(
+        &'a self,
+        name: &'a str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> BackendFuture<'a, IngredientIndex> {
+        self.write(format!("ingredient_create {}", name), move |store| {
+            store.ingredient_create(name, dairy, meat, gluten, animal_product)
+        })
+    }
+
+    fn ingredient_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> BackendFuture<'a, ()> {
+        self.write(format!("ingredient_update {}", id), move |store| {
+            store.ingredient_update(id, name, dairy, meat, gluten, animal_product)
+        })
+    }
+
+    fn ingredient_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(format!("ingredient_delete {}", id), move |store| {
+            store.ingredient_delete(id)
+        })
+    }
+
+    fn requirement_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.write(format!("requirement_create {}", recipe_id), move |store| {
+            store.requirement_create(recipe_id, ingredient_id, quantity, optional)
+        })
+    }
+
+    fn requirement_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.write(format!("requirement_delete {}", recipe_id), move |store| {
+            store.requirement_delete(recipe_id, ingredient_id)
+        })
+    }
+
+    fn dependency_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.write(format!("dependency_create {}", recipe_id), move |store| {
+            store.dependency_create(recipe_id, required_id, quantity, optional)
+        })
+    }
+
+    fn dependency_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.write(format!("dependency_delete {}", recipe_id), move |store| {
+            store.dependency_delete(recipe_id, required_id)
+        })
+    }
+
+    fn label_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<LabelIndex>> {
+        self.read(format!("label_index {}", pattern), move |store| {
+            store.label_index(pattern)
+        })
+    }
+
+    fn label_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Label> {
+        self.read(format!("label_get {}", id), move |store| {
+            store.label_get(id)
+        })
+    }
+}
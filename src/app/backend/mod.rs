@@ -0,0 +1,195 @@
+pub mod cache;
+pub mod http;
+pub mod local;
+#[cfg(test)]
+pub mod memory;
+pub mod retry;
+
+use crate::app::auth::grant;
+use crate::app::i18n::Language;
+use crate::app::settings::AppSettings;
+use futures::future::LocalBoxFuture;
+use ladle::models::{Ingredient, IngredientIndex, Label, LabelIndex, Recipe, RecipeIndex};
+use std::fmt;
+use std::ops::Deref;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    // The server could not be reached
+    Network(String),
+    // The server did not answer in time
+    Timeout,
+    NotFound(String),
+    // The server requires credentials, or refused the ones sent
+    Unauthorized(String),
+    // The server failed while handling the request
+    Server(String),
+    // The request was refused, e.g. because of conflicting data
+    Invalid(String),
+}
+
+impl Error {
+    // Whether trying again later may succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Network(_) | Error::Timeout | Error::Server(_))
+    }
+
+    pub fn describe(&self, language: Language) -> String {
+        match self {
+            Error::Network(detail) => language.tf("error.network", &[detail]),
+            Error::Timeout => language.t("error.timeout").to_string(),
+            Error::NotFound(detail) => language.tf("error.not_found", &[detail]),
+            Error::Unauthorized(detail) => language.tf("error.unauthorized", &[detail]),
+            Error::Server(detail) => language.tf("error.server", &[detail]),
+            Error::Invalid(detail) => detail.clone(),
+        }
+    }
+}
+
+// For logs, messages shown to the user go through `describe`
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe(Language::English))
+    }
+}
+
+pub type BackendFuture<'a, T> = LocalBoxFuture<'a, Result<T, Error>>;
+
+// Source of the recipe data. Components go through this trait instead of
+// calling ladle, so that the storage can be swapped or wrapped.
+pub trait Backend {
+    // Where the data lives, for display
+    fn location(&self) -> String;
+
+    // Forget any stored answer so that the next requests reach the data source
+    fn refresh(&self) {}
+
+    fn recipe_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<RecipeIndex>>;
+    fn recipe_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Recipe>;
+    fn recipe_create<'a>(
+        &'a self,
+        name: &'a str,
+        author: &'a str,
+        directions: &'a str,
+        information: &'a str,
+    ) -> BackendFuture<'a, RecipeIndex>;
+    fn recipe_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        author: Option<&'a str>,
+        directions: Option<&'a str>,
+        information: Option<&'a str>,
+    ) -> BackendFuture<'a, ()>;
+    fn recipe_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()>;
+    fn recipe_tag<'a>(&'a self, id: &'a str, label_name: &'a str) -> BackendFuture<'a, ()>;
+    fn recipe_untag<'a>(&'a self, id: &'a str, label_id: &'a str) -> BackendFuture<'a, ()>;
+
+    fn ingredient_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<IngredientIndex>>;
+    fn ingredient_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Ingredient>;
+    fn ingredient_create<'a>(
+        &'a self,
+        name: &'a str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> BackendFuture<'a, IngredientIndex>;
+    fn ingredient_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> BackendFuture<'a, ()>;
+    fn ingredient_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()>;
+
+    fn requirement_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()>;
+    fn requirement_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+    ) -> BackendFuture<'a, ()>;
+
+    fn dependency_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()>;
+    fn dependency_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+    ) -> BackendFuture<'a, ()>;
+
+    fn label_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<LabelIndex>>;
+    fn label_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Label>;
+}
+
+// Shared backend, comparing equal when pointing to the same instance so it
+// can live in the context
+#[derive(Clone)]
+pub struct BackendHandle(Rc<dyn Backend>);
+
+impl BackendHandle {
+    pub fn new(backend: impl Backend + 'static) -> Self {
+        BackendHandle(Rc::new(backend))
+    }
+}
+
+impl Deref for BackendHandle {
+    type Target = dyn Backend;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl PartialEq for BackendHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for BackendHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BackendHandle")
+    }
+}
+
+// Backend matching the storage chosen in the settings
+pub fn connect(settings: &AppSettings) -> BackendHandle {
+    match settings.local {
+        true => BackendHandle::new(local::LocalBackend::default()),
+        false => BackendHandle::new(cache::CacheBackend::new(
+            retry::RetryBackend::new(
+                http::HttpBackend::new(&settings.server_url),
+                settings.requests.clone(),
+            ),
+            settings.requests.cache_ttl_ms,
+        )),
+    }
+}
+
+// Backend of a server other than the data source, such as the target of a
+// migration, sending its stored credentials and retrying as `connect` does
+pub fn connect_server(url: &str, settings: &AppSettings) -> BackendHandle {
+    if let Some(credentials) = settings.credentials.get(url) {
+        grant(url, credentials);
+    }
+    BackendHandle::new(retry::RetryBackend::new(
+        http::HttpBackend::new(url),
+        settings.requests.clone(),
+    ))
+}
@@ -0,0 +1,236 @@
+use crate::app::backend::{Backend, BackendFuture, Error};
+use futures::future::{select, Either, FutureExt};
+use ladle::models::{Ingredient, IngredientIndex, Label, LabelIndex, Recipe, RecipeIndex};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::JsFuture;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct RequestPolicy {
+    // Time after which a request is abandoned
+    pub timeout_ms: u32,
+    // Number of tries for requests that only read data
+    pub attempts: u32,
+    // Delay before the first retry, doubled after every failure
+    pub backoff_ms: u32,
+    // How long answers are reused before asking again
+    pub cache_ttl_ms: u32,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        RequestPolicy {
+            timeout_ms: 10_000,
+            attempts: 3,
+            backoff_ms: 500,
+            cache_ttl_ms: 60_000,
+        }
+    }
+}
+
+fn sleep(ms: u32) -> JsFuture {
+    JsFuture::from(js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                ms.min(i32::MAX as u32) as i32,
+            );
+        }
+    }))
+}
+
+async fn with_timeout<T>(request: BackendFuture<'_, T>, ms: u32) -> Result<T, Error> {
+    match select(request, Box::pin(sleep(ms))).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(Error::Timeout),
+    }
+}
+
+// Wrap a backend to give up on requests that take too long, and retry reads
+// failing because of the network or the server. Writes are not retried as they
+// may have been applied before failing.
+pub struct RetryBackend<B: Backend> {
+    inner: B,
+    policy: RequestPolicy,
+}
+
+impl<B: Backend> RetryBackend<B> {
+    pub fn new(inner: B, policy: RequestPolicy) -> Self {
+        RetryBackend { inner, policy }
+    }
+
+    fn read<'a, T: 'a>(
+        &'a self,
+        request: impl Fn(&'a B) -> BackendFuture<'a, T> + 'a,
+    ) -> BackendFuture<'a, T> {
+        async move {
+            let mut delay = self.policy.backoff_ms;
+            let mut attempt = 1;
+
+            loop {
+                match with_timeout(request(&self.inner), self.policy.timeout_ms).await {
+                    Err(error) if error.is_transient() && attempt < self.policy.attempts => {
+                        log::warn!("Retrying in {} ms after: {}", delay, error);
+                        sleep(delay).await.ok();
+                        delay = delay.saturating_mul(2);
+                        attempt += 1;
+                    }
+                    result => return result,
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    fn write<'a, T: 'a>(&'a self, request: BackendFuture<'a, T>) -> BackendFuture<'a, T> {
+        with_timeout(request, self.policy.timeout_ms).boxed_local()
+    }
+}
+
+impl<B: Backend> Backend for RetryBackend<B> {
+    fn location(&self) -> String {
+        self.inner.location()
+    }
+
+    fn refresh(&self) {
+        self.inner.refresh()
+    }
+
+    fn recipe_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<RecipeIndex>> {
+        self.read(move |inner| inner.recipe_index(pattern))
+    }
+
+    fn recipe_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Recipe> {
+        self.read(move |inner| inner.recipe_get(id))
+    }
+
+    fn recipe_create<'a>(
+        &'a self,
+        name: &'a str,
+        author: &'a str,
+        directions: &'a str,
+        information: &'a str,
+    ) -> BackendFuture<'a, RecipeIndex> {
+        self.write(
+            self.inner
+                .recipe_create(name, author, directions, information),
+        )
+    }
+
+    fn recipe_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        author: Option<&'a str>,
+        directions: Option<&'a str>,
+        information: Option<&'a str>,
+    ) -> BackendFuture<'a, ()> {
+        self.write(
+            self.inner
+                .recipe_update(id, name, author, directions, information),
+        )
+    }
+
+    fn recipe_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(self.inner.recipe_delete(id))
+    }
+
+    fn recipe_tag<'a>(&'a self, id: &'a str, label_name: &'a str) -> BackendFuture<'a, ()> {
+        self.write(self.inner.recipe_tag(id, label_name))
+    }
+
+    fn recipe_untag<'a>(&'a self, id: &'a str, label_id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(self.inner.recipe_untag(id, label_id))
+    }
+
+    fn ingredient_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<IngredientIndex>> {
+        self.read(move |inner| inner.ingredient_index(pattern))
+    }
+
+    fn ingredient_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Ingredient> {
+        self.read(move |inner| inner.ingredient_get(id))
+    }
+
+    fn ingredient_create<'a>(
+        &'a self,
+        name: &'a str,
+        dairy: bool,
+        meat: bool,
+        gluten: bool,
+        animal_product: bool,
+    ) -> BackendFuture<'a, IngredientIndex> {
+        self.write(
+            self.inner
+                .ingredient_create(name, dairy, meat, gluten, animal_product),
+        )
+    }
+
+    fn ingredient_update<'a>(
+        &'a self,
+        id: &'a str,
+        name: Option<&'a str>,
+        dairy: Option<bool>,
+        meat: Option<bool>,
+        gluten: Option<bool>,
+        animal_product: Option<bool>,
+    ) -> BackendFuture<'a, ()> {
+        self.write(
+            self.inner
+                .ingredient_update(id, name, dairy, meat, gluten, animal_product),
+        )
+    }
+
+    fn ingredient_delete<'a>(&'a self, id: &'a str) -> BackendFuture<'a, ()> {
+        self.write(self.inner.ingredient_delete(id))
+    }
+
+    fn requirement_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.write(
+            self.inner
+                .requirement_create(recipe_id, ingredient_id, quantity, optional),
+        )
+    }
+
+    fn requirement_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        ingredient_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.write(self.inner.requirement_delete(recipe_id, ingredient_id))
+    }
+
+    fn dependency_create<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+        quantity: &'a str,
+        optional: bool,
+    ) -> BackendFuture<'a, ()> {
+        self.write(
+            self.inner
+                .dependency_create(recipe_id, required_id, quantity, optional),
+        )
+    }
+
+    fn dependency_delete<'a>(
+        &'a self,
+        recipe_id: &'a str,
+        required_id: &'a str,
+    ) -> BackendFuture<'a, ()> {
+        self.write(self.inner.dependency_delete(recipe_id, required_id))
+    }
+
+    fn label_index<'a>(&'a self, pattern: &'a str) -> BackendFuture<'a, Vec<LabelIndex>> {
+        self.read(move |inner| inner.label_index(pattern))
+    }
+
+    fn label_get<'a>(&'a self, id: &'a str) -> BackendFuture<'a, Label> {
+        self.read(move |inner| inner.label_get(id))
+    }
+}
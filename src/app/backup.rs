@@ -0,0 +1,954 @@
+use crate::app::backend::{connect_server, Backend};
+use crate::app::connection::validate_url;
+use crate::app::download::download;
+use crate::app::i18n::Language;
+use crate::app::ingredients::matching::normalize;
+use crate::app::{status_bar::Message, AppContext, DETAILS_CONCURRENCY};
+use futures::future::FutureExt;
+use futures::stream::{self, StreamExt};
+use ladle::models::{Classifications, IngredientIndex, Recipe, RecipeIndex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// Bump when the layout of the archive changes
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ArchivedIngredient {
+    pub id: String,
+    pub name: String,
+    pub classifications: Classifications,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ArchivedLabel {
+    pub id: String,
+    pub name: String,
+    pub tagged_recipes: Vec<String>,
+}
+
+// Contents of a knife server at a point in time
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct Archive {
+    pub version: u32,
+    pub created: String,
+    pub server: String,
+    pub ingredients: Vec<ArchivedIngredient>,
+    pub recipes: Vec<Recipe>,
+    pub labels: Vec<ArchivedLabel>,
+}
+
+impl Archive {
+    pub fn parse(source: &str, language: Language) -> Result<Archive, String> {
+        let archive: Archive = serde_json::from_str(source).map_err(|e| e.to_string())?;
+
+        match archive.version > ARCHIVE_VERSION {
+            true => Err(language.tf("backup.unsupported_version", &[&archive.version])),
+            false => Ok(archive),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ProgressEvent {
+    Start(usize),
+    Step,
+}
+
+// Fetch every ingredient, recipe and label of the server
+pub async fn fetch_archive(
+    backend: &dyn Backend,
+    progress: &Callback<ProgressEvent>,
+    language: Language,
+) -> Result<Archive, String> {
+    let ingredients = backend
+        .ingredient_index("")
+        .await
+        .map_err(|e| e.describe(language))?;
+    let recipes = backend
+        .recipe_index("")
+        .await
+        .map_err(|e| e.describe(language))?;
+    let labels = backend
+        .label_index("")
+        .await
+        .map_err(|e| e.describe(language))?;
+
+    progress.emit(ProgressEvent::Start(
+        ingredients.len() + recipes.len() + labels.len(),
+    ));
+
+    let fetches = ingredients.iter().map(|i| {
+        backend
+            .ingredient_get(&i.id)
+            .inspect(|_| progress.emit(ProgressEvent::Step))
+    });
+    let ingredients = stream::iter(fetches)
+        .buffer_unordered(DETAILS_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|response| {
+            response
+                .map(|ingredient| ArchivedIngredient {
+                    id: ingredient.id,
+                    name: ingredient.name,
+                    classifications: ingredient.classifications,
+                })
+                .map_err(|e| e.describe(language))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let fetches = recipes.iter().map(|r| {
+        backend
+            .recipe_get(&r.id)
+            .inspect(|_| progress.emit(ProgressEvent::Step))
+    });
+    let recipes = stream::iter(fetches)
+        .buffer_unordered(DETAILS_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|response| response.map_err(|e| e.describe(language)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let fetches = labels.iter().map(|l| {
+        backend
+            .label_get(&l.id)
+            .inspect(|_| progress.emit(ProgressEvent::Step))
+    });
+    let labels = stream::iter(fetches)
+        .buffer_unordered(DETAILS_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|response| {
+            response
+                .map(|label| ArchivedLabel {
+                    id: label.id,
+                    name: label.name,
+                    tagged_recipes: label.tagged_recipes.into_iter().map(|r| r.id).collect(),
+                })
+                .map_err(|e| e.describe(language))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Archive {
+        version: ARCHIVE_VERSION,
+        created: chrono::Utc::now().to_rfc3339(),
+        server: backend.location(),
+        ingredients,
+        recipes,
+        labels,
+    })
+}
+
+// What restoring an archive on a server will do. Items already present on
+// the server are reused instead of being created again: recipes with the same
+// name, and ingredients with the same normalized name.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct RestorePlan {
+    // Archived id -> matching item on the server
+    pub ingredients: HashMap<String, IngredientIndex>,
+    pub recipes: HashMap<String, RecipeIndex>,
+    pub new_ingredients: Vec<ArchivedIngredient>,
+    pub new_recipes: Vec<Recipe>,
+}
+
+impl RestorePlan {
+    pub fn new(
+        archive: &Archive,
+        server_ingredients: &[IngredientIndex],
+        server_recipes: &[RecipeIndex],
+    ) -> Self {
+        let mut plan = RestorePlan::default();
+
+        for ingredient in archive.ingredients.iter() {
+            let name = normalize(&ingredient.name);
+            match server_ingredients
+                .iter()
+                .find(|index| normalize(&index.name) == name)
+            {
+                Some(index) => {
+                    plan.ingredients
+                        .insert(ingredient.id.clone(), index.clone());
+                }
+                None => plan.new_ingredients.push(ingredient.clone()),
+            }
+        }
+
+        for recipe in archive.recipes.iter() {
+            match server_recipes
+                .iter()
+                .find(|index| index.name == recipe.name)
+            {
+                Some(index) => {
+                    plan.recipes.insert(recipe.id.clone(), index.clone());
+                }
+                None => plan.new_recipes.push(recipe.clone()),
+            }
+        }
+
+        plan
+    }
+
+    fn requirement_count(&self) -> usize {
+        self.new_recipes.iter().map(|r| r.requirements.len()).sum()
+    }
+
+    fn dependency_count(&self) -> usize {
+        self.new_recipes.iter().map(|r| r.dependencies.len()).sum()
+    }
+
+    fn tag_count(&self, archive: &Archive) -> usize {
+        archive
+            .labels
+            .iter()
+            .flat_map(|label| label.tagged_recipes.iter())
+            .filter(|id| self.new_recipes.iter().any(|recipe| &recipe.id == *id))
+            .count()
+    }
+
+    // Number of requests sent to the server when executing the plan
+    pub fn steps(&self, archive: &Archive) -> usize {
+        self.new_ingredients.len()
+            + 2 * self.new_recipes.len()
+            + self.requirement_count()
+            + self.dependency_count()
+            + self.tag_count(archive)
+    }
+
+    pub fn report(&self, archive: &Archive, language: Language) -> Vec<String> {
+        vec![
+            language.plural(
+                "plan.ingredients",
+                self.new_ingredients.len(),
+                &[&self.ingredients.len()],
+            ),
+            language.plural(
+                "plan.recipes",
+                self.new_recipes.len(),
+                &[&self.recipes.len()],
+            ),
+            language.plural("plan.requirements", self.requirement_count(), &[]),
+            language.plural("plan.dependencies", self.dependency_count(), &[]),
+            language.plural("plan.tags", self.tag_count(archive), &[]),
+        ]
+    }
+}
+
+pub async fn plan_restore(
+    backend: &dyn Backend,
+    archive: &Archive,
+    language: Language,
+) -> Result<RestorePlan, String> {
+    let ingredients = backend
+        .ingredient_index("")
+        .await
+        .map_err(|e| e.describe(language))?;
+    let recipes = backend
+        .recipe_index("")
+        .await
+        .map_err(|e| e.describe(language))?;
+
+    Ok(RestorePlan::new(archive, &ingredients, &recipes))
+}
+
+// Execute a restore plan, returning the errors encountered along the way
+pub async fn restore(
+    backend: &dyn Backend,
+    archive: &Archive,
+    plan: &RestorePlan,
+    progress: &Callback<ProgressEvent>,
+    language: Language,
+) -> Vec<String> {
+    let mut errors = vec![];
+    let mut ingredients: HashMap<String, String> = plan
+        .ingredients
+        .iter()
+        .map(|(id, index)| (id.clone(), index.id.clone()))
+        .collect();
+    let mut recipes: HashMap<String, String> = plan
+        .recipes
+        .iter()
+        .map(|(id, index)| (id.clone(), index.id.clone()))
+        .collect();
+
+    progress.emit(ProgressEvent::Start(plan.steps(archive)));
+
+    for ingredient in plan.new_ingredients.iter() {
+        match backend
+            .ingredient_create(
+                &ingredient.name,
+                ingredient.classifications.dairy,
+                ingredient.classifications.meat,
+                ingredient.classifications.gluten,
+                ingredient.classifications.animal_product,
+            )
+            .await
+        {
+            Ok(created) => {
+                ingredients.insert(ingredient.id.clone(), created.id);
+            }
+            Err(e) => errors.push(language.tf(
+                "restore.ingredient",
+                &[&ingredient.name, &e.describe(language)],
+            )),
+        }
+        progress.emit(ProgressEvent::Step);
+    }
+
+    // Create every recipe before linking them together
+    for recipe in plan.new_recipes.iter() {
+        match backend.recipe_create(&recipe.name, "", "", "").await {
+            Ok(created) => {
+                if let Err(e) = backend
+                    .recipe_update(
+                        &created.id,
+                        Some(&recipe.name),
+                        Some(&recipe.author),
+                        Some(&recipe.directions),
+                        None,
+                    )
+                    .await
+                {
+                    errors
+                        .push(language.tf("restore.recipe", &[&recipe.name, &e.describe(language)]))
+                }
+                recipes.insert(recipe.id.clone(), created.id);
+            }
+            Err(e) => {
+                errors.push(language.tf("restore.recipe", &[&recipe.name, &e.describe(language)]))
+            }
+        }
+        progress.emit(ProgressEvent::Step);
+        progress.emit(ProgressEvent::Step);
+    }
+
+    for recipe in plan.new_recipes.iter() {
+        let recipe_id = match recipes.get(&recipe.id) {
+            Some(id) => id.clone(),
+            None => {
+                (0..recipe.requirements.len() + recipe.dependencies.len())
+                    .for_each(|_| progress.emit(ProgressEvent::Step));
+                continue;
+            }
+        };
+
+        for requirement in recipe.requirements.iter() {
+            match ingredients.get(&requirement.ingredient.id) {
+                Some(ingredient_id) => {
+                    if let Err(e) = backend
+                        .requirement_create(
+                            &recipe_id,
+                            ingredient_id,
+                            &requirement.quantity,
+                            requirement.optional,
+                        )
+                        .await
+                    {
+                        errors.push(language.tf(
+                            "restore.requirement",
+                            &[
+                                &requirement.ingredient.name,
+                                &recipe.name,
+                                &e.describe(language),
+                            ],
+                        ))
+                    }
+                }
+                None => errors.push(language.tf(
+                    "restore.requirement",
+                    &[
+                        &requirement.ingredient.name,
+                        &recipe.name,
+                        &language.t("restore.unknown_ingredient"),
+                    ],
+                )),
+            }
+            progress.emit(ProgressEvent::Step);
+        }
+
+        for dependency in recipe.dependencies.iter() {
+            match recipes.get(&dependency.recipe.id) {
+                Some(dependency_id) => {
+                    if let Err(e) = backend
+                        .dependency_create(
+                            &recipe_id,
+                            dependency_id,
+                            &dependency.quantity,
+                            dependency.optional,
+                        )
+                        .await
+                    {
+                        errors.push(language.tf(
+                            "restore.dependency",
+                            &[&dependency.recipe.name, &recipe.name, &e.describe(language)],
+                        ))
+                    }
+                }
+                None => errors.push(language.tf(
+                    "restore.dependency",
+                    &[
+                        &dependency.recipe.name,
+                        &recipe.name,
+                        &language.t("restore.unknown_recipe"),
+                    ],
+                )),
+            }
+            progress.emit(ProgressEvent::Step);
+        }
+    }
+
+    for label in archive.labels.iter() {
+        for tagged in label.tagged_recipes.iter() {
+            if !plan.new_recipes.iter().any(|recipe| &recipe.id == tagged) {
+                continue;
+            }
+
+            if let Some(recipe_id) = recipes.get(tagged) {
+                if let Err(e) = backend.recipe_tag(recipe_id, &label.name).await {
+                    errors.push(language.tf("restore.label", &[&label.name, &e.describe(language)]))
+                }
+            }
+            progress.emit(ProgressEvent::Step);
+        }
+    }
+
+    errors
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct BackupPanelProps {
+    pub ingredient_cache_refresh: Callback<()>,
+    pub recipe_cache_refresh: Callback<()>,
+}
+
+enum BackupAction {
+    Progress(ProgressEvent),
+    Planned(Archive, RestorePlan, Vec<String>),
+    Done(Vec<String>),
+    Reset,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct BackupState {
+    busy: bool,
+    done: usize,
+    total: usize,
+    pending: Option<(Archive, RestorePlan)>,
+    report: Vec<String>,
+}
+
+impl Reducible for BackupState {
+    type Action = BackupAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut new_state: Self = (*self).clone();
+
+        match action {
+            BackupAction::Progress(ProgressEvent::Start(total)) => {
+                new_state.busy = true;
+                new_state.done = 0;
+                new_state.total = total;
+                new_state.report = vec![];
+            }
+            BackupAction::Progress(ProgressEvent::Step) => new_state.done += 1,
+            BackupAction::Planned(archive, plan, report) => {
+                new_state.report = report;
+                new_state.pending = Some((archive, plan));
+            }
+            BackupAction::Done(report) => {
+                new_state.busy = false;
+                new_state.pending = None;
+                new_state.report = report;
+            }
+            BackupAction::Reset => new_state = BackupState::default(),
+        }
+
+        new_state.into()
+    }
+}
+
+#[function_component(BackupPanel)]
+pub fn backup_panel(props: &BackupPanelProps) -> Html {
+    let state = use_reducer(BackupState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+
+    let state_cloned = state.clone();
+    let progress = Callback::from(move |event: ProgressEvent| {
+        state_cloned.dispatch(BackupAction::Progress(event))
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let progress_cloned = progress.clone();
+    let on_backup = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let progress_cloned = progress_cloned.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let language = context_cloned.settings.language;
+            let result = fetch_archive(&*context_cloned.backend, &progress_cloned, language)
+                .await
+                .and_then(|archive| {
+                    let contents =
+                        serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())?;
+                    let name = format!("spoon-backup-{}.json", chrono::Utc::now().format("%F"));
+                    download(&name, "application/json", &contents)
+                        .map_err(|e| e.as_string().unwrap_or(String::default()))?;
+                    Ok(archive)
+                });
+
+            match result {
+                Ok(archive) => state_cloned.dispatch(BackupAction::Done(vec![language.tf(
+                    "backup.saved",
+                    &[
+                        &language.plural("count.ingredients", archive.ingredients.len(), &[]),
+                        &language.plural("count.recipes", archive.recipes.len(), &[]),
+                        &language.plural("count.labels", archive.labels.len(), &[]),
+                    ],
+                )])),
+                Err(message) => {
+                    state_cloned.dispatch(BackupAction::Reset);
+                    context_cloned
+                        .status
+                        .emit(Message::Error(message, chrono::Utc::now()))
+                }
+            }
+        });
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_archive_select = Callback::from(move |e: Event| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let input = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>();
+        let file = input.files().and_then(|files| files.get(0));
+        input.set_value("");
+
+        if let Some(file) = file {
+            wasm_bindgen_futures::spawn_local(async move {
+                let source = JsFuture::from(file.text())
+                    .await
+                    .map(|contents| contents.as_string().unwrap_or_default())
+                    .map_err(|e| e.as_string().unwrap_or(String::default()));
+
+                let language = context_cloned.settings.language;
+                let result = match source.and_then(|source| Archive::parse(&source, language)) {
+                    Ok(archive) => plan_restore(&*context_cloned.backend, &archive, language)
+                        .await
+                        .map(|plan| (archive, plan)),
+                    Err(message) => Err(message),
+                };
+
+                match result {
+                    Ok((archive, plan)) => {
+                        let report = plan.report(&archive, context_cloned.settings.language);
+                        state_cloned.dispatch(BackupAction::Planned(archive, plan, report))
+                    }
+                    Err(message) => context_cloned
+                        .status
+                        .emit(Message::Error(message, chrono::Utc::now())),
+                }
+            });
+        }
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let props_cloned = props.clone();
+    let on_restore = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let props_cloned = props_cloned.clone();
+        let progress = progress.clone();
+
+        let (archive, plan) = match &state_cloned.pending {
+            Some(pending) => pending.clone(),
+            None => return,
+        };
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let language = context_cloned.settings.language;
+            let errors = restore(
+                &*context_cloned.backend,
+                &archive,
+                &plan,
+                &progress,
+                language,
+            )
+            .await;
+
+            props_cloned.ingredient_cache_refresh.emit(());
+            props_cloned.recipe_cache_refresh.emit(());
+
+            match errors.len() {
+                0 => context_cloned.status.emit(Message::Success(
+                    context_cloned
+                        .settings
+                        .language
+                        .t("backup.restored")
+                        .to_string(),
+                    chrono::Utc::now(),
+                )),
+                count => context_cloned.status.emit(Message::Error(
+                    context_cloned
+                        .settings
+                        .language
+                        .plural("backup.restored_errors", count, &[]),
+                    chrono::Utc::now(),
+                )),
+            }
+            state_cloned.dispatch(BackupAction::Done(errors));
+        });
+    });
+
+    let state_cloned = state.clone();
+    let on_cancel = Callback::from(move |_| state_cloned.dispatch(BackupAction::Reset));
+
+    let report = state
+        .report
+        .iter()
+        .map(|line| html! {<li>{line}</li>})
+        .collect::<Html>();
+
+    let language = context.settings.language;
+    html! {
+        <div class="backup">
+            <button disabled={state.busy} onclick={on_backup}>{language.t("backup.download")}</button>
+            <label class="restore-archive">
+                {language.t("backup.restore_from")}
+                <input
+                    type="file"
+                    accept=".json"
+                    disabled={state.busy}
+                    onchange={on_archive_select}
+                />
+            </label>
+            if state.busy {
+                <progress max={state.total.to_string()} value={state.done.to_string()} />
+            }
+            <ul class="backup-report">{report}</ul>
+            if state.pending.is_some() {
+                <div class="restore-confirm">
+                    <button disabled={state.busy} onclick={on_restore}>{language.t("backup.restore")}</button>
+                    <button disabled={state.busy} onclick={on_cancel}>{language.t("action.cancel")}</button>
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PushPanelProps {
+    pub server_url: String,
+}
+
+// Copy the data stored in the browser to a knife server
+#[function_component(PushPanel)]
+pub fn push_panel(props: &PushPanelProps) -> Html {
+    let state = use_reducer(BackupState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+
+    let state_cloned = state.clone();
+    let progress = Callback::from(move |event: ProgressEvent| {
+        state_cloned.dispatch(BackupAction::Progress(event))
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let props_cloned = props.clone();
+    let progress_cloned = progress.clone();
+    let on_plan = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let progress_cloned = progress_cloned.clone();
+        let language = context_cloned.settings.language;
+        let target = validate_url(&props_cloned.server_url).map_err(|e| e.translate(language));
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = match target {
+                Ok(target) => {
+                    match fetch_archive(&*context_cloned.backend, &progress_cloned, language).await
+                    {
+                        Ok(archive) => {
+                            let backend = connect_server(&target, &context_cloned.settings);
+                            plan_restore(&*backend, &archive, language)
+                                .await
+                                .map(|plan| (archive, plan))
+                        }
+                        Err(message) => Err(message),
+                    }
+                }
+                Err(message) => Err(message),
+            };
+
+            match result {
+                Ok((archive, plan)) => {
+                    let report = plan.report(&archive, context_cloned.settings.language);
+                    state_cloned.dispatch(BackupAction::Done(vec![]));
+                    state_cloned.dispatch(BackupAction::Planned(archive, plan, report))
+                }
+                Err(message) => {
+                    state_cloned.dispatch(BackupAction::Reset);
+                    context_cloned
+                        .status
+                        .emit(Message::Error(message, chrono::Utc::now()))
+                }
+            }
+        });
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let props_cloned = props.clone();
+    let on_push = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let progress = progress.clone();
+
+        let (archive, plan) = match &state_cloned.pending {
+            Some(pending) => pending.clone(),
+            None => return,
+        };
+        let target = match validate_url(&props_cloned.server_url) {
+            Ok(target) => target,
+            Err(message) => {
+                context_cloned.status.emit(Message::Error(
+                    message.translate(context_cloned.settings.language),
+                    chrono::Utc::now(),
+                ));
+                return;
+            }
+        };
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let backend = connect_server(&target, &context_cloned.settings);
+            let language = context_cloned.settings.language;
+            let errors = restore(&*backend, &archive, &plan, &progress, language).await;
+
+            match errors.len() {
+                0 => context_cloned.status.emit(Message::Success(
+                    context_cloned.settings.language.tf("push.done", &[&target]),
+                    chrono::Utc::now(),
+                )),
+                count => context_cloned.status.emit(Message::Error(
+                    context_cloned
+                        .settings
+                        .language
+                        .plural("push.done_errors", count, &[]),
+                    chrono::Utc::now(),
+                )),
+            }
+            state_cloned.dispatch(BackupAction::Done(errors));
+        });
+    });
+
+    let state_cloned = state.clone();
+    let on_cancel = Callback::from(move |_| state_cloned.dispatch(BackupAction::Reset));
+
+    let report = state
+        .report
+        .iter()
+        .map(|line| html! {<li>{line}</li>})
+        .collect::<Html>();
+
+    let language = context.settings.language;
+    html! {
+        <div class="backup">
+            <button
+                disabled={state.busy || props.server_url.is_empty()}
+                onclick={on_plan}>
+                {language.tf("push.button", &[&props.server_url])}
+            </button>
+            if state.busy {
+                <progress max={state.total.to_string()} value={state.done.to_string()} />
+            }
+            <ul class="backup-report">{report}</ul>
+            if state.pending.is_some() {
+                <div class="restore-confirm">
+                    <button disabled={state.busy} onclick={on_push}>{language.t("push.push")}</button>
+                    <button disabled={state.busy} onclick={on_cancel}>{language.t("action.cancel")}</button>
+                </div>
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::backend::memory::MemoryBackend;
+    use futures::executor::block_on;
+    use std::cell::RefCell;
+
+    // Archive of a server with a pie made of dough, the pie using butter and
+    // the dough flour
+    fn archive() -> Archive {
+        let source = MemoryBackend::default();
+        block_on(async {
+            let flour = source
+                .ingredient_create("Flour", false, false, true, false)
+                .await
+                .unwrap();
+            let butter = source
+                .ingredient_create("Butter", true, false, false, true)
+                .await
+                .unwrap();
+            let dough = source
+                .recipe_create("Dough", "", "Knead", "")
+                .await
+                .unwrap();
+            let pie = source.recipe_create("Pie", "", "Bake", "").await.unwrap();
+            source
+                .requirement_create(&dough.id, &flour.id, "200 g", false)
+                .await
+                .unwrap();
+            source
+                .requirement_create(&pie.id, &butter.id, "50 g", true)
+                .await
+                .unwrap();
+            source
+                .dependency_create(&pie.id, &dough.id, "1", false)
+                .await
+                .unwrap();
+            source.recipe_tag(&pie.id, "Dessert").await.unwrap();
+        });
+
+        block_on(fetch_archive(
+            &source,
+            &Callback::from(|_| ()),
+            Language::English,
+        ))
+        .unwrap()
+    }
+
+    fn archived_id(archive: &Archive, name: &str) -> String {
+        archive
+            .recipes
+            .iter()
+            .find(|recipe| recipe.name == name)
+            .map(|recipe| recipe.id.clone())
+            .unwrap()
+    }
+
+    fn plan(archive: &Archive, target: &MemoryBackend) -> RestorePlan {
+        block_on(plan_restore(target, archive, Language::English)).unwrap()
+    }
+
+    fn recipe(target: &MemoryBackend, name: &str) -> Recipe {
+        let index = block_on(target.recipe_index(name)).unwrap();
+        block_on(target.recipe_get(&index[0].id)).unwrap()
+    }
+
+    #[test]
+    fn recipes_are_reused_by_name() {
+        let archive = archive();
+        let target = MemoryBackend::default();
+        let dough = block_on(target.recipe_create("Dough", "", "", "")).unwrap();
+
+        let plan = plan(&archive, &target);
+        assert_eq!(
+            plan.recipes.get(&archived_id(&archive, "Dough")),
+            Some(&dough)
+        );
+        let new: Vec<&str> = plan.new_recipes.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(new, vec!["Pie"]);
+    }
+
+    #[test]
+    fn ingredients_are_matched_on_normalized_names() {
+        let archive = archive();
+        let target = MemoryBackend::default();
+        let flour =
+            block_on(target.ingredient_create("FLOURS ", false, false, true, false)).unwrap();
+
+        let plan = plan(&archive, &target);
+        let mapped: Vec<&IngredientIndex> = plan.ingredients.values().collect();
+        assert_eq!(mapped, vec![&flour]);
+        let new: Vec<&str> = plan
+            .new_ingredients
+            .iter()
+            .map(|i| i.name.as_str())
+            .collect();
+        assert_eq!(new, vec!["Butter"]);
+    }
+
+    #[test]
+    fn restore_remaps_dependencies_and_requirements() {
+        let archive = archive();
+        let target = MemoryBackend::default();
+        // Shift the ids of the target away from the archived ones
+        block_on(target.ingredient_create("Sugar", false, false, false, false)).unwrap();
+        let dough = block_on(target.recipe_create("Dough", "", "", "")).unwrap();
+
+        let plan = plan(&archive, &target);
+        let errors = block_on(restore(
+            &target,
+            &archive,
+            &plan,
+            &Callback::from(|_| ()),
+            Language::English,
+        ));
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let pie = recipe(&target, "Pie");
+        let dependencies: Vec<&str> = pie
+            .dependencies
+            .iter()
+            .map(|d| d.recipe.id.as_str())
+            .collect();
+        assert_eq!(dependencies, vec![dough.id.as_str()]);
+        let butter = block_on(target.ingredient_index("Butter")).unwrap();
+        let requirements: Vec<&str> = pie
+            .requirements
+            .iter()
+            .map(|r| r.ingredient.id.as_str())
+            .collect();
+        assert_eq!(requirements, vec![butter[0].id.as_str()]);
+        assert_eq!(pie.directions, "Bake");
+        assert_eq!(
+            pie.tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["Dessert"]
+        );
+        // The reused dough is left as it was on the target
+        assert!(recipe(&target, "Dough").requirements.is_empty());
+    }
+
+    #[test]
+    fn steps_match_the_progress_events() {
+        let archive = archive();
+        let target = MemoryBackend::default();
+        let plan = plan(&archive, &target);
+
+        let events = Rc::new(RefCell::new(vec![]));
+        let events_cloned = events.clone();
+        let progress = Callback::from(move |event| events_cloned.borrow_mut().push(event));
+        let errors = block_on(restore(
+            &target,
+            &archive,
+            &plan,
+            &progress,
+            Language::English,
+        ));
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let events = events.borrow();
+        let steps = plan.steps(&archive);
+        assert_eq!(events[0], ProgressEvent::Start(steps));
+        assert_eq!(events.len() - 1, steps);
+        assert!(events[1..].iter().all(|e| *e == ProgressEvent::Step));
+        // Two ingredients, two recipes created then updated, two links, a tag
+        assert_eq!(steps, 2 + 2 * 2 + 2 + 1 + 1);
+    }
+}
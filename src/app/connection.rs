@@ -0,0 +1,118 @@
+use crate::app::auth::{grant, Credentials};
+use crate::app::backend::{http::HttpBackend, Backend, Error};
+use crate::app::i18n::{Language, Text};
+use futures::join;
+
+// Check the url is an absolute http(s) url, and return it without trailing
+// slash as ladle appends paths to it
+pub fn validate_url(url: &str) -> Result<String, Text> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(Text::new("url.empty", &[]));
+    }
+
+    let parsed = web_sys::Url::new(url).map_err(|_| Text::new("url.invalid", &[&url]))?;
+
+    match parsed.protocol().as_str() {
+        "http:" | "https:" => (),
+        protocol => {
+            return Err(Text::new(
+                "url.protocol",
+                &[&protocol.trim_end_matches(':')],
+            ))
+        }
+    }
+
+    if parsed.hostname().is_empty() {
+        return Err(Text::new("url.no_host", &[&url]));
+    }
+
+    Ok(url.trim_end_matches('/').to_string())
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct ConnectionReport {
+    pub latency: f64,
+    pub recipes: usize,
+    pub ingredients: usize,
+    pub labels: usize,
+}
+
+impl ConnectionReport {
+    pub fn describe(&self, language: Language) -> String {
+        language.tf(
+            "connection.report",
+            &[
+                &format!("{:.0}", self.latency),
+                &language.plural("count.recipes", self.recipes, &[]),
+                &language.plural("count.ingredients", self.ingredients, &[]),
+                &language.plural("count.labels", self.labels, &[]),
+            ],
+        )
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Default)]
+pub enum ConnectionStatus {
+    #[default]
+    Unknown,
+    Testing,
+    Reachable(ConnectionReport),
+    Unreachable(String),
+    // The server refused the request, its credentials are asked for
+    AuthRequired(String),
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum ProbeError {
+    Unauthorized,
+    Failed(String),
+}
+
+// Name no recipe has, so that the timed request gets an empty answer
+static LATENCY_PATTERN: &str = "spoon-connection-test";
+
+// Query the index endpoints of the server with `credentials`. All of them
+// answering in the format ladle expects means the server speaks a compatible
+// API. The latency is that of a single request with an empty answer.
+pub async fn probe(
+    url: &str,
+    credentials: &Credentials,
+    language: Language,
+) -> Result<ConnectionReport, ProbeError> {
+    // Keep the stored credentials of the origin when none are given
+    if *credentials != Credentials::None {
+        grant(url, credentials);
+    }
+    let backend = HttpBackend::new(url);
+
+    let incompatible = |endpoint: &str, error: Error| match error {
+        Error::Unauthorized(_) => ProbeError::Unauthorized,
+        error => ProbeError::Failed(language.tf(
+            "connection.incompatible",
+            &[&endpoint, &url, &error.describe(language)],
+        )),
+    };
+
+    let start = js_sys::Date::now();
+    backend
+        .recipe_index(LATENCY_PATTERN)
+        .await
+        .map_err(|e| incompatible("recipes", e))?;
+    let latency = js_sys::Date::now() - start;
+
+    let (recipes, ingredients, labels) = join!(
+        backend.recipe_index(""),
+        backend.ingredient_index(""),
+        backend.label_index("")
+    );
+
+    Ok(ConnectionReport {
+        latency,
+        recipes: recipes.map_err(|e| incompatible("recipes", e))?.len(),
+        ingredients: ingredients
+            .map_err(|e| incompatible("ingredients", e))?
+            .len(),
+        labels: labels.map_err(|e| incompatible("labels", e))?.len(),
+    })
+}
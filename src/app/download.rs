@@ -0,0 +1,44 @@
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+// Have the browser save `contents` to a file named `filename`
+pub fn download(filename: &str, mime: &str, contents: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or(JsValue::from_str("No document available"))?;
+
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let anchor = document
+        .create_element("a")?
+        .unchecked_into::<HtmlAnchorElement>();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
+
+// Turn a recipe name into something usable as a file name
+pub fn file_name(name: &str, extension: &str) -> String {
+    let stem: String = unidecode::unidecode(name)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    let stem = stem
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    match stem.len() {
+        0 => format!("recipe.{}", extension),
+        _ => format!("{}.{}", stem, extension),
+    }
+}
@@ -0,0 +1,285 @@
+// English catalog, also used for keys missing from the other catalogs
+pub static MESSAGES: &[(&str, &str)] = &[
+    ("action.add", "Add"),
+    ("action.cancel", "Cancel"),
+    ("action.close", "Close"),
+    ("action.delete", "Delete"),
+    ("action.edit", "Edit"),
+    ("action.next", "Next"),
+    ("action.reset", "Reset"),
+    ("action.retry", "Retry"),
+    ("action.save", "Save"),
+    ("action.submit", "Submit"),
+    ("action.undo", "Undo"),
+    ("action.update", "Update"),
+    ("auth.basic", "Username and password"),
+    ("auth.bearer", "Bearer token"),
+    ("auth.method", "Authentication:"),
+    ("auth.none", "None"),
+    ("auth.password", "Password:"),
+    (
+        "auth.refused",
+        "The server refused access with these credentials.",
+    ),
+    ("auth.required", "The server requires authentication."),
+    (
+        "auth.session_only",
+        "Forget credentials when the browser closes",
+    ),
+    ("auth.token", "Token:"),
+    ("auth.username", "Username:"),
+    ("backup.download", "Download backup"),
+    ("backup.restore", "Restore"),
+    ("backup.restore_from", "Restore from archive: "),
+    ("backup.restored", "Archive restored"),
+    (
+        "backup.restored_errors.one",
+        "Archive restored with {} error",
+    ),
+    (
+        "backup.restored_errors.other",
+        "Archive restored with {} errors",
+    ),
+    ("backup.saved", "Saved {}, {} and {}"),
+    (
+        "backup.unsupported_version",
+        "Archive version {} is not supported by this version of spoon",
+    ),
+    ("classification.animal_product", "animal products"),
+    ("classification.dairy", "dairy"),
+    ("classification.dairy_free", "Dairy-free"),
+    ("classification.gluten", "gluten"),
+    ("classification.gluten_free", "Gluten-free"),
+    ("classification.meat", "meat"),
+    ("classification.vegan", "Vegan"),
+    ("classification.vegetarian", "Vegetarian"),
+    (
+        "connection.incompatible",
+        "Failed to query {} on {}, is it a compatible knife server ? ({})",
+    ),
+    ("connection.report", "Connected in {} ms: {}, {}, {}"),
+    ("count.ingredients.one", "{} ingredient"),
+    ("count.ingredients.other", "{} ingredients"),
+    ("count.labels.one", "{} label"),
+    ("count.labels.other", "{} labels"),
+    ("count.recipes.one", "{} recipe"),
+    ("count.recipes.other", "{} recipes"),
+    ("edit.add_dependency", "Add a required recipe"),
+    ("edit.delete_confirm", "Delete recipe ?"),
+    (
+        "edit.delete_used_confirm",
+        "This recipe is used in: {}.\nDelete recipe anyway ?",
+    ),
+    ("edit.ingredient", "Ingredient"),
+    ("edit.new_ingredient", "New ingredient"),
+    ("edit.quantity", "Quantity"),
+    ("edit.recipe", "Recipe"),
+    ("edit.save_before_exit", "Save before exiting ?"),
+    ("edit.saved", "Recipe saved"),
+    (
+        "error.network",
+        "Could not reach the server, check your connection ({})",
+    ),
+    ("error.not_found", "Not found: {}"),
+    (
+        "error.server",
+        "The server failed to handle the request ({})",
+    ),
+    ("error.timeout", "The server took too long to answer"),
+    (
+        "error.unauthorized",
+        "The server refused the credentials ({})",
+    ),
+    ("header.ingredients", "Ingredients"),
+    ("header.recipes", "Recipes"),
+    ("header.refresh", "Reload data from the server"),
+    ("header.settings", "Settings"),
+    ("import.cooklang", "Import .cook"),
+    ("import.cooklang_placeholder", "Paste a Cooklang recipe"),
+    ("import.default_name", "Imported recipe"),
+    ("import.import", "Import"),
+    ("import.json_ld", "Import JSON-LD"),
+    (
+        "import.json_ld_placeholder",
+        "Paste schema.org JSON-LD or a saved HTML page",
+    ),
+    ("import.new_ingredient", "New ingredient: {}"),
+    ("import.no_cooklang", "No recipe found in Cooklang source"),
+    ("import.no_json_ld", "No JSON-LD data found"),
+    ("import.no_recipe", "No schema.org Recipe found"),
+    (
+        "import.partial.one",
+        "Recipe imported, {} element could not be added: {}",
+    ),
+    (
+        "import.partial.other",
+        "Recipe imported, {} elements could not be added: {}",
+    ),
+    ("import.skip", "Skip"),
+    ("ingredient.add", "Add ingredient"),
+    ("ingredient.contains", "Contains: {}."),
+    (
+        "ingredient.contains_animal_product",
+        "contains animal products",
+    ),
+    ("ingredient.contains_dairy", "contains dairy"),
+    ("ingredient.contains_gluten", "contains gluten"),
+    ("ingredient.contains_meat", "contains meat"),
+    ("ingredient.delete_confirm", "Delete ingredient ?"),
+    ("ingredient.name_prompt", "Ingredient name:"),
+    ("ingredient.updated", "Ingredient updated"),
+    ("ingredient.used_in.one", "Used in {} recipe:"),
+    ("ingredient.used_in.other", "Used in {} recipes:"),
+    ("markdown.author", "*by {}*"),
+    ("markdown.ingredient", "Ingredient"),
+    ("markdown.optional", "(optional)"),
+    ("markdown.quantity", "Quantity"),
+    ("migration.copy", "Copy"),
+    ("migration.dependency", " (dependency)"),
+    ("migration.done", "Recipes copied"),
+    ("migration.done_errors.one", "Recipes copied with {} error"),
+    (
+        "migration.done_errors.other",
+        "Recipes copied with {} errors",
+    ),
+    ("migration.kept", "Recipes already on the target server"),
+    (
+        "migration.matched",
+        "Ingredients matched on the target server",
+    ),
+    ("migration.new_ingredients", "Ingredients to create"),
+    ("migration.page_title", "Migration"),
+    ("migration.plan", "Plan"),
+    (
+        "migration.same_target",
+        "Choose a target server different from the current one",
+    ),
+    ("migration.target", "Target server url:"),
+    ("migration.title", "Copy recipes to another server"),
+    ("migration.to_copy", "Recipes to copy"),
+    ("notifications.clear", "Clear"),
+    ("notifications.dismiss", "Dismiss"),
+    ("notifications.empty", "No notifications"),
+    ("notifications.title", "Notifications"),
+    ("palette.action", "Action"),
+    ("palette.create_ingredient", "Create an ingredient"),
+    ("palette.create_recipe", "Create a recipe"),
+    ("palette.edit_recipe", "Edit this recipe"),
+    ("palette.empty", "Nothing matches"),
+    ("palette.ingredient", "Ingredient"),
+    ("palette.label", "Label"),
+    ("palette.open_settings", "Open the settings"),
+    (
+        "palette.placeholder",
+        "Search recipes, ingredients, labels and actions...",
+    ),
+    ("palette.recipe", "Recipe"),
+    ("plan.dependencies.one", "{} dependency to create"),
+    ("plan.dependencies.other", "{} dependencies to create"),
+    (
+        "plan.ingredients.one",
+        "{} ingredient to create, {} already present",
+    ),
+    (
+        "plan.ingredients.other",
+        "{} ingredients to create, {} already present",
+    ),
+    (
+        "plan.recipes.one",
+        "{} recipe to create, {} already present and left untouched",
+    ),
+    (
+        "plan.recipes.other",
+        "{} recipes to create, {} already present and left untouched",
+    ),
+    ("plan.requirements.one", "{} requirement to create"),
+    ("plan.requirements.other", "{} requirements to create"),
+    ("plan.tags.one", "{} tag to apply"),
+    ("plan.tags.other", "{} tags to apply"),
+    ("profile.contains", "Contains {}"),
+    ("profile.unchecked", "Diet not checked yet"),
+    ("push.button", "Push browser data to {}"),
+    ("push.done", "Data pushed to {}"),
+    ("push.done_errors.one", "Data pushed with {} error"),
+    ("push.done_errors.other", "Data pushed with {} errors"),
+    ("push.push", "Push"),
+    ("recipe.add", "Add recipe"),
+    ("recipe.directions", "Directions"),
+    ("recipe.ingredients", "Ingredients"),
+    ("recipe.loading", "Loading"),
+    ("recipe.name_prompt", "Recipe name:"),
+    ("recipe.no_data", "No data"),
+    ("recipe.no_substitution", "No substitution"),
+    ("recipe.not_classified", "Not {}: {}"),
+    ("recipe.offender_via", "{}, via {}"),
+    ("recipe.optional", "Optional"),
+    ("recipe.restrictions", "Dietary restrictions"),
+    ("recipe.used_in", "Used in"),
+    ("restore.dependency", "Dependency {} of {}: {}"),
+    ("restore.ingredient", "Ingredient {}: {}"),
+    ("restore.label", "Label {}: {}"),
+    ("restore.recipe", "Recipe {}: {}"),
+    ("restore.requirement", "Requirement {} of {}: {}"),
+    ("restore.unknown_ingredient", "unknown ingredient"),
+    ("restore.unknown_recipe", "unknown recipe"),
+    ("search.labels", "labels"),
+    ("search.placeholder", "Search recipes ..."),
+    ("servers.name", "Name"),
+    ("servers.use", "Use"),
+    ("settings.attempts", "Attempts when loading data:"),
+    ("settings.avoid_animal_product", "Avoid animal products"),
+    ("settings.avoid_dairy", "Avoid dairy"),
+    ("settings.avoid_gluten", "Avoid gluten"),
+    ("settings.avoid_ingredient", "Avoid ingredient"),
+    ("settings.avoid_meat", "Avoid meat"),
+    ("settings.backup", "Backup"),
+    ("settings.cache_ttl", "Keep answers for (seconds):"),
+    ("settings.force", "Save anyway"),
+    ("settings.hide_incompatible", "Hide incompatible recipes"),
+    ("settings.language", "Language:"),
+    (
+        "settings.local",
+        "Store data in this browser instead of a knife server",
+    ),
+    ("settings.profile", "Diet profile"),
+    ("settings.requests", "Requests"),
+    ("settings.server_url", "Knife server url:"),
+    ("settings.servers", "Servers"),
+    ("settings.test", "Test connection"),
+    ("settings.testing", "Testing connection..."),
+    ("settings.theme", "Theme:"),
+    ("settings.timeout", "Timeout (seconds):"),
+    (
+        "settings.unreachable",
+        "{}. Check 'Save anyway' to use this url regardless.",
+    ),
+    (
+        "shortcuts.add_requirement",
+        "Open the ingredient row, then add the ingredient",
+    ),
+    (
+        "shortcuts.close",
+        "Close the editor, offering to save changes",
+    ),
+    ("shortcuts.help", "Show or hide this help"),
+    ("shortcuts.navigate", "Move between ingredient rows"),
+    ("shortcuts.palette", "Open the command palette"),
+    ("shortcuts.save", "Save the recipe"),
+    ("shortcuts.title", "Keyboard shortcuts"),
+    ("substitutions.invalid_ratio", "Invalid ratio: {}"),
+    ("substitutions.note", "Note"),
+    ("substitutions.ratio", "Ratio"),
+    ("substitutions.replacement", "Replacement"),
+    ("substitutions.title", "Substitutions"),
+    ("theme.dark", "Dark"),
+    ("theme.high_contrast", "High contrast"),
+    ("theme.light", "Light"),
+    ("theme.system", "Follow the system"),
+    ("url.empty", "The server url is empty"),
+    ("url.invalid", "'{}' is not a valid url"),
+    ("url.no_host", "'{}' has no host"),
+    (
+        "url.protocol",
+        "Unsupported protocol '{}', use http or https",
+    ),
+];
@@ -0,0 +1,317 @@
+pub static MESSAGES: &[(&str, &str)] = &[
+    ("action.add", "Ajouter"),
+    ("action.cancel", "Annuler"),
+    ("action.close", "Fermer"),
+    ("action.delete", "Supprimer"),
+    ("action.edit", "Modifier"),
+    ("action.next", "Suivant"),
+    ("action.reset", "Réinitialiser"),
+    ("action.retry", "Réessayer"),
+    ("action.save", "Enregistrer"),
+    ("action.submit", "Valider"),
+    ("action.undo", "Annuler"),
+    ("action.update", "Mettre à jour"),
+    ("auth.basic", "Nom d'utilisateur et mot de passe"),
+    ("auth.bearer", "Jeton d'accès"),
+    ("auth.method", "Authentification :"),
+    ("auth.none", "Aucune"),
+    ("auth.password", "Mot de passe :"),
+    (
+        "auth.refused",
+        "Le serveur a refusé l'accès avec ces identifiants.",
+    ),
+    ("auth.required", "Le serveur demande une authentification."),
+    (
+        "auth.session_only",
+        "Oublier les identifiants à la fermeture du navigateur",
+    ),
+    ("auth.token", "Jeton :"),
+    ("auth.username", "Nom d'utilisateur :"),
+    ("backup.download", "Télécharger une sauvegarde"),
+    ("backup.restore", "Restaurer"),
+    ("backup.restore_from", "Restaurer depuis une archive : "),
+    ("backup.restored", "Archive restaurée"),
+    (
+        "backup.restored_errors.one",
+        "Archive restaurée avec {} erreur",
+    ),
+    (
+        "backup.restored_errors.other",
+        "Archive restaurée avec {} erreurs",
+    ),
+    ("backup.saved", "{}, {} et {} sauvegardés"),
+    (
+        "backup.unsupported_version",
+        "La version {} de l'archive n'est pas prise en charge par cette version de spoon",
+    ),
+    ("classification.animal_product", "produit animalier"),
+    ("classification.dairy", "produit laitier"),
+    ("classification.dairy_free", "Sans lactose"),
+    ("classification.gluten", "gluten"),
+    ("classification.gluten_free", "Sans gluten"),
+    ("classification.meat", "viande"),
+    ("classification.vegan", "Végétalien"),
+    ("classification.vegetarian", "Végétarien"),
+    (
+        "connection.incompatible",
+        "Échec de la requête {} sur {}, est-ce un serveur knife compatible ? ({})",
+    ),
+    ("connection.report", "Connecté en {} ms : {}, {}, {}"),
+    ("count.ingredients.one", "{} ingrédient"),
+    ("count.ingredients.other", "{} ingrédients"),
+    ("count.labels.one", "{} étiquette"),
+    ("count.labels.other", "{} étiquettes"),
+    ("count.recipes.one", "{} recette"),
+    ("count.recipes.other", "{} recettes"),
+    ("edit.add_dependency", "Ajouter une recette nécessaire"),
+    ("edit.delete_confirm", "Supprimer la recette ?"),
+    (
+        "edit.delete_used_confirm",
+        "Cette recette est utilisée dans : {}.\nSupprimer la recette malgré tout ?",
+    ),
+    ("edit.ingredient", "Ingrédient"),
+    ("edit.new_ingredient", "Nouvel ingrédient"),
+    ("edit.quantity", "Quantité"),
+    ("edit.recipe", "Recette"),
+    ("edit.save_before_exit", "Enregistrer avant de quitter ?"),
+    ("edit.saved", "Recette sauvegardée"),
+    (
+        "error.network",
+        "Impossible de joindre le serveur, vérifiez votre connexion ({})",
+    ),
+    ("error.not_found", "Introuvable : {}"),
+    (
+        "error.server",
+        "Le serveur n'a pas pu traiter la requête ({})",
+    ),
+    ("error.timeout", "Le serveur a mis trop de temps à répondre"),
+    (
+        "error.unauthorized",
+        "Le serveur a refusé les identifiants ({})",
+    ),
+    ("header.ingredients", "Ingrédients"),
+    ("header.recipes", "Recettes"),
+    ("header.refresh", "Recharger les données du serveur"),
+    ("header.settings", "Réglages"),
+    ("import.cooklang", "Importer un .cook"),
+    ("import.cooklang_placeholder", "Coller une recette Cooklang"),
+    ("import.default_name", "Recette importée"),
+    ("import.import", "Importer"),
+    ("import.json_ld", "Importer du JSON-LD"),
+    (
+        "import.json_ld_placeholder",
+        "Coller du JSON-LD schema.org ou une page HTML enregistrée",
+    ),
+    ("import.new_ingredient", "Nouvel ingrédient : {}"),
+    (
+        "import.no_cooklang",
+        "Aucune recette trouvée dans la source Cooklang",
+    ),
+    ("import.no_json_ld", "Aucune donnée JSON-LD trouvée"),
+    ("import.no_recipe", "Aucune recette schema.org trouvée"),
+    (
+        "import.partial.one",
+        "Recette importée, {} élément n'a pas pu être ajouté : {}",
+    ),
+    (
+        "import.partial.other",
+        "Recette importée, {} éléments n'ont pas pu être ajoutés : {}",
+    ),
+    ("import.skip", "Ignorer"),
+    ("ingredient.add", "Ajouter un ingrédient"),
+    ("ingredient.contains", "Contient : {}."),
+    (
+        "ingredient.contains_animal_product",
+        "contient des produits animaliers",
+    ),
+    (
+        "ingredient.contains_dairy",
+        "contient des produits laitiers",
+    ),
+    ("ingredient.contains_gluten", "contient du gluten"),
+    ("ingredient.contains_meat", "contient de la viande"),
+    ("ingredient.delete_confirm", "Supprimer l'ingrédient ?"),
+    ("ingredient.name_prompt", "Nom de l'ingrédient :"),
+    ("ingredient.updated", "Ingrédient mis à jour"),
+    ("ingredient.used_in.one", "Utilisé dans {} recette :"),
+    ("ingredient.used_in.other", "Utilisé dans {} recettes :"),
+    ("markdown.author", "*par {}*"),
+    ("markdown.ingredient", "Ingrédient"),
+    ("markdown.optional", "(facultatif)"),
+    ("markdown.quantity", "Quantité"),
+    ("migration.copy", "Copier"),
+    ("migration.dependency", " (dépendance)"),
+    ("migration.done", "Recettes copiées"),
+    (
+        "migration.done_errors.one",
+        "Recettes copiées avec {} erreur",
+    ),
+    (
+        "migration.done_errors.other",
+        "Recettes copiées avec {} erreurs",
+    ),
+    (
+        "migration.kept",
+        "Recettes déjà présentes sur le serveur cible",
+    ),
+    (
+        "migration.matched",
+        "Ingrédients retrouvés sur le serveur cible",
+    ),
+    ("migration.new_ingredients", "Ingrédients à créer"),
+    ("migration.page_title", "Migration"),
+    ("migration.plan", "Préparer"),
+    (
+        "migration.same_target",
+        "Choisissez un serveur cible différent du serveur actuel",
+    ),
+    ("migration.target", "Url du serveur cible :"),
+    (
+        "migration.title",
+        "Copier des recettes vers un autre serveur",
+    ),
+    ("migration.to_copy", "Recettes à copier"),
+    ("notifications.clear", "Effacer"),
+    ("notifications.dismiss", "Fermer"),
+    ("notifications.empty", "Aucune notification"),
+    ("notifications.title", "Notifications"),
+    ("palette.action", "Action"),
+    ("palette.create_ingredient", "Créer un ingrédient"),
+    ("palette.create_recipe", "Créer une recette"),
+    ("palette.edit_recipe", "Modifier cette recette"),
+    ("palette.empty", "Aucun résultat"),
+    ("palette.ingredient", "Ingrédient"),
+    ("palette.label", "Étiquette"),
+    ("palette.open_settings", "Ouvrir les réglages"),
+    (
+        "palette.placeholder",
+        "Chercher des recettes, ingrédients, étiquettes et actions...",
+    ),
+    ("palette.recipe", "Recette"),
+    ("plan.dependencies.one", "{} dépendance à créer"),
+    ("plan.dependencies.other", "{} dépendances à créer"),
+    (
+        "plan.ingredients.one",
+        "{} ingrédient à créer, {} déjà présents",
+    ),
+    (
+        "plan.ingredients.other",
+        "{} ingrédients à créer, {} déjà présents",
+    ),
+    (
+        "plan.recipes.one",
+        "{} recette à créer, {} déjà présentes et laissées intactes",
+    ),
+    (
+        "plan.recipes.other",
+        "{} recettes à créer, {} déjà présentes et laissées intactes",
+    ),
+    ("plan.requirements.one", "{} ingrédient de recette à créer"),
+    (
+        "plan.requirements.other",
+        "{} ingrédients de recette à créer",
+    ),
+    ("plan.tags.one", "{} étiquette à appliquer"),
+    ("plan.tags.other", "{} étiquettes à appliquer"),
+    ("profile.contains", "Contient : {}"),
+    ("profile.unchecked", "Régime pas encore vérifié"),
+    ("push.button", "Envoyer les données du navigateur vers {}"),
+    ("push.done", "Données envoyées vers {}"),
+    ("push.done_errors.one", "Données envoyées avec {} erreur"),
+    ("push.done_errors.other", "Données envoyées avec {} erreurs"),
+    ("push.push", "Envoyer"),
+    ("recipe.add", "Ajouter une recette"),
+    ("recipe.directions", "Préparation"),
+    ("recipe.ingredients", "Ingrédients"),
+    ("recipe.loading", "Chargement"),
+    ("recipe.name_prompt", "Nom de la recette :"),
+    ("recipe.no_data", "Aucune donnée"),
+    ("recipe.no_substitution", "Pas de substitution"),
+    ("recipe.not_classified", "Pas {} : {}"),
+    ("recipe.offender_via", "{}, via {}"),
+    ("recipe.optional", "Facultatif"),
+    ("recipe.restrictions", "Restrictions alimentaires"),
+    ("recipe.used_in", "Utilisée dans"),
+    ("restore.dependency", "Dépendance {} de {} : {}"),
+    ("restore.ingredient", "Ingrédient {} : {}"),
+    ("restore.label", "Étiquette {} : {}"),
+    ("restore.recipe", "Recette {} : {}"),
+    ("restore.requirement", "Ingrédient {} de {} : {}"),
+    ("restore.unknown_ingredient", "ingrédient inconnu"),
+    ("restore.unknown_recipe", "recette inconnue"),
+    ("search.labels", "étiquettes"),
+    ("search.placeholder", "Chercher une recette ..."),
+    ("servers.name", "Nom"),
+    ("servers.use", "Utiliser"),
+    (
+        "settings.attempts",
+        "Tentatives au chargement des données :",
+    ),
+    (
+        "settings.avoid_animal_product",
+        "Éviter les produits animaliers",
+    ),
+    ("settings.avoid_dairy", "Éviter les produits laitiers"),
+    ("settings.avoid_gluten", "Éviter le gluten"),
+    ("settings.avoid_ingredient", "Éviter un ingrédient"),
+    ("settings.avoid_meat", "Éviter la viande"),
+    ("settings.backup", "Sauvegarde"),
+    (
+        "settings.cache_ttl",
+        "Garder les réponses pendant (secondes) :",
+    ),
+    ("settings.force", "Enregistrer quand même"),
+    (
+        "settings.hide_incompatible",
+        "Masquer les recettes incompatibles",
+    ),
+    ("settings.language", "Langue :"),
+    (
+        "settings.local",
+        "Stocker les données dans ce navigateur plutôt que sur un serveur knife",
+    ),
+    ("settings.profile", "Profil alimentaire"),
+    ("settings.requests", "Requêtes"),
+    ("settings.server_url", "Url du serveur knife :"),
+    ("settings.servers", "Serveurs"),
+    ("settings.test", "Tester la connexion"),
+    ("settings.testing", "Test de la connexion..."),
+    ("settings.theme", "Thème :"),
+    ("settings.timeout", "Délai d'expiration (secondes) :"),
+    (
+        "settings.unreachable",
+        "{}. Cochez « Enregistrer quand même » pour utiliser cette url malgré tout.",
+    ),
+    (
+        "shortcuts.add_requirement",
+        "Ouvrir la ligne d'ingrédient, puis ajouter l'ingrédient",
+    ),
+    (
+        "shortcuts.close",
+        "Fermer l'éditeur, en proposant d'enregistrer les modifications",
+    ),
+    ("shortcuts.help", "Afficher ou masquer cette aide"),
+    (
+        "shortcuts.navigate",
+        "Passer d'une ligne d'ingrédient à l'autre",
+    ),
+    ("shortcuts.palette", "Ouvrir la palette de commandes"),
+    ("shortcuts.save", "Enregistrer la recette"),
+    ("shortcuts.title", "Raccourcis clavier"),
+    ("substitutions.invalid_ratio", "Ratio invalide : {}"),
+    ("substitutions.note", "Note"),
+    ("substitutions.ratio", "Ratio"),
+    ("substitutions.replacement", "Remplacement"),
+    ("substitutions.title", "Substitutions"),
+    ("theme.dark", "Sombre"),
+    ("theme.high_contrast", "Contraste élevé"),
+    ("theme.light", "Clair"),
+    ("theme.system", "Suivre le système"),
+    ("url.empty", "L'adresse du serveur est vide"),
+    ("url.invalid", "« {} » n'est pas une adresse valide"),
+    ("url.no_host", "« {} » n'a pas d'hôte"),
+    (
+        "url.protocol",
+        "Protocole « {} » non pris en charge, utilisez http ou https",
+    ),
+];
@@ -0,0 +1,201 @@
+mod en;
+mod fr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use wasm_bindgen::JsValue;
+
+// Language of the interface. Texts are looked up by key in the catalog of
+// the language, falling back to English, then to the key itself.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Language {
+    English,
+    French,
+}
+
+impl Default for Language {
+    // Follow the browser until a language is picked in the settings
+    fn default() -> Self {
+        let browser = web_sys::window().and_then(|window| window.navigator().language());
+        match browser {
+            Some(code) if code.starts_with("fr") => Language::French,
+            _ => Language::English,
+        }
+    }
+}
+
+impl Language {
+    pub fn all() -> [Language; 2] {
+        [Language::English, Language::French]
+    }
+
+    // BCP 47 code, used by the browser to format dates
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+        }
+    }
+
+    // Name of the language in itself
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => en::MESSAGES,
+            Language::French => fr::MESSAGES,
+        }
+    }
+
+    fn lookup(self, key: &str) -> Option<&'static str> {
+        let find = |catalog: &'static [(&'static str, &'static str)]| {
+            catalog
+                .iter()
+                .find(|(entry, _)| *entry == key)
+                .map(|(_, text)| *text)
+        };
+        find(self.catalog()).or_else(|| find(en::MESSAGES))
+    }
+
+    pub fn t(self, key: &'static str) -> &'static str {
+        self.lookup(key).unwrap_or(key)
+    }
+
+    // Text with each `{}` replaced by the arguments, in order
+    pub fn tf(self, key: &'static str, args: &[&dyn Display]) -> String {
+        fill(self.t(key), args)
+    }
+
+    // Text in the form matching `count`, found under `<key>.one` or
+    // `<key>.other`. The count replaces the first `{}`.
+    pub fn plural(self, key: &'static str, count: usize, args: &[&dyn Display]) -> String {
+        let form = match (self, count) {
+            (Language::English, 1) => "one",
+            // French uses the singular for zero as well
+            (Language::French, 0 | 1) => "one",
+            _ => "other",
+        };
+        let text = self.lookup(&format!("{}.{}", key, form)).unwrap_or(key);
+
+        let mut all: Vec<&dyn Display> = vec![&count];
+        all.extend_from_slice(args);
+        fill(text, &all)
+    }
+
+    // Date and time in the conventions of the language and the timezone of
+    // the browser
+    pub fn date(self, time: &DateTime<Utc>) -> String {
+        let date = js_sys::Date::new(&JsValue::from_f64(time.timestamp_millis() as f64));
+        date.to_locale_string(self.code(), &JsValue::UNDEFINED)
+            .into()
+    }
+}
+
+fn fill(text: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut parts = text.split("{}");
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for (index, part) in parts.enumerate() {
+        match args.get(index) {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+// Text built before the language it is shown in is known, such as an error,
+// kept as a catalog key and the arguments to fill it with
+#[derive(Clone, Debug, PartialEq)]
+pub struct Text {
+    key: &'static str,
+    args: Vec<String>,
+}
+
+impl Text {
+    pub fn new(key: &'static str, args: &[&dyn Display]) -> Self {
+        Text {
+            key,
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    pub fn translate(&self, language: Language) -> String {
+        let args: Vec<&dyn Display> = self.args.iter().map(|arg| arg as &dyn Display).collect();
+        language.tf(self.key, &args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_replaces_placeholders_in_order() {
+        assert_eq!(fill("{} of {}", &[&1, &"two"]), "1 of two");
+        assert_eq!(fill("{}{}", &[&"a", &"b"]), "ab");
+        assert_eq!(fill("No placeholder", &[&1]), "No placeholder");
+    }
+
+    #[test]
+    fn fill_keeps_placeholders_without_argument() {
+        assert_eq!(fill("{} and {}", &[&"one"]), "one and {}");
+        assert_eq!(fill("{}", &[]), "{}");
+    }
+
+    #[test]
+    fn plural_in_english() {
+        let english = Language::English;
+        assert_eq!(english.plural("count.recipes", 0, &[]), "0 recipes");
+        assert_eq!(english.plural("count.recipes", 1, &[]), "1 recipe");
+        assert_eq!(english.plural("count.recipes", 2, &[]), "2 recipes");
+    }
+
+    #[test]
+    fn plural_in_french_uses_the_singular_for_zero() {
+        let french = Language::French;
+        assert_eq!(french.plural("count.recipes", 0, &[]), "0 recette");
+        assert_eq!(french.plural("count.recipes", 1, &[]), "1 recette");
+        assert_eq!(french.plural("count.recipes", 2, &[]), "2 recettes");
+    }
+
+    #[test]
+    fn plural_fills_the_other_arguments() {
+        assert_eq!(
+            Language::English.plural("import.partial", 2, &[&"a, b"]),
+            "Recipe imported, 2 elements could not be added: a, b"
+        );
+    }
+
+    #[test]
+    fn missing_keys_are_shown_as_is() {
+        assert_eq!(Language::French.t("no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn text_is_translated_when_shown() {
+        let text = Text::new("url.no_host", &[&"http://"]);
+        assert_eq!(text.translate(Language::English), "'http://' has no host");
+        assert_eq!(
+            text.translate(Language::French),
+            "« http:// » n'a pas d'hôte"
+        );
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys() {
+        let keys = |catalog: &[(&str, &str)]| -> Vec<String> {
+            catalog.iter().map(|(key, _)| key.to_string()).collect()
+        };
+        assert_eq!(keys(en::MESSAGES), keys(fr::MESSAGES));
+    }
+}
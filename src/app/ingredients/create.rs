@@ -1,71 +1,70 @@
 use crate::app::status_bar::Message;
+use crate::app::sync::CacheChange;
 use crate::app::AppContext;
 use crate::app::Route;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-static INGREDIENT_NAME_PROMPT: &str = "Ingredient name:";
-
 #[derive(Clone, Default)]
 struct IngredientCreateState {}
 
 #[derive(Properties, PartialEq, Clone)]
-pub struct IngredientCreateProps {
-    pub ingredient_cache_refresh: Callback<()>,
-}
+pub struct IngredientCreateProps {}
 
-#[function_component(IngredientCreateButton)]
-pub fn ingredient_create_button(props: &IngredientCreateProps) -> Html {
-    let _state = use_state(IngredientCreateState::default);
-    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
-    let navigator = use_navigator().unwrap();
+// Ask for a name, then create the ingredient and open it
+pub fn create_ingredient_prompt(context: &AppContext, navigator: &Navigator) {
+    let name = match web_sys::window()
+        .unwrap()
+        .prompt_with_message(context.settings.language.t("ingredient.name_prompt"))
+    {
+        Ok(Some(name)) => name,
+        Ok(None) => return,
+        Err(error) => {
+            context.status.emit(Message::Error(
+                error.as_string().unwrap_or(String::default()),
+                chrono::Utc::now(),
+            ));
+            return;
+        }
+    };
 
     let context_cloned = context.clone();
-    let props_cloned = props.clone();
-    let name_submit = Callback::from(move |name: String| {
-        let context_cloned = context_cloned.clone();
-        let props_cloned = props_cloned.clone();
-        let nc = navigator.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            match ladle::ingredient_create(
-                context_cloned.settings.server_url.as_str(),
-                name.as_str(),
-                false,
-                false,
-                false,
-                false,
-            )
+    let nc = navigator.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        match context_cloned
+            .backend
+            .ingredient_create(name.as_str(), false, false, false, false)
             .await
-            {
-                Ok(ingredient) => {
-                    nc.push(&Route::ShowIngredient { id: ingredient.id });
-                    props_cloned.ingredient_cache_refresh.emit(());
-                }
-                Err(error) => context_cloned
-                    .status
-                    .emit(Message::Error(error.to_string(), chrono::Utc::now())),
-            }
-        });
-    });
-
-    let context_cloned = context.clone();
-    let name_prompt = Callback::from(move |_| {
-        match web_sys::window()
-            .unwrap()
-            .prompt_with_message(INGREDIENT_NAME_PROMPT)
         {
-            Ok(Some(name)) => name_submit.emit(name),
-            Ok(None) => (),
+            Ok(ingredient) => {
+                nc.push(&Route::ShowIngredient {
+                    id: ingredient.id.clone(),
+                });
+                context_cloned
+                    .update_cache
+                    .emit(CacheChange::IngredientSaved(ingredient));
+            }
             Err(error) => context_cloned.status.emit(Message::Error(
-                error.as_string().unwrap_or(String::default()),
+                error.describe(context_cloned.settings.language),
                 chrono::Utc::now(),
             )),
         }
     });
+}
+
+#[function_component(IngredientCreateButton)]
+pub fn ingredient_create_button(_props: &IngredientCreateProps) -> Html {
+    let _state = use_state(IngredientCreateState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let navigator = use_navigator().unwrap();
+
+    let context_cloned = context.clone();
+    let name_prompt =
+        Callback::from(move |_| create_ingredient_prompt(&context_cloned, &navigator));
 
     html! {
         <button class="create-item create-ingredient" onclick={name_prompt}>
-            {"Add ingredient"}
+            {context.settings.language.t("ingredient.add")}
         </button>
     }
 }
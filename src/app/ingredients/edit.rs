@@ -1,4 +1,9 @@
-use crate::app::{status_bar::Message, AppContext, Route};
+use crate::app::backend::Error;
+use crate::app::ingredients::substitutions::{Substitution, SubstitutionList};
+use crate::app::status_bar::{Action, Message};
+use crate::app::sync::CacheChange;
+use crate::app::tasks::use_task_scope;
+use crate::app::{AppContext, Route};
 use std::rc::Rc;
 use wasm_bindgen::JsCast;
 use web_sys::{EventTarget, HtmlInputElement};
@@ -8,7 +13,7 @@ use yew_router::prelude::*;
 #[derive(Properties, PartialEq, Clone)]
 pub struct IngredientEditProps {
     pub ingredient_id: Option<String>,
-    pub ingredient_cache_refresh: Callback<()>,
+    pub update_substitutions: Callback<Vec<Substitution>>,
 }
 
 enum IngredientEditActions {
@@ -67,11 +72,39 @@ impl Reducible for IngredientEditState {
     }
 }
 
+// Send all the fields of an ingredient to the server and update the cache
+async fn save_ingredient(
+    context: &AppContext,
+    ingredient: &ladle::models::Ingredient,
+) -> Result<(), Error> {
+    context
+        .backend
+        .ingredient_update(
+            &ingredient.id,
+            Some(&ingredient.name),
+            Some(ingredient.classifications.dairy),
+            Some(ingredient.classifications.meat),
+            Some(ingredient.classifications.gluten),
+            Some(ingredient.classifications.animal_product),
+        )
+        .await?;
+
+    context.update_cache.emit(CacheChange::IngredientSaved(
+        ladle::models::IngredientIndex {
+            id: ingredient.id.clone(),
+            name: ingredient.name.clone(),
+        },
+    ));
+    Ok(())
+}
+
 #[function_component(IngredientEdit)]
 pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
     let state = use_reducer(IngredientEditState::default);
     let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
     let navigator = use_navigator().unwrap();
+    let tasks = use_task_scope();
 
     let state_cloned = state.clone();
     let context_cloned = context.clone();
@@ -82,17 +115,16 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
             let context_cloned = context_cloned.clone();
             let ingredient_id = ingredient_id.clone();
             if let Some(ingredient_id) = ingredient_id {
-                wasm_bindgen_futures::spawn_local(async move {
-                    match ladle::ingredient_get(&context_cloned.settings.server_url, &ingredient_id)
-                        .await
-                    {
+                tasks.spawn(async move {
+                    match context_cloned.backend.ingredient_get(&ingredient_id).await {
                         Ok(ingredient) => {
                             state_cloned
                                 .dispatch(IngredientEditActions::UpdateIngredient(ingredient));
                         }
-                        Err(message) => context_cloned
-                            .status
-                            .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                        Err(message) => context_cloned.status.emit(Message::Error(
+                            message.describe(context_cloned.settings.language),
+                            chrono::Utc::now(),
+                        )),
                     }
                 });
             }
@@ -106,44 +138,57 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
         let state_cloned = state_cloned.clone();
         let context_cloned = context_cloned.clone();
         wasm_bindgen_futures::spawn_local(async move {
-            if state_cloned.original_ingredient.is_some() {
-                let new = &state_cloned.new_ingredient;
-                match ladle::ingredient_update(
-                    context_cloned.settings.server_url.as_str(),
-                    &new.id,
-                    Some(&new.name),
-                    Some(new.classifications.dairy),
-                    Some(new.classifications.meat),
-                    Some(new.classifications.gluten),
-                    Some(new.classifications.animal_product),
-                )
-                .await
-                {
-                    Ok(_) => context_cloned.status.emit(Message::Success(
-                        String::from("Ingredient updated"),
+            if let Some(original) = &state_cloned.original_ingredient {
+                match save_ingredient(&context_cloned, &state_cloned.new_ingredient).await {
+                    Ok(_) => {
+                        // Offer to put the previous values back
+                        let original = original.clone();
+                        let state_cloned = state_cloned.clone();
+                        let context_undo = context_cloned.clone();
+                        let undo = Callback::from(move |_| {
+                            let original = original.clone();
+                            let state_cloned = state_cloned.clone();
+                            let context_cloned = context_undo.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                match save_ingredient(&context_cloned, &original).await {
+                                    Ok(_) => state_cloned.dispatch(
+                                        IngredientEditActions::UpdateIngredient(original),
+                                    ),
+                                    Err(message) => context_cloned.status.emit(Message::Error(
+                                        message.describe(context_cloned.settings.language),
+                                        chrono::Utc::now(),
+                                    )),
+                                }
+                            });
+                        });
+                        context_cloned.notify.emit((
+                            Message::Success(
+                                language.t("ingredient.updated").to_string(),
+                                chrono::Utc::now(),
+                            ),
+                            Action::new(language.t("action.undo"), undo),
+                        ))
+                    }
+                    Err(message) => context_cloned.status.emit(Message::Error(
+                        message.describe(context_cloned.settings.language),
                         chrono::Utc::now(),
                     )),
-                    Err(message) => context_cloned
-                        .status
-                        .emit(Message::Error(message.to_string(), chrono::Utc::now())),
                 }
             }
         });
     });
 
     let state_cloned = state.clone();
-    let props_cloned = props.clone();
     let context_cloned = context.clone();
     let nc = navigator.clone();
     let on_delete_clicked = Callback::from(move |_| {
         let state_cloned = state_cloned.clone();
-        let props_cloned = props_cloned.clone();
         let context_cloned = context_cloned.clone();
         let nc = nc.clone();
 
         let confirm = match web_sys::window()
             .unwrap()
-            .confirm_with_message(&format!("Delete ingredient ?"))
+            .confirm_with_message(language.t("ingredient.delete_confirm"))
         {
             Ok(true) => true,
             _ => false,
@@ -155,16 +200,17 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
             }
 
             if let Some(ing) = &state_cloned.original_ingredient {
-                match ladle::ingredient_delete(context_cloned.settings.server_url.as_str(), &ing.id)
-                    .await
-                {
+                match context_cloned.backend.ingredient_delete(&ing.id).await {
                     Ok(_) => {
-                        props_cloned.ingredient_cache_refresh.emit(());
+                        context_cloned
+                            .update_cache
+                            .emit(CacheChange::IngredientRemoved(ing.id.clone()));
                         nc.push(&Route::ListIngredients);
                     }
-                    Err(message) => context_cloned
-                        .status
-                        .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                    Err(message) => context_cloned.status.emit(Message::Error(
+                        message.describe(context_cloned.settings.language),
+                        chrono::Utc::now(),
+                    )),
                 }
             }
         });
@@ -220,7 +266,7 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
                             checked={state_cloned.new_ingredient.classifications.gluten} />
                     </td>
                     <td>
-                        {"contains gluten"}
+                        {language.t("ingredient.contains_gluten")}
                     </td>
                 </tr>
                 <tr>
@@ -231,7 +277,7 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
                             checked={state_cloned.new_ingredient.classifications.meat} />
                     </td>
                     <td>
-                        {"contains meat"}
+                        {language.t("ingredient.contains_meat")}
                     </td>
                 </tr>
                 <tr>
@@ -242,7 +288,7 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
                             checked={state_cloned.new_ingredient.classifications.dairy} />
                     </td>
                     <td>
-                        {"contains dairy"}
+                        {language.t("ingredient.contains_dairy")}
                     </td>
                 </tr>
                 <tr>
@@ -253,16 +299,25 @@ pub fn ingredient_edit_window(props: &IngredientEditProps) -> Html {
                             checked={state_cloned.new_ingredient.classifications.animal_product} />
                     </td>
                     <td>
-                        {"contains animal products"}
+                        {language.t("ingredient.contains_animal_product")}
                     </td>
                 </tr>
             </table>
             <div class={"options"}>
-                <button onclick={on_reset_clicked}>{"Reset"}</button>
-                <button onclick={on_update_clicked}>{"Update"}</button>
-                <button onclick={on_delete_clicked}>{"Delete"}</button>
-                <button onclick={Callback::from(move |_| {navigator.back();})}>{"Close"}</button>
+                <button onclick={on_reset_clicked}>{language.t("action.reset")}</button>
+                <button onclick={on_update_clicked}>{language.t("action.update")}</button>
+                <button onclick={on_delete_clicked}>{language.t("action.delete")}</button>
+                <button onclick={Callback::from(move |_| {navigator.back();})}>{language.t("action.close")}</button>
             </div>
+            if let Some(ingredient) = &state_cloned.original_ingredient {
+                <SubstitutionList
+                    ingredient={ladle::models::IngredientIndex {
+                        id: ingredient.id.clone(),
+                        name: ingredient.name.clone(),
+                    }}
+                    update_substitutions={props.update_substitutions.clone()}
+                />
+            }
         </div>
     </div>
     }
@@ -275,6 +330,7 @@ pub struct IngredientEditButtonProps {
 
 #[function_component(IngredientEditButton)]
 pub fn ingredient_edit_button(props: &IngredientEditButtonProps) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
     let navigator = use_navigator().unwrap();
 
     let props_cloned = props.clone();
@@ -286,7 +342,7 @@ pub fn ingredient_edit_button(props: &IngredientEditButtonProps) -> Html {
                     navigator.push(&Route::EditIngredient {id: props_cloned.ingredient_id.clone()});
                 })
             }>
-            {"Edit"}
+            {context.settings.language.t("action.edit")}
         </button>
     }
 }
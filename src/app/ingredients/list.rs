@@ -5,10 +5,13 @@ use yew::prelude::*;
 
 #[function_component(IngredientList)]
 pub fn list() -> Html {
-    set_title("Ingredients - spoon");
-
     let context = use_context::<AppContext>().unwrap_or(AppContext::default());
 
+    set_title(&format!(
+        "{} - spoon",
+        context.settings.language.t("header.ingredients")
+    ));
+
     let mut items: Vec<_> = context.ingredient_cache.iter().collect();
     items.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)));
 
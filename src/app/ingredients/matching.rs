@@ -0,0 +1,107 @@
+use ladle::models::IngredientIndex;
+
+// Reduce a name to lowercase ascii words, dropping plural marks, so that
+// "Œufs", "oeuf" and "OEUF " compare equal
+pub fn normalize(name: &str) -> String {
+    unidecode::unidecode(name)
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(
+            |word| match word.len() > 3 && (word.ends_with('s') || word.ends_with('x')) {
+                true => &word[..word.len() - 1],
+                false => word,
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    format!(" {} ", haystack).contains(&format!(" {} ", needle))
+}
+
+// Find the item whose name best matches `name`: an exact match on the
+// normalized names first, then the longest name contained in `name`
+pub fn best_match<'a, T>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a T>,
+    name_of: impl Fn(&T) -> &str,
+) -> Option<&'a T> {
+    let target = normalize(name);
+    if target.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<(usize, &T)> = None;
+
+    for candidate in candidates {
+        let normalized = normalize(name_of(candidate));
+        if normalized.is_empty() {
+            continue;
+        }
+
+        let score = if normalized == target {
+            usize::MAX
+        } else if contains_words(&target, &normalized) {
+            normalized.len()
+        } else {
+            continue;
+        };
+
+        match best {
+            Some((best_score, _)) if best_score >= score => (),
+            _ => best = Some((score, candidate)),
+        }
+    }
+
+    best.map(|(_, candidate)| candidate)
+}
+
+pub fn match_ingredient<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a IngredientIndex>,
+) -> Option<&'a IngredientIndex> {
+    best_match(name, candidates, |ingredient| ingredient.name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_accents_case_and_plurals() {
+        assert_eq!(normalize("Œufs"), "oeuf");
+        assert_eq!(normalize("OEUF "), "oeuf");
+        assert_eq!(normalize("Pommes de terre"), "pomme de terre");
+        assert_eq!(normalize("Choux"), normalize("chou"));
+        assert_eq!(normalize("crème-fraîche"), "creme fraiche");
+        assert_eq!(normalize(" - "), "");
+    }
+
+    #[test]
+    fn best_match_prefers_exact_names() {
+        let names = ["pomme", "pomme de terre", "terre"];
+
+        assert_eq!(
+            best_match("Pommes de terre", names.iter(), |name| name),
+            Some(&"pomme de terre")
+        );
+        assert_eq!(
+            best_match("pommes", names.iter(), |name| name),
+            Some(&"pomme")
+        );
+    }
+
+    #[test]
+    fn best_match_picks_the_longest_contained_name() {
+        let names = ["sucre", "sucre roux", "roux"];
+
+        assert_eq!(
+            best_match("50 g de sucre roux bio", names.iter(), |name| name),
+            Some(&"sucre roux")
+        );
+        assert_eq!(best_match("sucrette", names.iter(), |name| name), None);
+        assert_eq!(best_match("", names.iter(), |name| name), None);
+    }
+}
@@ -2,4 +2,6 @@ pub mod create;
 pub mod edit;
 pub mod list;
 mod list_item;
+pub mod matching;
 pub mod show;
+pub mod substitutions;
@@ -1,3 +1,5 @@
+use crate::app::i18n::Language;
+use crate::app::tasks::use_task_scope;
 use crate::app::{set_title, AppContext, Message, Route};
 use ladle::models::{Ingredient, RecipeIndex};
 use yew::prelude::*;
@@ -12,7 +14,7 @@ struct IngredientViewState {
     ingredient: Option<Ingredient>,
 }
 
-fn render_ingredient(data: &Ingredient) -> Html {
+fn render_ingredient(data: &Ingredient, language: Language) -> Html {
     let recipes = data
         .used_in
         .iter()
@@ -29,31 +31,25 @@ fn render_ingredient(data: &Ingredient) -> Html {
 
     let mut classifications = vec![];
     if data.classifications.dairy {
-        classifications.push("produit laitier")
+        classifications.push(language.t("classification.dairy"))
     }
     if data.classifications.meat {
-        classifications.push("viande")
+        classifications.push(language.t("classification.meat"))
     }
     if data.classifications.gluten {
-        classifications.push("gluten")
+        classifications.push(language.t("classification.gluten"))
     }
     if data.classifications.animal_product {
-        classifications.push("produit animalier")
+        classifications.push(language.t("classification.animal_product"))
     }
 
-    let trail = match data.used_in.len() {
-        0 => ".",
-        1 => ":",
-        _ => "s:",
-    };
-
     html! {
         <div>
             <h1>{data.name.as_str()}</h1>
             if classifications.len() > 0 {
-                <p>{format!("Contient: {}.", classifications.join(", "))}</p>
+                <p>{language.tf("ingredient.contains", &[&classifications.join(", ")])}</p>
             }
-            <h3>{format!("Used in {} recipe{}", data.used_in.len(), trail)}</h3>
+            <h3>{language.plural("ingredient.used_in", data.used_in.len(), &[])}</h3>
             <ul>
                 {recipes}
             </ul>
@@ -65,6 +61,8 @@ fn render_ingredient(data: &Ingredient) -> Html {
 pub fn view(props: &IngredientViewProps) -> Html {
     let state = use_state(|| IngredientViewState { ingredient: None });
     let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let tasks = use_task_scope();
+    let language = context.settings.language;
 
     let state_cloned = state.clone();
     let context_cloned = context.clone();
@@ -75,17 +73,17 @@ pub fn view(props: &IngredientViewProps) -> Html {
             let context_cloned = context_cloned.clone();
             let ingredient_id = ingredient_id.clone();
             if let Some(ingredient_id) = ingredient_id {
-                wasm_bindgen_futures::spawn_local(async move {
-                    match ladle::ingredient_get(&context.settings.server_url, &ingredient_id).await
-                    {
+                tasks.spawn(async move {
+                    match context.backend.ingredient_get(&ingredient_id).await {
                         Ok(ingredient) => {
                             state_cloned.set(IngredientViewState {
                                 ingredient: Some(ingredient),
                             });
                         }
-                        Err(message) => context_cloned
-                            .status
-                            .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                        Err(message) => context_cloned.status.emit(Message::Error(
+                            message.describe(context_cloned.settings.language),
+                            chrono::Utc::now(),
+                        )),
                     }
                 });
             }
@@ -97,7 +95,7 @@ pub fn view(props: &IngredientViewProps) -> Html {
         None => html! {},
         Some(data) => {
             set_title(&format!("{} - spoon", data.name));
-            render_ingredient(data)
+            render_ingredient(data, language)
         }
     }
 }
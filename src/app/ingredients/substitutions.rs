@@ -0,0 +1,236 @@
+use crate::app::{status_bar::Message, AppContext};
+use ladle::models::IngredientIndex;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+use unidecode::unidecode;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// An ingredient that can be used in place of another, `ratio` being the
+// quantity of replacement to use for one unit of the original
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct Substitution {
+    pub original: IngredientIndex,
+    pub replacement: IngredientIndex,
+    pub ratio: f64,
+    pub note: String,
+}
+
+impl Substitution {
+    pub fn describe(&self) -> String {
+        match self.note.len() {
+            0 => format!("{} (1:{})", self.replacement.name, self.ratio),
+            _ => format!(
+                "{} (1:{}) - {}",
+                self.replacement.name, self.ratio, self.note
+            ),
+        }
+    }
+}
+
+pub fn substitutions_for<'a>(
+    catalogue: &'a [Substitution],
+    ingredient_id: &str,
+) -> Vec<&'a Substitution> {
+    catalogue
+        .iter()
+        .filter(|s| s.original.id == ingredient_id)
+        .collect()
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SubstitutionListProps {
+    pub ingredient: IngredientIndex,
+    pub update_substitutions: Callback<Vec<Substitution>>,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct SubstitutionListState {
+    selected_replacement: Option<IngredientIndex>,
+    ratio_buffer: String,
+    note_buffer: String,
+}
+
+#[function_component(SubstitutionList)]
+pub fn substitution_list(props: &SubstitutionListProps) -> Html {
+    let state = use_state(SubstitutionListState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+
+    let substitutions = substitutions_for(&context.substitutions, &props.ingredient.id)
+        .into_iter()
+        .map(|substitution| {
+            let context_cloned = context.clone();
+            let props_cloned = props.clone();
+            let sc = substitution.clone();
+            let delete = Callback::from(move |_| {
+                let mut catalogue = context_cloned.substitutions.clone();
+                catalogue.retain(|s| s != &sc);
+                props_cloned.update_substitutions.emit(catalogue);
+            });
+
+            html! {
+                <tr key={substitution.replacement.id.as_str()}>
+                    <td>{substitution.replacement.name.as_str()}</td>
+                    <td>{format!("1:{}", substitution.ratio)}</td>
+                    <td>{substitution.note.as_str()}</td>
+                    <td><button onclick={delete}>{language.t("action.delete")}</button></td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_replacement_select = Callback::from(move |e: Event| {
+        let selected_id = e
+            .target()
+            .expect("")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        let mut data = state_cloned.deref().clone();
+        data.selected_replacement = context_cloned
+            .ingredient_cache
+            .iter()
+            .find(|index| index.id == selected_id)
+            .cloned();
+        state_cloned.set(data);
+    });
+
+    let state_cloned = state.clone();
+    let on_ratio_edit = Callback::from(move |e: Event| {
+        let mut data = state_cloned.deref().clone();
+        data.ratio_buffer = e
+            .target()
+            .expect("")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        state_cloned.set(data);
+    });
+
+    let state_cloned = state.clone();
+    let on_note_edit = Callback::from(move |e: Event| {
+        let mut data = state_cloned.deref().clone();
+        data.note_buffer = e
+            .target()
+            .expect("")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        state_cloned.set(data);
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let props_cloned = props.clone();
+    let create_substitution = Callback::from(move |_| {
+        let replacement = match &state_cloned.selected_replacement {
+            Some(replacement) => replacement.clone(),
+            None => return,
+        };
+
+        let ratio = match state_cloned.ratio_buffer.trim() {
+            "" => 1.0,
+            value => match value.parse::<f64>() {
+                Ok(ratio) if ratio > 0.0 => ratio,
+                _ => {
+                    context_cloned.status.emit(Message::Error(
+                        context_cloned
+                            .settings
+                            .language
+                            .tf("substitutions.invalid_ratio", &[&value]),
+                        chrono::Utc::now(),
+                    ));
+                    return;
+                }
+            },
+        };
+
+        let mut catalogue = context_cloned.substitutions.clone();
+        catalogue.retain(|s| {
+            s.original.id != props_cloned.ingredient.id || s.replacement.id != replacement.id
+        });
+        catalogue.push(Substitution {
+            original: props_cloned.ingredient.clone(),
+            replacement,
+            ratio,
+            note: state_cloned.note_buffer.clone(),
+        });
+        props_cloned.update_substitutions.emit(catalogue);
+
+        state_cloned.set(SubstitutionListState::default());
+    });
+
+    let mut options: Vec<IngredientIndex> = context
+        .ingredient_cache
+        .iter()
+        .filter(|idx| idx.id != props.ingredient.id)
+        .cloned()
+        .collect();
+    options.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)));
+    let option_html = options
+        .iter()
+        .map(|opt| {
+            let selected = match &state.selected_replacement {
+                Some(idx) => idx == opt,
+                None => false,
+            };
+
+            html! {
+                <option
+                    selected={selected}
+                    value={opt.id.clone()}>
+                    {opt.name.clone()}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="substitutions">
+            <h3>{language.t("substitutions.title")}</h3>
+            <table>
+                {substitutions}
+                <tr key={"substitution_add"}>
+                    <td>
+                        <select
+                            autocomplete="off"
+                            onchange={on_replacement_select}>
+                            <option
+                                hidden=true
+                                disabled=true
+                                selected={state.selected_replacement.is_none()}>
+                                {language.t("substitutions.replacement")}
+                            </option>
+                            {option_html}
+                        </select>
+                    </td>
+                    <td>
+                        <input
+                            type="text"
+                            placeholder={language.t("substitutions.ratio")}
+                            value={state.ratio_buffer.clone()}
+                            onchange={on_ratio_edit}
+                        />
+                    </td>
+                    <td>
+                        <input
+                            type="text"
+                            placeholder={language.t("substitutions.note")}
+                            value={state.note_buffer.clone()}
+                            onchange={on_note_edit}
+                        />
+                    </td>
+                    <td>
+                        <button
+                            disabled={state.selected_replacement.is_none()}
+                            onclick={create_substitution}>
+                            {language.t("action.add")}
+                        </button>
+                    </td>
+                </tr>
+            </table>
+        </div>
+    }
+}
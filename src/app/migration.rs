@@ -0,0 +1,406 @@
+use crate::app::backend::{connect_server, Backend};
+use crate::app::backup::{
+    plan_restore, restore, Archive, ArchivedIngredient, ArchivedLabel, ProgressEvent, RestorePlan,
+    ARCHIVE_VERSION,
+};
+use crate::app::connection::validate_url;
+use crate::app::i18n::Language;
+use crate::app::{set_title, status_bar::Message, AppContext, DETAILS_CONCURRENCY};
+use futures::stream::{self, StreamExt};
+use ladle::models::{Recipe, RecipeIndex};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::rc::Rc;
+use unidecode::unidecode;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// Fetch the selected recipes along with their transitive dependencies, the
+// ingredients they use and their labels
+pub async fn fetch_selection(
+    backend: &dyn Backend,
+    selection: &[String],
+    language: Language,
+) -> Result<Archive, String> {
+    let mut recipes: Vec<Recipe> = vec![];
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: Vec<String> = selection.to_vec();
+
+    while !queue.is_empty() {
+        queue.retain(|id| visited.insert(id.clone()));
+
+        let fetches = queue.iter().map(|id| backend.recipe_get(id));
+        let level = stream::iter(fetches)
+            .buffer_unordered(DETAILS_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(|response| response.map_err(|e| e.describe(language)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        queue = level
+            .iter()
+            .flat_map(|recipe| recipe.dependencies.iter())
+            .map(|dependency| dependency.recipe.id.clone())
+            .filter(|id| !visited.contains(id))
+            .collect();
+        recipes.extend(level);
+    }
+
+    let ingredient_ids: BTreeSet<&str> = recipes
+        .iter()
+        .flat_map(|recipe| recipe.requirements.iter())
+        .map(|requirement| requirement.ingredient.id.as_str())
+        .collect();
+    let fetches = ingredient_ids.iter().map(|id| backend.ingredient_get(id));
+    let ingredients = stream::iter(fetches)
+        .buffer_unordered(DETAILS_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|response| {
+            response
+                .map(|ingredient| ArchivedIngredient {
+                    id: ingredient.id,
+                    name: ingredient.name,
+                    classifications: ingredient.classifications,
+                })
+                .map_err(|e| e.describe(language))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut labels: BTreeMap<String, ArchivedLabel> = BTreeMap::new();
+    for recipe in recipes.iter() {
+        for tag in recipe.tags.iter() {
+            labels
+                .entry(tag.id.clone())
+                .or_insert(ArchivedLabel {
+                    id: tag.id.clone(),
+                    name: tag.name.clone(),
+                    tagged_recipes: vec![],
+                })
+                .tagged_recipes
+                .push(recipe.id.clone());
+        }
+    }
+
+    Ok(Archive {
+        version: ARCHIVE_VERSION,
+        created: chrono::Utc::now().to_rfc3339(),
+        server: backend.location(),
+        ingredients,
+        recipes,
+        labels: labels.into_values().collect(),
+    })
+}
+
+enum MigrationAction {
+    SetTarget(String),
+    Toggle(String),
+    Planning,
+    // The validated url of the target comes along with its plan
+    Planned(String, Archive, RestorePlan),
+    Progress(ProgressEvent),
+    Done(Vec<String>),
+    Cancel,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct MigrationState {
+    target: String,
+    selected: BTreeSet<String>,
+    busy: bool,
+    done: usize,
+    total: usize,
+    pending: Option<(String, Archive, RestorePlan)>,
+    errors: Vec<String>,
+}
+
+impl Reducible for MigrationState {
+    type Action = MigrationAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut new_state: Self = (*self).clone();
+
+        match action {
+            MigrationAction::SetTarget(target) => {
+                new_state.target = target;
+                new_state.pending = None;
+            }
+            MigrationAction::Toggle(id) => {
+                if !new_state.selected.remove(&id) {
+                    new_state.selected.insert(id);
+                }
+                new_state.pending = None;
+            }
+            MigrationAction::Planning => {
+                new_state.busy = true;
+                new_state.errors = vec![];
+            }
+            MigrationAction::Planned(target, archive, plan) => {
+                new_state.busy = false;
+                new_state.pending = Some((target, archive, plan));
+            }
+            MigrationAction::Progress(ProgressEvent::Start(total)) => {
+                new_state.busy = true;
+                new_state.done = 0;
+                new_state.total = total;
+            }
+            MigrationAction::Progress(ProgressEvent::Step) => new_state.done += 1,
+            MigrationAction::Done(errors) => {
+                new_state.busy = false;
+                new_state.total = 0;
+                new_state.pending = None;
+                new_state.errors = errors;
+            }
+            MigrationAction::Cancel => {
+                new_state.busy = false;
+                new_state.pending = None;
+            }
+        }
+
+        new_state.into()
+    }
+}
+
+fn render_plan(
+    archive: &Archive,
+    plan: &RestorePlan,
+    selected: &BTreeSet<String>,
+    language: Language,
+) -> Html {
+    let summary = plan
+        .report(archive, language)
+        .into_iter()
+        .map(|line| html! {<li>{line}</li>})
+        .collect::<Html>();
+
+    let new_recipes = plan
+        .new_recipes
+        .iter()
+        .map(|recipe| {
+            html! {
+                <li>
+                    {recipe.name.as_str()}
+                    if !selected.contains(&recipe.id) {
+                        <span class="knife-id">{language.t("migration.dependency")}</span>
+                    }
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    let kept_recipes = archive
+        .recipes
+        .iter()
+        .filter_map(|recipe| plan.recipes.get(&recipe.id))
+        .map(|index| html! {<li>{index.name.as_str()}</li>})
+        .collect::<Html>();
+
+    let new_ingredients = plan
+        .new_ingredients
+        .iter()
+        .map(|ingredient| html! {<li>{ingredient.name.as_str()}</li>})
+        .collect::<Html>();
+
+    let matched_ingredients = archive
+        .ingredients
+        .iter()
+        .filter_map(|ingredient| {
+            plan.ingredients
+                .get(&ingredient.id)
+                .map(|index| (ingredient, index))
+        })
+        .map(|(ingredient, index)| {
+            html! {<li>{format!("{} → {}", ingredient.name, index.name)}</li>}
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="migration-plan">
+            <ul>{summary}</ul>
+            <h4>{language.t("migration.to_copy")}</h4>
+            <ul>{new_recipes}</ul>
+            <h4>{language.t("migration.kept")}</h4>
+            <ul>{kept_recipes}</ul>
+            <h4>{language.t("migration.new_ingredients")}</h4>
+            <ul>{new_ingredients}</ul>
+            <h4>{language.t("migration.matched")}</h4>
+            <ul>{matched_ingredients}</ul>
+        </div>
+    }
+}
+
+#[function_component(Migration)]
+pub fn migration() -> Html {
+    let state = use_reducer(MigrationState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+
+    let language = context.settings.language;
+    set_title(&format!("{} - spoon", language.t("migration.page_title")));
+
+    let state_cloned = state.clone();
+    let on_target_edit = Callback::from(move |e: InputEvent| {
+        let target = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        state_cloned.dispatch(MigrationAction::SetTarget(target));
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_plan = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let source = context_cloned.backend.location();
+        let selection: Vec<String> = state_cloned.selected.iter().cloned().collect();
+        let target = match validate_url(&state_cloned.target) {
+            Ok(target) => target,
+            Err(message) => {
+                context_cloned.status.emit(Message::Error(
+                    message.translate(language),
+                    chrono::Utc::now(),
+                ));
+                return;
+            }
+        };
+
+        if target == source.trim_end_matches('/') {
+            context_cloned.status.emit(Message::Error(
+                language.t("migration.same_target").to_string(),
+                chrono::Utc::now(),
+            ));
+            return;
+        }
+
+        state_cloned.dispatch(MigrationAction::Planning);
+        wasm_bindgen_futures::spawn_local(async move {
+            let backend = connect_server(&target, &context_cloned.settings);
+            let result = match fetch_selection(&*context_cloned.backend, &selection, language).await
+            {
+                Ok(archive) => plan_restore(&*backend, &archive, language)
+                    .await
+                    .map(|plan| (archive, plan)),
+                Err(message) => Err(message),
+            };
+
+            match result {
+                Ok((archive, plan)) => {
+                    state_cloned.dispatch(MigrationAction::Planned(target, archive, plan))
+                }
+                Err(message) => {
+                    state_cloned.dispatch(MigrationAction::Cancel);
+                    context_cloned
+                        .status
+                        .emit(Message::Error(message, chrono::Utc::now()))
+                }
+            }
+        });
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_migrate = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+
+        let (target, archive, plan) = match &state_cloned.pending {
+            Some(pending) => pending.clone(),
+            None => return,
+        };
+
+        let state_progress = state_cloned.clone();
+        let progress = Callback::from(move |event: ProgressEvent| {
+            state_progress.dispatch(MigrationAction::Progress(event))
+        });
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let backend = connect_server(&target, &context_cloned.settings);
+            let errors = restore(&*backend, &archive, &plan, &progress, language).await;
+
+            match errors.len() {
+                0 => context_cloned.status.emit(Message::Success(
+                    language.t("migration.done").to_string(),
+                    chrono::Utc::now(),
+                )),
+                count => context_cloned.status.emit(Message::Error(
+                    language.plural("migration.done_errors", count, &[]),
+                    chrono::Utc::now(),
+                )),
+            }
+            state_cloned.dispatch(MigrationAction::Done(errors));
+        });
+    });
+
+    let state_cloned = state.clone();
+    let on_cancel = Callback::from(move |_| state_cloned.dispatch(MigrationAction::Cancel));
+
+    let mut recipes: Vec<RecipeIndex> = context.recipe_cache.iter().cloned().collect();
+    recipes.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)));
+    let recipe_list = recipes
+        .iter()
+        .map(|recipe| {
+            let state_cloned = state.clone();
+            let id = recipe.id.clone();
+            html! {
+                <li key={recipe.id.as_str()}>
+                    <label>
+                        <input
+                            type="checkbox"
+                            checked={state.selected.contains(&recipe.id)}
+                            onclick={Callback::from(move |_| {
+                                state_cloned.dispatch(MigrationAction::Toggle(id.clone()))
+                            })}
+                        />
+                        {recipe.name.as_str()}
+                    </label>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    let errors = state
+        .errors
+        .iter()
+        .map(|error| html! {<li>{error}</li>})
+        .collect::<Html>();
+
+    html! {
+        <div class="settings migration">
+            <h3>{language.t("migration.title")}</h3>
+            <table class="items">
+                <tr>
+                    <td>
+                        <label for="target">{language.t("migration.target")}</label>
+                    </td>
+                    <td>
+                        <input type="text"
+                            name="target"
+                            oninput={on_target_edit}
+                            value={state.target.clone()}
+                        />
+                    </td>
+                </tr>
+            </table>
+            <ul class="migration-selection">{recipe_list}</ul>
+            <button
+                disabled={state.busy || state.selected.is_empty()}
+                onclick={on_plan}>
+                {language.t("migration.plan")}
+            </button>
+            if let Some((_, archive, plan)) = &state.pending {
+                {render_plan(archive, plan, &state.selected, language)}
+                <div class="restore-confirm">
+                    <button disabled={state.busy} onclick={on_migrate}>{language.t("migration.copy")}</button>
+                    <button disabled={state.busy} onclick={on_cancel}>{language.t("action.cancel")}</button>
+                </div>
+            }
+            if state.total > 0 {
+                <progress max={state.total.to_string()} value={state.done.to_string()} />
+            }
+            <ul class="backup-report">{errors}</ul>
+        </div>
+    }
+}
@@ -1,22 +1,48 @@
+mod auth;
+mod backend;
+mod backup;
+mod connection;
+mod download;
+mod i18n;
 mod ingredients;
+mod migration;
+mod palette;
+mod profile;
 mod recipes;
+mod servers;
 mod settings;
 mod status_bar;
+mod sync;
+mod tasks;
+mod theme;
 
+use auth::{authorize, Credentials, CredentialsPrompt};
+use backend::{connect, http::HttpBackend, BackendHandle};
+use futures::stream::{self, StreamExt};
 use ingredients::{
     create::IngredientCreateButton,
     edit::{IngredientEdit, IngredientEditButton},
     list::IngredientList,
     show::IngredientView,
+    substitutions::Substitution,
 };
-use ladle::models::{IngredientIndex, RecipeIndex};
+use ladle::models::{IngredientIndex, Recipe, RecipeIndex};
+use migration::Migration;
+use palette::CommandPalette;
 use recipes::edit::RecipeEditWindow;
 use recipes::list::RecipeList;
 use recipes::window::RecipeWindow;
+use servers::ServerSwitcher;
 use settings::{AppSettings, Settings};
-use status_bar::{Message, StatusBar};
-use std::collections::HashSet;
+use status_bar::{
+    Action, Message, NotificationAction, NotificationHistory, Notifications, StatusBar,
+};
+use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
+use sync::{CacheAction, CacheChange, Caches, Revision, SYNC_INTERVAL_MS};
+use theme::apply_theme;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use yew::prelude::*;
 use yew_hooks::prelude::*;
 use yew_router::prelude::*;
@@ -37,6 +63,8 @@ enum Route {
     EditIngredient { id: String },
     #[at("/settings")]
     Settings,
+    #[at("/settings/migrate")]
+    Migrate,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -44,15 +72,16 @@ enum Route {
 
 #[derive(PartialEq, Clone)]
 struct AppState {
-    last_error: Message,
     edition: bool,
+    // Status code of a request refused by the server, to ask for credentials
+    auth_prompt: Option<u16>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         AppState {
-            last_error: Message::None,
             edition: false,
+            auth_prompt: None,
         }
     }
 }
@@ -60,22 +89,76 @@ impl Default for AppState {
 #[derive(PartialEq, Clone, Debug)]
 struct AppContext {
     settings: AppSettings,
+    backend: BackendHandle,
     status: Callback<Message>,
+    // Display a message along with a button running an action
+    notify: Callback<(Message, Action)>,
+    // Apply a change made from this client to the caches below
+    update_cache: Callback<CacheChange>,
+    // Copies of the caches kept by the App, filled in when provided
     ingredient_cache: HashSet<IngredientIndex>,
     recipe_cache: HashSet<RecipeIndex>,
+    recipe_details: HashMap<String, Recipe>,
+    substitutions: Vec<Substitution>,
 }
 
 impl Default for AppContext {
     fn default() -> Self {
         AppContext {
             settings: AppSettings::default(),
+            backend: BackendHandle::new(HttpBackend::new("")),
             status: Callback::from(|_| ()),
+            notify: Callback::from(|_| ()),
+            update_cache: Callback::from(|_| ()),
             ingredient_cache: HashSet::new(),
             recipe_cache: HashSet::new(),
+            recipe_details: HashMap::new(),
+            substitutions: vec![],
         }
     }
 }
 
+// Data cached on disk, indexed by server url
+type ServerCaches<T> = HashMap<String, T>;
+
+fn cached<T: Clone + Default>(storage: &Option<ServerCaches<T>>, server_url: &str) -> T {
+    storage
+        .as_ref()
+        .and_then(|caches| caches.get(server_url))
+        .cloned()
+        .unwrap_or_default()
+}
+
+// Requests sent at the same time when fetching many objects, such as the
+// details cache or a backup, and number of recipes merged into the cache at
+// once
+const DETAILS_CONCURRENCY: usize = 8;
+const DETAILS_BATCH: usize = 50;
+
+fn load_caches(
+    source: &str,
+    ingredients: &Option<ServerCaches<HashSet<IngredientIndex>>>,
+    recipes: &Option<ServerCaches<HashSet<RecipeIndex>>>,
+    details: &Option<ServerCaches<HashMap<String, Recipe>>>,
+    fetched: &Option<ServerCaches<HashMap<String, f64>>>,
+) -> Caches {
+    Caches {
+        source: source.to_string(),
+        ingredients: cached(ingredients, source),
+        recipes: cached(recipes, source),
+        details: cached(details, source),
+        fetched: cached(fetched, source),
+        ..Caches::default()
+    }
+}
+
+fn store<T>(mut storage: Option<ServerCaches<T>>, server_url: &str, value: T) -> ServerCaches<T> {
+    storage
+        .get_or_insert_with(HashMap::new)
+        .insert(server_url.to_string(), value);
+    storage.unwrap_or_default()
+}
+
 pub fn set_title(title: &str) {
     if let Some(window) = web_sys::window() {
         if let Some(document) = window.document() {
@@ -88,118 +171,468 @@ pub fn set_title(title: &str) {
 pub fn app() -> Html {
     let state = use_state_eq(|| AppState::default());
 
-    // Callback to display a banner on the main window for the user
-    let state_cloned = state.clone();
-    let display_status = Callback::from(move |status: Message| {
-        let mut data = state_cloned.deref().clone();
-        data.last_error = status;
-        state_cloned.set(data);
-    });
+    // Set while the credentials prompt is shown, errors being expected then
+    let auth_denied = use_mut_ref(|| false);
+
+    // Messages for the user, shown as toasts and kept in the history
+    let notifications = use_reducer(Notifications::default);
+
+    // Callbacks to display a message on the main window for the user
+    let dispatcher = notifications.dispatcher();
+    let denied_cloned = auth_denied.clone();
+    let display_notification =
+        Callback::from(move |(status, action): (Message, Option<Action>)| {
+            if matches!(status, Message::Error(_, _)) && *denied_cloned.borrow() {
+                return;
+            }
+            dispatcher.dispatch(NotificationAction::Push(status, action));
+        });
+    let display_status = display_notification.reform(|status| (status, None));
+    let notify = display_notification.reform(|(status, action)| (status, Some(action)));
 
     // Application settings stored on client disk
     let persistent_settings = use_local_storage::<AppSettings>("persistent_settings".to_string());
-    let ingredient_cache = use_local_storage::<HashSet<ladle::models::IngredientIndex>>(
+    // Caches are stored per server url, to keep them apart when switching servers
+    let ingredient_cache = use_local_storage::<ServerCaches<HashSet<ladle::models::IngredientIndex>>>(
         "ingredient_cache".to_string(),
     );
-    let recipe_cache =
-        use_local_storage::<HashSet<ladle::models::RecipeIndex>>("recipe_cache".to_string());
+    let recipe_cache = use_local_storage::<ServerCaches<HashSet<ladle::models::RecipeIndex>>>(
+        "recipe_cache".to_string(),
+    );
+    let recipe_details = use_local_storage::<ServerCaches<HashMap<String, ladle::models::Recipe>>>(
+        "recipe_details".to_string(),
+    );
+    let details_fetched =
+        use_local_storage::<ServerCaches<HashMap<String, f64>>>("details_fetched".to_string());
+    // Substitutions refer to ingredient ids, which only mean something on
+    // the server they come from
+    let substitutions =
+        use_local_storage::<ServerCaches<Vec<Substitution>>>("substitutions".to_string());
+    // Credentials kept only for the browser session
+    let session_credentials =
+        use_session_storage::<HashMap<String, Credentials>>("credentials".to_string());
+
+    let sync_tick = use_reducer(Revision::default);
 
     // Data accessible by all children
-    let context = use_state(|| AppContext {
-        settings: (*persistent_settings).clone().unwrap_or_default(),
-        status: display_status,
-        ingredient_cache: (*ingredient_cache).clone().unwrap_or_default(),
-        recipe_cache: (*recipe_cache).clone().unwrap_or_default(),
+    let context = use_state(|| {
+        let mut settings = (*persistent_settings).clone().unwrap_or_default();
+        if settings.session_only {
+            settings.credentials = (*session_credentials).clone().unwrap_or_default();
+        }
+        AppContext {
+            status: display_status,
+            notify,
+            update_cache: Callback::from(|_| ()),
+            substitutions: cached(&substitutions, settings.data_source()),
+            backend: connect(&settings),
+            settings,
+            ..AppContext::default()
+        }
+    });
+
+    // Caches of the data source in use, handed to children along the context
+    let caches = use_reducer(|| {
+        load_caches(
+            context.settings.data_source(),
+            &ingredient_cache,
+            &recipe_cache,
+            &recipe_details,
+            &details_fetched,
+        )
     });
 
-    // Callback to trigger a refresh of the ingredient cache
+    // Run both comparisons with the server again, offered when one fails
+    let sync_dispatcher = sync_tick.dispatcher();
+    let retry_sync = Callback::from(move |_| sync_dispatcher.dispatch(()));
+
+    // Callback to compare the ingredient cache with the server
     let context_cloned = context.clone();
+    let dispatcher = caches.dispatcher();
+    let retry = retry_sync.clone();
     let update_ingredient_cache = Callback::from(move |_| {
         let context_cloned = context_cloned.clone();
-        let ingredient_cache = ingredient_cache.clone();
+        let dispatcher = dispatcher.clone();
+        let retry = retry.clone();
         wasm_bindgen_futures::spawn_local(async move {
-            let mut data = context_cloned.deref().clone();
-            match ladle::ingredient_index(&context_cloned.settings.server_url, "").await {
-                Ok(ingredients) => {
-                    let set: HashSet<_> = ingredients.iter().cloned().collect();
-                    data.ingredient_cache = set.clone();
-                    // Make it available to child components
-                    context_cloned.set(data);
-                    // Store it on disk
-                    ingredient_cache.set(set);
-                }
-                Err(error) => context_cloned
-                    .status
-                    .emit(Message::Error(error.to_string(), chrono::Utc::now())),
+            let source = context_cloned.settings.data_source().to_string();
+            match context_cloned.backend.ingredient_index("").await {
+                Ok(ingredients) => dispatcher.dispatch(CacheAction::ReplaceIngredients(
+                    source,
+                    ingredients.into_iter().collect(),
+                )),
+                Err(error) => context_cloned.notify.emit((
+                    Message::Error(
+                        error.describe(context_cloned.settings.language),
+                        chrono::Utc::now(),
+                    ),
+                    Action::new(
+                        context_cloned.settings.language.t("action.retry"),
+                        retry.clone(),
+                    ),
+                )),
             }
         });
     });
 
-    // Callback to trigger a refresh of the recipe cache
+    // Callback to compare the recipe cache with the server. Details are
+    // fetched below for new and renamed recipes, or all of them on a full
+    // reload.
     let context_cloned = context.clone();
-    let update_recipe_cache = Callback::from(move |_| {
+    let dispatcher = caches.dispatcher();
+    let retry = retry_sync.clone();
+    let sync_recipe_cache = Callback::from(move |full: bool| {
         let context_cloned = context_cloned.clone();
-        let recipe_cache = recipe_cache.clone();
+        let dispatcher = dispatcher.clone();
+        let retry = retry.clone();
         wasm_bindgen_futures::spawn_local(async move {
-            let mut data = context_cloned.deref().clone();
-            match ladle::recipe_index(&context_cloned.settings.server_url, "").await {
+            let source = context_cloned.settings.data_source().to_string();
+            match context_cloned.backend.recipe_index("").await {
                 Ok(recipes) => {
-                    let set: HashSet<_> = recipes.iter().cloned().collect();
-                    data.recipe_cache = set.clone();
-                    // Make it available to child components
-                    context_cloned.set(data);
-                    // Store it on disk
-                    recipe_cache.set(set);
+                    dispatcher.dispatch(CacheAction::ReplaceRecipes(
+                        source.clone(),
+                        recipes.into_iter().collect(),
+                    ));
+                    match full {
+                        true => dispatcher.dispatch(CacheAction::ClearDetails(source)),
+                        false => dispatcher.dispatch(CacheAction::ExpireDetails(
+                            source,
+                            js_sys::Date::now() - SYNC_INTERVAL_MS as f64,
+                        )),
+                    }
                 }
-                Err(error) => context_cloned
-                    .status
-                    .emit(Message::Error(error.to_string(), chrono::Utc::now())),
+                Err(error) => context_cloned.notify.emit((
+                    Message::Error(
+                        error.describe(context_cloned.settings.language),
+                        chrono::Utc::now(),
+                    ),
+                    Action::new(
+                        context_cloned.settings.language.t("action.retry"),
+                        retry.clone(),
+                    ),
+                )),
             }
         });
     });
+    let sync_cloned = sync_recipe_cache.clone();
+    let update_recipe_cache = Callback::from(move |_| sync_cloned.emit(true));
+
+    // Fetch the full recipes missing from the cache, to know which recipes
+    // depend on which. A few requests run at a time, and the answers are
+    // merged by batches so that a long download is stored as it goes.
+    // Recipes that failed are left alone until the next sync.
+    let in_flight = use_mut_ref(HashSet::<String>::new);
+    let failed = use_mut_ref(HashSet::<String>::new);
+    let context_cloned = context.clone();
+    let caches_cloned = caches.clone();
+    let in_flight_cloned = in_flight.clone();
+    let failed_cloned = failed.clone();
+    use_effect_with_deps(
+        move |(source, _, _)| {
+            let missing: Vec<String> = caches_cloned
+                .recipes
+                .iter()
+                .filter(|recipe| !caches_cloned.details.contains_key(&recipe.id))
+                .filter(|recipe| !in_flight_cloned.borrow().contains(&recipe.id))
+                .filter(|recipe| !failed_cloned.borrow().contains(&recipe.id))
+                .map(|recipe| recipe.id.clone())
+                .collect();
+
+            if !missing.is_empty() {
+                in_flight_cloned
+                    .borrow_mut()
+                    .extend(missing.iter().cloned());
+                let source = source.clone();
+                let dispatcher = caches_cloned.dispatcher();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let backend = &context_cloned.backend;
+                    let mut batches = stream::iter(missing)
+                        .map(|id| async move {
+                            let recipe = backend.recipe_get(&id).await;
+                            (id, recipe)
+                        })
+                        .buffer_unordered(DETAILS_CONCURRENCY)
+                        .chunks(DETAILS_BATCH);
+
+                    while let Some(batch) = batches.next().await {
+                        let mut recipes = vec![];
+                        for (id, recipe) in batch {
+                            in_flight_cloned.borrow_mut().remove(&id);
+                            match recipe {
+                                Ok(recipe) => recipes.push(recipe),
+                                Err(_) => {
+                                    failed_cloned.borrow_mut().insert(id);
+                                }
+                            }
+                        }
+                        dispatcher.dispatch(CacheAction::MergeDetails(source.clone(), recipes));
+                    }
+                });
+            }
+        },
+        (
+            caches.source.clone(),
+            caches.recipes_revision,
+            caches.details_revision,
+        ),
+    );
+
+    // Store each cache on disk when it changes
+    let storage = ingredient_cache.clone();
+    let caches_cloned = caches.clone();
+    use_effect_with_deps(
+        move |(source, _)| {
+            storage.set(store(
+                (*storage).clone(),
+                source,
+                caches_cloned.ingredients.clone(),
+            ))
+        },
+        (caches.source.clone(), caches.ingredients_revision),
+    );
+
+    let storage = recipe_cache.clone();
+    let caches_cloned = caches.clone();
+    use_effect_with_deps(
+        move |(source, _)| {
+            storage.set(store(
+                (*storage).clone(),
+                source,
+                caches_cloned.recipes.clone(),
+            ))
+        },
+        (caches.source.clone(), caches.recipes_revision),
+    );
+
+    let storage = recipe_details.clone();
+    let fetched_storage = details_fetched.clone();
+    let caches_cloned = caches.clone();
+    use_effect_with_deps(
+        move |(source, _)| {
+            storage.set(store(
+                (*storage).clone(),
+                source,
+                caches_cloned.details.clone(),
+            ));
+            fetched_storage.set(store(
+                (*fetched_storage).clone(),
+                source,
+                caches_cloned.fetched.clone(),
+            ))
+        },
+        (caches.source.clone(), caches.details_revision),
+    );
+
+    // Compare the caches with the server periodically
+    let sync_dispatcher = sync_tick.dispatcher();
+    use_effect_with_deps(
+        move |_| {
+            let tick = Closure::<dyn Fn()>::new(move || {
+                let hidden = web_sys::window()
+                    .and_then(|window| window.document())
+                    .map_or(false, |document| document.hidden());
+                if !hidden {
+                    sync_dispatcher.dispatch(())
+                }
+            });
+            let interval = web_sys::window().and_then(|window| {
+                window
+                    .set_interval_with_callback_and_timeout_and_arguments_0(
+                        tick.as_ref().unchecked_ref(),
+                        SYNC_INTERVAL_MS,
+                    )
+                    .ok()
+            });
+
+            move || {
+                if let (Some(window), Some(interval)) = (web_sys::window(), interval) {
+                    window.clear_interval_with_handle(interval);
+                }
+                drop(tick);
+            }
+        },
+        (),
+    );
 
-    // On change of the 'update' field of the state, fetch and cache ingredients
+    // Authenticate requests to the server, and prompt for credentials when refused
+    let state_cloned = state.clone();
+    let denied_cloned = auth_denied.clone();
+    let server_access = (
+        context.settings.data_source().to_string(),
+        context.settings.server_credentials(),
+    );
+    let settings_cloned = context.settings.clone();
+    use_effect_with_deps(
+        move |_| {
+            let denied = Callback::from(move |status: u16| {
+                *denied_cloned.borrow_mut() = true;
+                let mut data = state_cloned.deref().clone();
+                data.auth_prompt = Some(status);
+                state_cloned.set(data);
+            });
+            let listener = authorize(&settings_cloned, denied);
+            move || drop(listener)
+        },
+        (
+            context.settings.data_source().to_string(),
+            context.settings.credentials.clone(),
+        ),
+    );
+
+    // On change of the server or its credentials, and periodically, bring the
+    // caches up to date
     let update_ing = update_ingredient_cache.clone();
-    let update_rec = update_recipe_cache.clone();
+    let sync_rec = sync_recipe_cache.clone();
+    let failed_cloned = failed.clone();
     use_effect_with_deps(
         move |_| {
+            failed_cloned.borrow_mut().clear();
             update_ing.emit(());
-            update_rec.emit(());
+            sync_rec.emit(false);
         },
-        context.settings.server_url.clone(),
+        (server_access, *sync_tick),
     );
 
+    // Switch the palette of the page along with the setting
+    use_effect_with_deps(|theme| apply_theme(*theme), context.settings.theme);
+
     // Callback to update settings to the value passed as an argument
     let context_cloned = context.clone();
+    let dispatcher = caches.dispatcher();
+    let failed_cloned = failed.clone();
+    let substitutions_cloned = substitutions.clone();
     let update_settings = Callback::from(move |settings: AppSettings| {
         let mut data = context_cloned.deref().clone();
+        // Each server has its own caches, load them when switching
+        if settings.data_source() != data.settings.data_source() {
+            in_flight.borrow_mut().clear();
+            failed_cloned.borrow_mut().clear();
+            dispatcher.dispatch(CacheAction::Load(load_caches(
+                settings.data_source(),
+                &ingredient_cache,
+                &recipe_cache,
+                &recipe_details,
+                &details_fetched,
+            )));
+            data.substitutions = cached(&substitutions_cloned, settings.data_source());
+            data.backend = connect(&settings);
+        } else if settings.requests != data.settings.requests {
+            data.backend = connect(&settings);
+        }
         data.settings = settings.clone();
         context_cloned.set(data);
 
-        persistent_settings.set(settings);
+        // Keep session-only credentials off the disk
+        if settings.session_only {
+            session_credentials.set(settings.credentials.clone());
+            persistent_settings.set(AppSettings {
+                credentials: HashMap::new(),
+                ..settings
+            });
+        } else {
+            session_credentials.delete();
+            persistent_settings.set(settings);
+        }
+    });
+
+    // Callback to replace the substitution catalogue
+    let context_cloned = context.clone();
+    let update_substitutions = Callback::from(move |catalogue: Vec<Substitution>| {
+        let mut data = context_cloned.deref().clone();
+        data.substitutions = catalogue.clone();
+        let source = data.settings.data_source().to_string();
+        context_cloned.set(data);
+
+        substitutions.set(store((*substitutions).clone(), &source, catalogue));
+    });
+
+    let switch_server = update_settings.clone();
+
+    let state_cloned = state.clone();
+    let close_prompt = Callback::from(move |_| {
+        *auth_denied.borrow_mut() = false;
+        let mut data = state_cloned.deref().clone();
+        data.auth_prompt = None;
+        state_cloned.set(data);
     });
+    let prompt_settings = update_settings.clone();
 
+    let context_cloned = context.clone();
+    let update_ing = update_ingredient_cache.clone();
+    let sync_rec = sync_recipe_cache.clone();
+    let on_refresh = Callback::from(move |_| {
+        context_cloned.backend.refresh();
+        failed.borrow_mut().clear();
+        update_ing.emit(());
+        sync_rec.emit(true);
+    });
+
+    // Changes are tagged with the data source at hand when the request was
+    // sent, a change completing after a switch is then dropped
+    let source = caches.source.clone();
+    let provided = AppContext {
+        update_cache: caches
+            .dispatcher()
+            .reform(move |change| CacheAction::Apply(source.clone(), change)),
+        ingredient_cache: caches.ingredients.clone(),
+        recipe_cache: caches.recipes.clone(),
+        recipe_details: caches.details.clone(),
+        ..(*context).clone()
+    };
+
+    let language = context.settings.language;
     let context_cloned = context.clone();
     html! {
         <main>
-            <StatusBar current={state.last_error.clone()} />
-            <ContextProvider<AppContext> context={(*context).clone()}>
+            <StatusBar
+                language={context.settings.language}
+                toasts={notifications.toasts.clone()}
+                dismiss={notifications.dispatcher().reform(NotificationAction::Dismiss)}
+            />
+            if let Some(status) = state.auth_prompt {
+                <CredentialsPrompt
+                    {status}
+                    current={context.settings.clone()}
+                    update_settings={prompt_settings}
+                    on_close={close_prompt}
+                />
+            }
+            <ContextProvider<AppContext> context={provided}>
                 <BrowserRouter>
+                    <CommandPalette />
                     <div class="header">
                         <div class="left">
                             <Link<Route> to={Route::ListRecipes}>
-                                {"Recipes"}
+                                {language.t("header.recipes")}
                             </Link<Route>>
                             <Link<Route> to={Route::ListIngredients}>
-                                {"Ingredients"}
+                                {language.t("header.ingredients")}
                             </Link<Route>>
                         </div>
                         <div class="logo">
                             {format!("spoon v{}", env!("CARGO_PKG_VERSION"))}
+                            if let Some(server) = context.settings.active_server() {
+                                <span class="server-name">{server.name.as_str()}</span>
+                            }
                         </div>
                         <div class="right">
+                            <ServerSwitcher
+                                current={context.settings.clone()}
+                                update_settings={switch_server}
+                            />
+                            <NotificationHistory
+                                {language}
+                                history={notifications.history.clone()}
+                                clear={notifications.dispatcher().reform(|_| NotificationAction::ClearHistory)}
+                            />
+                            <button
+                                class="refresh"
+                                title={language.t("header.refresh")}
+                                onclick={on_refresh}>
+                                {"⟳"}
+                            </button>
                             <Link<Route> to={Route::Settings}>
-                                {"Settings"}
+                                {language.t("header.settings")}
                             </Link<Route>>
                         </div>
                     </div>
@@ -208,18 +641,17 @@ pub fn app() -> Html {
                             let context_cloned = context_cloned.clone();
                             let update_settings = update_settings.clone();
                             let update_ingredient_cache = update_ingredient_cache.clone();
+                            let update_recipe_cache = update_recipe_cache.clone();
+                            let update_substitutions = update_substitutions.clone();
                             match switch {
                                 Route::ListRecipes => html! {
-                                    <RecipeList/>
+                                    <RecipeList />
                                 },
                                 Route::ShowRecipe { id } => html! {
                                     <RecipeWindow recipe_id={Some(id)}/>
                                 },
                                 Route::EditRecipe { id } => html! {
-                                    <RecipeEditWindow
-                                        recipe_id={id}
-                                        ingredient_cache_refresh={update_ingredient_cache}
-                                    />
+                                    <RecipeEditWindow recipe_id={id} />
                                 },
                                 Route::ListIngredients => html! {
                                     <div class={"ingredient-main"}>
@@ -228,9 +660,7 @@ pub fn app() -> Html {
                                             ingredient_id={Option::<String>::None}
                                         />
                                         <div class={"options"}>
-                                            <IngredientCreateButton
-                                                ingredient_cache_refresh={update_ingredient_cache}
-                                            />
+                                            <IngredientCreateButton />
                                         </div>
                                     </div>
                                 },
@@ -239,9 +669,7 @@ pub fn app() -> Html {
                                         <IngredientList />
                                         <IngredientView ingredient_id={Some(id.clone())}/>
                                         <div class={"options"}>
-                                            <IngredientCreateButton
-                                                ingredient_cache_refresh={update_ingredient_cache}
-                                            />
+                                            <IngredientCreateButton />
                                             <IngredientEditButton
                                                 ingredient_id={id}
                                             />
@@ -253,7 +681,7 @@ pub fn app() -> Html {
                                         <IngredientList />
                                         <IngredientEdit
                                             ingredient_id={Some(id)}
-                                            ingredient_cache_refresh={update_ingredient_cache}
+                                            update_substitutions={update_substitutions}
                                         />
                                     </div>
                                 },
@@ -261,8 +689,13 @@ pub fn app() -> Html {
                                     <Settings
                                         current={context_cloned.settings.clone()}
                                         update_settings={update_settings}
+                                        ingredient_cache_refresh={update_ingredient_cache}
+                                        recipe_cache_refresh={update_recipe_cache}
                                         />
                                 },
+                                Route::Migrate => html! {
+                                    <Migration />
+                                },
                                 Route::NotFound => html! {"404"},
                             }
                         })}
@@ -0,0 +1,341 @@
+use crate::app::i18n::Language;
+use crate::app::ingredients::create::create_ingredient_prompt;
+use crate::app::recipes::list::create_button::create_recipe_prompt;
+use crate::app::recipes::list::filters::Filters;
+use crate::app::recipes::list::simplify_name;
+use crate::app::{AppContext, Route};
+use std::collections::BTreeSet;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, KeyboardEvent};
+use yew::prelude::*;
+use yew_hooks::prelude::*;
+use yew_router::prelude::*;
+
+// Results shown at once, the rest being reached by refining the query
+static RESULT_COUNT: usize = 20;
+
+#[derive(PartialEq, Clone, Debug)]
+enum Command {
+    Recipe(ladle::models::RecipeIndex),
+    Ingredient(ladle::models::IngredientIndex),
+    Label(String),
+    CreateRecipe,
+    CreateIngredient,
+    OpenSettings,
+    EditRecipe(String),
+}
+
+impl Command {
+    fn name(&self, language: Language) -> String {
+        match self {
+            Command::Recipe(recipe) => recipe.name.clone(),
+            Command::Ingredient(ingredient) => ingredient.name.clone(),
+            Command::Label(name) => name.clone(),
+            Command::CreateRecipe => language.t("palette.create_recipe").to_string(),
+            Command::CreateIngredient => language.t("palette.create_ingredient").to_string(),
+            Command::OpenSettings => language.t("palette.open_settings").to_string(),
+            Command::EditRecipe(_) => language.t("palette.edit_recipe").to_string(),
+        }
+    }
+
+    fn kind(&self, language: Language) -> &'static str {
+        match self {
+            Command::Recipe(_) => language.t("palette.recipe"),
+            Command::Ingredient(_) => language.t("palette.ingredient"),
+            Command::Label(_) => language.t("palette.label"),
+            _ => language.t("palette.action"),
+        }
+    }
+
+    // Actions come first, then the data they apply to
+    fn rank(&self) -> u8 {
+        match self {
+            Command::Recipe(_) => 1,
+            Command::Ingredient(_) => 2,
+            Command::Label(_) => 3,
+            _ => 0,
+        }
+    }
+
+    fn run(&self, context: &AppContext, navigator: &Navigator) {
+        match self {
+            Command::Recipe(recipe) => navigator.push(&Route::ShowRecipe {
+                id: recipe.id.clone(),
+            }),
+            Command::Ingredient(ingredient) => navigator.push(&Route::ShowIngredient {
+                id: ingredient.id.clone(),
+            }),
+            Command::Label(name) => {
+                let _ = navigator.push_with_query(
+                    &Route::ListRecipes,
+                    &Filters {
+                        labels: vec![name.clone()],
+                        restrictions: String::from(""),
+                        name: String::from(""),
+                    },
+                );
+            }
+            Command::CreateRecipe => create_recipe_prompt(context, navigator),
+            Command::CreateIngredient => create_ingredient_prompt(context, navigator),
+            Command::OpenSettings => navigator.push(&Route::Settings),
+            Command::EditRecipe(id) => navigator.push(&Route::EditRecipe { id: id.clone() }),
+        }
+    }
+}
+
+// Score `candidate` against `query` when all the characters of the query
+// appear in it in order. Consecutive characters and characters starting a
+// word count more, gaps count against.
+fn fuzzy_score(query: &[char], candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = simplify_name(candidate).chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.iter().filter(|c| !c.is_whitespace()) {
+        let found = position + candidate[position..].iter().position(|c| c == wanted)?;
+
+        score += match previous {
+            Some(index) if index + 1 == found => 5,
+            _ => 1,
+        };
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position).min(5) as i32;
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+fn commands(context: &AppContext, route: Option<Route>) -> Vec<Command> {
+    let mut commands = vec![
+        Command::CreateRecipe,
+        Command::CreateIngredient,
+        Command::OpenSettings,
+    ];
+    if let Some(Route::ShowRecipe { id }) = route {
+        commands.push(Command::EditRecipe(id));
+    }
+
+    commands.extend(context.recipe_cache.iter().cloned().map(Command::Recipe));
+    commands.extend(
+        context
+            .ingredient_cache
+            .iter()
+            .cloned()
+            .map(Command::Ingredient),
+    );
+
+    // Labels are not indexed locally, gather the ones of the known recipes
+    let labels: BTreeSet<String> = context
+        .recipe_details
+        .values()
+        .flat_map(|recipe| recipe.tags.iter().map(|label| label.name.clone()))
+        .collect();
+    commands.extend(labels.into_iter().map(Command::Label));
+
+    commands
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CommandPaletteProps {}
+
+// Overlay opened with Ctrl+K to jump anywhere from the keyboard
+#[function_component(CommandPalette)]
+pub fn command_palette(_props: &CommandPaletteProps) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+    let navigator = use_navigator().unwrap();
+    let route = use_route::<Route>();
+    let open = use_state(|| false);
+    let query = use_state(String::new);
+    let selected = use_state(|| 0usize);
+    let input = use_node_ref();
+
+    let open_cloned = open.clone();
+    let query_cloned = query.clone();
+    let selected_cloned = selected.clone();
+    use_event_with_window("keydown", move |e: KeyboardEvent| {
+        if (e.ctrl_key() || e.meta_key()) && e.key().to_lowercase() == "k" {
+            e.prevent_default();
+            query_cloned.set(String::new());
+            selected_cloned.set(0);
+            open_cloned.set(!*open_cloned);
+        }
+    });
+
+    let input_cloned = input.clone();
+    use_effect_with_deps(
+        move |open| {
+            if *open {
+                if let Some(input) = input_cloned.cast::<HtmlInputElement>() {
+                    input.focus().ok();
+                }
+            }
+        },
+        *open,
+    );
+
+    if !*open {
+        return html! {};
+    }
+
+    let needle: Vec<char> = simplify_name(&query).chars().collect();
+    let mut results: Vec<(i32, Command)> = commands(&context, route)
+        .into_iter()
+        .filter_map(|command| {
+            fuzzy_score(&needle, &command.name(language)).map(|score| (score, command))
+        })
+        .collect();
+    results.sort_by(|(lhs_score, lhs), (rhs_score, rhs)| {
+        rhs_score
+            .cmp(lhs_score)
+            .then_with(|| lhs.rank().cmp(&rhs.rank()))
+            .then_with(|| lhs.name(language).cmp(&rhs.name(language)))
+    });
+    let results: Vec<Command> = results
+        .into_iter()
+        .take(RESULT_COUNT)
+        .map(|(_, command)| command)
+        .collect();
+
+    let open_cloned = open.clone();
+    let close = Callback::from(move |_: ()| open_cloned.set(false));
+
+    let context_cloned = context.clone();
+    let close_cloned = close.clone();
+    let execute = Callback::from(move |command: Command| {
+        close_cloned.emit(());
+        command.run(&context_cloned, &navigator);
+    });
+
+    let query_cloned = query.clone();
+    let selected_cloned = selected.clone();
+    let on_input = Callback::from(move |e: InputEvent| {
+        let value = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        query_cloned.set(value);
+        selected_cloned.set(0);
+    });
+
+    let selected_cloned = selected.clone();
+    let results_cloned = results.clone();
+    let execute_cloned = execute.clone();
+    let close_cloned = close.clone();
+    let on_keydown = Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+        "ArrowDown" => {
+            e.prevent_default();
+            if *selected_cloned + 1 < results_cloned.len() {
+                selected_cloned.set(*selected_cloned + 1);
+            }
+        }
+        "ArrowUp" => {
+            e.prevent_default();
+            selected_cloned.set(selected_cloned.saturating_sub(1));
+        }
+        // Keep the key from the shortcuts of the page below
+        "Enter" => {
+            e.stop_propagation();
+            if let Some(command) = results_cloned.get(*selected_cloned) {
+                execute_cloned.emit(command.clone());
+            }
+        }
+        "Escape" => {
+            e.stop_propagation();
+            close_cloned.emit(());
+        }
+        _ => (),
+    });
+
+    let entries = results
+        .iter()
+        .enumerate()
+        .map(|(index, command)| {
+            let command_cloned = command.clone();
+            let execute = execute.clone();
+            html! {
+                <li
+                    class={classes!("palette-entry", (index == *selected).then_some("selected"))}
+                    onclick={Callback::from(move |_| execute.emit(command_cloned.clone()))}>
+                    <span class="palette-name">{command.name(language)}</span>
+                    <span class="palette-kind">{command.kind(language)}</span>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="command-palette" onclick={close.reform(|_| ())}>
+            <div class="palette-content" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                <input
+                    type="text"
+                    ref={input}
+                    value={(*query).clone()}
+                    placeholder={language.t("palette.placeholder")}
+                    oninput={on_input}
+                    onkeydown={on_keydown}
+                />
+                if results.is_empty() {
+                    <p class="palette-empty">{language.t("palette.empty")}</p>
+                } else {
+                    <ul>{entries}</ul>
+                }
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(query: &str, candidate: &str) -> Option<i32> {
+        let query: Vec<char> = simplify_name(query).chars().collect();
+        fuzzy_score(&query, candidate)
+    }
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert!(score("tart", "Tarte tatin").is_some());
+        assert!(score("tt", "Tarte tatin").is_some());
+        assert_eq!(score("nt", "Tarte"), None);
+        assert_eq!(score("tartes", "Tarte"), None);
+    }
+
+    #[test]
+    fn accents_case_and_spaces_are_ignored() {
+        assert_eq!(
+            score("creme", "Crème brûlée"),
+            score("Crème", "crème brûlée")
+        );
+        assert_eq!(
+            score("creme brulee", "Crème brûlée"),
+            score("cremebrulee", "Crème brûlée")
+        );
+        assert!(score("CRÈME", "creme").is_some());
+    }
+
+    #[test]
+    fn consecutive_characters_rank_higher() {
+        assert!(score("pain", "Pain perdu") > score("pain", "Pâte à pizza inratable"));
+    }
+
+    #[test]
+    fn word_starts_rank_higher() {
+        assert!(score("p", "Pain") > score("p", "Soupe"));
+        assert!(score("t", "Tarte") > score("t", "Pâte"));
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(score("", "Anything"), Some(0));
+        assert_eq!(score(" ", ""), Some(0));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::app::i18n::Language;
+use crate::app::recipes::dependents::{all_known, all_requirements};
+use ladle::models::{Classifications, IngredientIndex, Recipe};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Dietary restrictions of the user, checked against the recipes displayed
+#[derive(Default, Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct DietProfile {
+    pub dairy: bool,
+    pub gluten: bool,
+    pub meat: bool,
+    pub animal_product: bool,
+    pub avoided_ingredients: Vec<IngredientIndex>,
+    pub hide_incompatible: bool,
+}
+
+impl DietProfile {
+    // Whether there is nothing to check
+    pub fn is_empty(&self) -> bool {
+        !(self.dairy || self.gluten || self.meat || self.animal_product)
+            && self.avoided_ingredients.is_empty()
+    }
+
+    // List the reasons why an item is incompatible with the profile
+    pub fn warnings<'a>(
+        &self,
+        language: Language,
+        classifications: &Classifications,
+        ingredients: impl Iterator<Item = &'a IngredientIndex>,
+    ) -> Vec<String> {
+        let mut out = vec![];
+
+        if self.dairy && classifications.dairy {
+            out.push(language.t("classification.dairy").to_string());
+        }
+        if self.gluten && classifications.gluten {
+            out.push(language.t("classification.gluten").to_string());
+        }
+        if self.meat && classifications.meat {
+            out.push(language.t("classification.meat").to_string());
+        }
+        if self.animal_product && classifications.animal_product {
+            out.push(language.t("classification.animal_product").to_string());
+        }
+
+        let mut avoided: Vec<String> = ingredients
+            .filter(|ingredient| {
+                self.avoided_ingredients
+                    .iter()
+                    .any(|avoided| avoided.id == ingredient.id)
+            })
+            .map(|ingredient| ingredient.name.clone())
+            .collect();
+        avoided.sort();
+        avoided.dedup();
+        out.extend(avoided);
+
+        out
+    }
+}
+
+// Result of checking a cached recipe against the profile
+#[derive(PartialEq, Clone, Debug)]
+pub enum DietCheck {
+    Compatible,
+    // Reasons why the recipe does not fit the profile
+    Incompatible(Vec<String>),
+    // The recipe or one of its dependencies is not in the cache yet. It is
+    // treated as incompatible rather than let through unchecked.
+    Unchecked,
+}
+
+impl DietCheck {
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, DietCheck::Compatible)
+    }
+
+    pub fn describe(&self, language: Language) -> Option<String> {
+        match self {
+            DietCheck::Compatible => None,
+            DietCheck::Incompatible(warnings) => Some(describe_warnings(language, warnings)),
+            DietCheck::Unchecked => Some(language.t("profile.unchecked").to_string()),
+        }
+    }
+}
+
+// Check a cached recipe and all of its dependencies against the profile
+pub fn recipe_warnings(
+    language: Language,
+    profile: &DietProfile,
+    recipes: &HashMap<String, Recipe>,
+    recipe_id: &str,
+) -> DietCheck {
+    if profile.is_empty() {
+        return DietCheck::Compatible;
+    }
+
+    let recipe = match recipes.get(recipe_id) {
+        Some(recipe) if all_known(recipes, recipe_id) => recipe,
+        _ => return DietCheck::Unchecked,
+    };
+
+    let warnings = profile.warnings(
+        language,
+        &recipe.classifications,
+        all_requirements(recipes, recipe_id)
+            .iter()
+            .map(|requirement| &requirement.ingredient),
+    );
+    match warnings.is_empty() {
+        true => DietCheck::Compatible,
+        false => DietCheck::Incompatible(warnings),
+    }
+}
+
+pub fn describe_warnings(language: Language, warnings: &[String]) -> String {
+    language.tf("profile.contains", &[&warnings.join(", ")])
+}
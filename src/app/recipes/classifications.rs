@@ -0,0 +1,54 @@
+use crate::app::i18n::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Classifications {
+    Vegan,
+    Vegetarian,
+    DairyFree,
+    GlutenFree,
+}
+
+impl Classifications {
+    pub const ALL: [Classifications; 4] = [
+        Classifications::DairyFree,
+        Classifications::GlutenFree,
+        Classifications::Vegetarian,
+        Classifications::Vegan,
+    ];
+
+    // Whether an item with the given classifications breaks the restriction
+    pub fn violated_by(&self, data: &ladle::models::Classifications) -> bool {
+        match self {
+            Classifications::DairyFree => data.dairy,
+            Classifications::GlutenFree => data.gluten,
+            Classifications::Vegetarian => data.meat,
+            Classifications::Vegan => data.meat || data.animal_product || data.dairy,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Classifications::DairyFree => "dairy-free",
+            Classifications::GlutenFree => "gluten-free",
+            Classifications::Vegetarian => "vegetarian",
+            Classifications::Vegan => "vegan",
+        }
+    }
+
+    pub fn name(&self, language: Language) -> &'static str {
+        match self {
+            Classifications::DairyFree => language.t("classification.dairy_free"),
+            Classifications::GlutenFree => language.t("classification.gluten_free"),
+            Classifications::Vegetarian => language.t("classification.vegetarian"),
+            Classifications::Vegan => language.t("classification.vegan"),
+        }
+    }
+}
+
+pub fn get_classifications(data: &ladle::models::Classifications) -> Vec<Classifications> {
+    Classifications::ALL
+        .iter()
+        .filter(|class| !class.violated_by(data))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,76 @@
+use ladle::models::{Recipe, RecipeIndex, Requirement};
+use std::collections::{HashMap, HashSet};
+use unidecode::unidecode;
+
+// Map every recipe id to the recipes listing it as a dependency
+pub fn reverse_dependencies(
+    recipes: &HashMap<String, Recipe>,
+) -> HashMap<String, Vec<RecipeIndex>> {
+    let mut index: HashMap<String, Vec<RecipeIndex>> = HashMap::new();
+
+    for recipe in recipes.values() {
+        for dependency in recipe.dependencies.iter() {
+            index
+                .entry(dependency.recipe.id.clone())
+                .or_default()
+                .push(RecipeIndex {
+                    id: recipe.id.clone(),
+                    name: recipe.name.clone(),
+                });
+        }
+    }
+
+    index.values_mut().for_each(|users| {
+        users.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)))
+    });
+
+    index
+}
+
+pub fn used_by(recipes: &HashMap<String, Recipe>, recipe_id: &str) -> Vec<RecipeIndex> {
+    reverse_dependencies(recipes)
+        .remove(recipe_id)
+        .unwrap_or_default()
+}
+
+// Whether a recipe and all the recipes it depends on are in the cache
+pub fn all_known(recipes: &HashMap<String, Recipe>, recipe_id: &str) -> bool {
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut fifo: Vec<&str> = vec![recipe_id];
+
+    while let Some(id) = fifo.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+
+        match recipes.get(id) {
+            Some(recipe) => fifo.extend(recipe.dependencies.iter().map(|d| d.recipe.id.as_str())),
+            None => return false,
+        }
+    }
+
+    true
+}
+
+// Requirements of a recipe and of all the recipes it depends on
+pub fn all_requirements<'a>(
+    recipes: &'a HashMap<String, Recipe>,
+    recipe_id: &str,
+) -> Vec<&'a Requirement> {
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut fifo: Vec<&str> = vec![recipe_id];
+    let mut requirements = vec![];
+
+    while let Some(id) = fifo.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+
+        if let Some(recipe) = recipes.get(id) {
+            requirements.extend(recipe.requirements.iter());
+            fifo.extend(recipe.dependencies.iter().map(|d| d.recipe.id.as_str()));
+        }
+    }
+
+    requirements
+}
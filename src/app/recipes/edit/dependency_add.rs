@@ -138,12 +138,13 @@ pub fn dependency_add_item(props: &DependencyAddItemProps) -> Html {
         state_cloned.dispatch(DependencyAddItemAction::Open);
     });
 
+    let language = context.settings.language;
     let state_cloned = state.clone();
     html! {
         if state_cloned.mode == DependencyAddItemMode::Collapsed {
             <button
                 onclick={on_add_clicked}>
-                {"Ajouter une recette necessaire"}
+                {language.t("edit.add_dependency")}
             </button>
         } else {
             <tr key={"dependency_add"}>
@@ -155,7 +156,7 @@ pub fn dependency_add_item(props: &DependencyAddItemProps) -> Html {
                             hidden=true
                             disabled=true
                             selected={state_cloned.selected_recipe.is_none()}>
-                            {"Recipe"}
+                            {language.t("edit.recipe")}
                         </option>
                         {option_html}
                     </select>
@@ -163,7 +164,7 @@ pub fn dependency_add_item(props: &DependencyAddItemProps) -> Html {
                 <td>
                     <input
                         type="text"
-                        placeholder="Quantity"
+                        placeholder={language.t("edit.quantity")}
                         value={state.quantity_buffer.clone()}
                         onchange={on_quantity_edit}
                     />
@@ -179,7 +180,7 @@ pub fn dependency_add_item(props: &DependencyAddItemProps) -> Html {
                     <button
                         disabled={state_cloned.selected_recipe.is_none()}
                         onclick={create_dependency}>
-                        {"Add"}
+                        {language.t("action.add")}
                     </button>
                 </td>
             </tr>
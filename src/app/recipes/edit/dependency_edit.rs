@@ -1,3 +1,4 @@
+use crate::app::AppContext;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -11,6 +12,9 @@ pub struct DependencyEditItemProps {
 
 #[function_component(DependencyEditItem)]
 pub fn dependency_edit_item(props: &DependencyEditItemProps) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+
     let props_cloned = props.clone();
     let on_quantity_edit = Callback::from(move |e: Event| {
         let quantity = e
@@ -50,7 +54,7 @@ pub fn dependency_edit_item(props: &DependencyEditItemProps) -> Html {
                 checked={props.dependency.optional}
                 onclick={on_optional_edit}
             /></td>
-            <td><button onclick={delete_dependency}>{"Delete"}</button></td>
+            <td><button onclick={delete_dependency}>{language.t("action.delete")}</button></td>
         </tr>
     }
 }
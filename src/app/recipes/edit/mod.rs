@@ -3,6 +3,7 @@ mod dependency_add;
 mod dependency_edit;
 mod requirement_add;
 mod requirement_edit;
+mod shortcuts;
 mod tag_add;
 mod tag_edit;
 
@@ -10,22 +11,26 @@ use dependency_add::DependencyAddItem;
 use dependency_edit::DependencyEditItem;
 use requirement_add::RequirementAddItem;
 use requirement_edit::RequirementEditItem;
+use shortcuts::{commit_focused_field, focus_neighbour_row, is_typing, ShortcutHelp};
 use tag_add::TagAddItem;
 use tag_edit::TagEditItem;
 
+use crate::app::recipes::dependents::used_by;
+use crate::app::sync::CacheChange;
+use crate::app::tasks::use_task_scope;
 use crate::app::{status_bar::Message, AppContext, Route};
 use futures::future::join_all;
 use std::collections::BTreeSet;
 use std::rc::Rc;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlInputElement, KeyboardEvent};
 use yew::prelude::*;
+use yew_hooks::prelude::*;
 use yew_router::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct RecipeEditWindowProps {
     pub recipe_id: String,
-    pub ingredient_cache_refresh: Callback<()>,
 }
 
 enum RecipeEditWindowActions {
@@ -47,6 +52,14 @@ enum RecipeEditWindowActions {
     //Reset,
 }
 
+// Commands from the keyboard, run on the render following the commit of the
+// field being edited
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum EditorCommand {
+    Save,
+    Close,
+}
+
 #[derive(Clone, Default, Debug, PartialEq)]
 struct RecipeEditWindowState {
     original_recipe: Option<ladle::models::Recipe>,
@@ -157,6 +170,9 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
 
     let state = use_reducer(RecipeEditWindowState::default);
     let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+
+    let tasks = use_task_scope();
 
     let state_cloned = state.clone();
     let props_cloned = props.clone();
@@ -166,19 +182,19 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
             let state_cloned = state_cloned.clone();
             let props_cloned = props_cloned.clone();
             let context_cloned = context_cloned.clone();
-            wasm_bindgen_futures::spawn_local(async move {
-                match ladle::recipe_get(
-                    context_cloned.settings.server_url.as_str(),
-                    &props_cloned.recipe_id.clone(),
-                )
-                .await
+            tasks.spawn(async move {
+                match context_cloned
+                    .backend
+                    .recipe_get(&props_cloned.recipe_id.clone())
+                    .await
                 {
                     Ok(recipe) => {
                         state_cloned.dispatch(RecipeEditWindowActions::UpdateRecipe(recipe))
                     }
-                    Err(message) => context_cloned
-                        .status
-                        .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                    Err(message) => context_cloned.status.emit(Message::Error(
+                        message.describe(context_cloned.settings.language),
+                        chrono::Utc::now(),
+                    )),
                 }
             });
         }
@@ -192,10 +208,17 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
         let context_cloned = context_cloned.clone();
         let nc = nc.clone();
 
-        let confirm = match web_sys::window()
-            .unwrap()
-            .confirm_with_message(&format!("Delete recipe ?"))
-        {
+        let dependents = used_by(&context_cloned.recipe_details, &state_cloned.new_recipe.id)
+            .iter()
+            .map(|r| r.name.clone())
+            .collect::<Vec<_>>();
+
+        let message = match dependents.len() {
+            0 => language.t("edit.delete_confirm").to_string(),
+            _ => language.tf("edit.delete_used_confirm", &[&dependents.join(", ")]),
+        };
+
+        let confirm = match web_sys::window().unwrap().confirm_with_message(&message) {
             Ok(true) => true,
             _ => false,
         };
@@ -206,15 +229,17 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
             }
 
             if let Some(recipe) = &state_cloned.original_recipe {
-                match ladle::recipe_delete(context_cloned.settings.server_url.as_str(), &recipe.id)
-                    .await
-                {
+                match context_cloned.backend.recipe_delete(&recipe.id).await {
                     Ok(_) => {
+                        context_cloned
+                            .update_cache
+                            .emit(CacheChange::RecipeRemoved(recipe.id.clone()));
                         nc.push(&Route::ListRecipes);
                     }
-                    Err(message) => context_cloned
-                        .status
-                        .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                    Err(message) => context_cloned.status.emit(Message::Error(
+                        message.describe(context_cloned.settings.language),
+                        chrono::Utc::now(),
+                    )),
                 }
             }
         });
@@ -223,7 +248,7 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
     let state_cloned = state.clone();
     let context_cloned = context.clone();
     let fetch_cloned = fetch_recipe.clone();
-    let on_update_clicked = Callback::from(move |_| {
+    let on_update_clicked = Callback::from(move |_: ()| {
         let state_cloned = state_cloned.clone();
         let context_cloned = context_cloned.clone();
         let fetch_cloned = fetch_cloned.clone();
@@ -250,11 +275,9 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                     .collect::<BTreeSet<&ladle::models::Dependency>>();
 
                 let requests = missing_dependencies.iter().map(|dependency| {
-                    ladle::dependency_delete(
-                        &context_cloned.settings.server_url,
-                        &recipe.id,
-                        &dependency.recipe.id,
-                    )
+                    context_cloned
+                        .backend
+                        .dependency_delete(&recipe.id, &dependency.recipe.id)
                 });
 
                 join_all(requests)
@@ -272,8 +295,7 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                     .collect::<BTreeSet<&ladle::models::Dependency>>();
 
                 let requests = new_dependencies.iter().map(|dependency| {
-                    ladle::dependency_create(
-                        &context_cloned.settings.server_url,
+                    context_cloned.backend.dependency_create(
                         &recipe.id,
                         &dependency.recipe.id,
                         &dependency.quantity,
@@ -305,11 +327,9 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                     .collect::<BTreeSet<&ladle::models::Requirement>>();
 
                 let requests = missing_requirements.iter().map(|requirement| {
-                    ladle::requirement_delete(
-                        &context_cloned.settings.server_url,
-                        &recipe.id,
-                        &requirement.ingredient.id,
-                    )
+                    context_cloned
+                        .backend
+                        .requirement_delete(&recipe.id, &requirement.ingredient.id)
                 });
 
                 join_all(requests)
@@ -327,8 +347,7 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                     .collect::<BTreeSet<&ladle::models::Requirement>>();
 
                 let requests = new_requirements.iter().map(|requirement| {
-                    ladle::requirement_create(
-                        &context_cloned.settings.server_url,
+                    context_cloned.backend.requirement_create(
                         &recipe.id,
                         &requirement.ingredient.id,
                         &requirement.quantity,
@@ -348,9 +367,9 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
 
             let deleted_tags: Vec<_> = original.tags.difference(&recipe.tags).collect();
 
-            let requests = deleted_tags.iter().map(|label| {
-                ladle::recipe_untag(&context_cloned.settings.server_url, &recipe.id, &label.id)
-            });
+            let requests = deleted_tags
+                .iter()
+                .map(|label| context_cloned.backend.recipe_untag(&recipe.id, &label.id));
 
             join_all(requests)
                 .await
@@ -363,9 +382,9 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
 
             let added_tags: Vec<_> = recipe.tags.difference(&original.tags).collect();
 
-            let requests = added_tags.iter().map(|label| {
-                ladle::recipe_tag(&context_cloned.settings.server_url, &recipe.id, &label.name)
-            });
+            let requests = added_tags
+                .iter()
+                .map(|label| context_cloned.backend.recipe_tag(&recipe.id, &label.name));
 
             join_all(requests)
                 .await
@@ -376,26 +395,37 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                 })
                 .for_each(drop);
 
-            match ladle::recipe_update(
-                &context_cloned.settings.server_url,
-                &recipe.id,
-                Some(&recipe.name),
-                Some(&recipe.author),
-                Some(&recipe.directions),
-                None,
-            )
-            .await
+            match context_cloned
+                .backend
+                .recipe_update(
+                    &recipe.id,
+                    Some(&recipe.name),
+                    Some(&recipe.author),
+                    Some(&recipe.directions),
+                    None,
+                )
+                .await
             {
                 Ok(_) => {
                     fetch_cloned.emit(());
+                    // The server computes classifications, use its copy
+                    let change = match context_cloned.backend.recipe_get(&recipe.id).await {
+                        Ok(saved) => CacheChange::RecipeSaved(saved),
+                        Err(_) => CacheChange::RecipeIndexed(ladle::models::RecipeIndex {
+                            id: recipe.id.clone(),
+                            name: recipe.name.clone(),
+                        }),
+                    };
+                    context_cloned.update_cache.emit(change);
                     context_cloned.status.emit(Message::Success(
-                        String::from("Recette sauvegardee"),
+                        language.t("edit.saved").to_string(),
                         chrono::Utc::now(),
                     ))
                 }
-                Err(message) => context_cloned
-                    .status
-                    .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                Err(message) => context_cloned.status.emit(Message::Error(
+                    message.describe(context_cloned.settings.language),
+                    chrono::Utc::now(),
+                )),
             }
         });
     });
@@ -403,7 +433,7 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
     let nc = navigator.clone();
     let state_cloned = state.clone();
     let update = on_update_clicked.clone();
-    let on_exit_clicked = Callback::from(move |_| {
+    let on_exit_clicked = Callback::from(move |_: ()| {
         if let Some(recipe) = &state_cloned.original_recipe {
             if recipe.requirements != state_cloned.new_recipe.requirements
                 || recipe.name != state_cloned.new_recipe.name
@@ -414,9 +444,9 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
             {
                 match web_sys::window()
                     .unwrap()
-                    .confirm_with_message("Save before exiting ?")
+                    .confirm_with_message(language.t("edit.save_before_exit"))
                 {
-                    Ok(true) => update.emit(yew::MouseEvent::new("").unwrap()),
+                    Ok(true) => update.emit(()),
                     _ => (),
                 }
             }
@@ -481,6 +511,69 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
     let fetch_recipe_cloned = fetch_recipe.clone();
     use_effect_with_deps(move |_| fetch_recipe_cloned.emit(()), props.clone());
 
+    let pending = use_state(|| None::<EditorCommand>);
+    let help = use_state(|| false);
+
+    let pending_cloned = pending.clone();
+    let update = on_update_clicked.clone();
+    let exit = on_exit_clicked.clone();
+    use_effect_with_deps(
+        move |command| {
+            match command {
+                Some(EditorCommand::Save) => update.emit(()),
+                Some(EditorCommand::Close) => exit.emit(()),
+                None => return,
+            }
+            pending_cloned.set(None);
+        },
+        *pending,
+    );
+
+    let pending_cloned = pending.clone();
+    let help_cloned = help.clone();
+    use_event_with_window("keydown", move |e: KeyboardEvent| {
+        let command = match e.key().as_str() {
+            "s" | "S" if e.ctrl_key() || e.meta_key() => Some(EditorCommand::Save),
+            "Escape" if *help_cloned => {
+                help_cloned.set(false);
+                None
+            }
+            // Already handled by the add row, which collapsed
+            "Escape" if e.default_prevented() => return,
+            // Leave the field first, closing is for a second press
+            "Escape" if is_typing(&e) => {
+                commit_focused_field();
+                None
+            }
+            "Escape" => Some(EditorCommand::Close),
+            "?" if !is_typing(&e) => {
+                help_cloned.set(!*help_cloned);
+                None
+            }
+            _ => return,
+        };
+
+        e.prevent_default();
+        if let Some(command) = command {
+            commit_focused_field();
+            pending_cloned.set(Some(command));
+        }
+    });
+
+    let on_requirements_keydown = Callback::from(move |e: KeyboardEvent| {
+        let up = match e.key().as_str() {
+            "ArrowUp" => true,
+            "ArrowDown" => false,
+            _ => return,
+        };
+        if focus_neighbour_row(&e, up) {
+            e.prevent_default();
+        }
+    });
+
+    let help_cloned = help.clone();
+    let toggle_help = Callback::from(move |_: ()| help_cloned.set(!*help_cloned));
+
     let state_cloned = state.clone();
     let recipe = &state_cloned.new_recipe;
     let dependencies = recipe
@@ -609,12 +702,11 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                     recipe_blacklist={recipes_in_use}
                 />
             </table>
-            <table>
+            <table class="requirements" onkeydown={on_requirements_keydown}>
                 {requirements}
                 <RequirementAddItem
                     create_requirement={create_requirement}
                     ingredient_blacklist={ingredients_in_use}
-                    ingredient_cache_refresh={props.ingredient_cache_refresh.clone()}
                 />
             </table>
             <textarea
@@ -628,13 +720,21 @@ pub fn edit_window(props: &RecipeEditWindowProps) -> Html {
                     add_tag={add_tag}
                 />
             </ul>
+            if *help {
+                <ShortcutHelp on_close={toggle_help.clone()} />
+            }
             <div class="options">
-                <button onclick={on_update_clicked}>{"Update"}</button>
-                <button onclick={on_delete_clicked}>{"Delete"}</button>
+                <button
+                    title={language.t("shortcuts.title")}
+                    onclick={toggle_help.reform(|_| ())}>
+                    {"?"}
+                </button>
+                <button onclick={on_update_clicked.reform(|_| ())}>{language.t("action.update")}</button>
+                <button onclick={on_delete_clicked}>{language.t("action.delete")}</button>
                 <button
                     class={classes!("recipe-deselect")}
-                    onclick={on_exit_clicked}>
-                    {"Close"}
+                    onclick={on_exit_clicked.reform(|_| ())}>
+                    {language.t("action.close")}
                 </button>
             </div>
         </div>
@@ -1,16 +1,16 @@
-use crate::app::{status_bar::Message, AppContext};
+use crate::app::{status_bar::Message, sync::CacheChange, AppContext};
 use ladle::models::IngredientIndex;
 use std::rc::Rc;
 use unidecode::unidecode;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlElement, HtmlInputElement, KeyboardEvent};
 use yew::prelude::*;
+use yew_hooks::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct RequirementAddItemProps {
     pub create_requirement: Callback<(ladle::models::IngredientIndex, String, bool), ()>,
     pub ingredient_blacklist: Callback<(), Vec<String>>,
-    pub ingredient_cache_refresh: Callback<()>,
 }
 
 #[derive(PartialEq, Clone, Default)]
@@ -63,30 +63,26 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
 
     let state_cloned = state.clone();
     let context_cloned = context.clone();
-    let props_cloned = props.clone();
     let create_ingredient = Callback::from(move |name: String| {
         let state_cloned = state_cloned.clone();
         let context_cloned = context_cloned.clone();
-        let props_cloned = props_cloned.clone();
         wasm_bindgen_futures::spawn_local(async move {
-            match ladle::ingredient_create(
-                context_cloned.settings.server_url.as_str(),
-                name.as_str(),
-                false,
-                false,
-                false,
-                false,
-            )
-            .await
+            match context_cloned
+                .backend
+                .ingredient_create(name.as_str(), false, false, false, false)
+                .await
             {
                 Ok(ingredient) => {
                     state_cloned
                         .dispatch(RequirementAddItemAction::SetIngredient(ingredient.clone()));
-                    props_cloned.ingredient_cache_refresh.emit(());
+                    context_cloned
+                        .update_cache
+                        .emit(CacheChange::IngredientSaved(ingredient));
                 }
-                Err(error) => context_cloned
-                    .status
-                    .emit(Message::Error(error.to_string(), chrono::Utc::now())),
+                Err(error) => context_cloned.status.emit(Message::Error(
+                    error.describe(context_cloned.settings.language),
+                    chrono::Utc::now(),
+                )),
             }
         });
     });
@@ -103,7 +99,7 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
         if selected_ingredient_id == "new" {
             match web_sys::window()
                 .unwrap()
-                .prompt_with_message("Ingredient name:")
+                .prompt_with_message(context_cloned.settings.language.t("ingredient.name_prompt"))
             {
                 Ok(Some(name)) => create_ingredient.emit(name),
                 Ok(None) => (),
@@ -123,8 +119,9 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
         }
     });
 
+    // Follow the quantity as it is typed, so that Ctrl+Enter adds it
     let state_cloned = state.clone();
-    let on_quantity_edit = Callback::from(move |e: Event| {
+    let on_quantity_edit = Callback::from(move |e: InputEvent| {
         let value = e
             .target()
             .expect("")
@@ -140,7 +137,7 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
 
     let props_cloned = props.clone();
     let state_cloned = state.clone();
-    let create_requirement = Callback::from(move |_| {
+    let create_requirement = Callback::from(move |_: ()| {
         if let Some(ingredient) = &state_cloned.selected_ingredient {
             props_cloned.create_requirement.emit((
                 ingredient.clone(),
@@ -183,28 +180,69 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
         state_cloned.dispatch(RequirementAddItemAction::Open);
     });
 
+    // Ctrl+Enter opens the row, then adds the requirement once filled
+    let state_cloned = state.clone();
+    let create_cloned = create_requirement.clone();
+    use_event_with_window("keydown", move |e: KeyboardEvent| {
+        if !(e.key() == "Enter" && (e.ctrl_key() || e.meta_key())) {
+            return;
+        }
+        e.prevent_default();
+        match state_cloned.mode {
+            RequirementAddItemMode::Collapsed => {
+                state_cloned.dispatch(RequirementAddItemAction::Open)
+            }
+            RequirementAddItemMode::Open => create_cloned.emit(()),
+        }
+    });
+
+    // Esc collapses the row before reaching the editor, which sees the event
+    // as handled
+    let state_cloned = state.clone();
+    let on_row_keydown = Callback::from(move |e: KeyboardEvent| {
+        if e.key() == "Escape" {
+            e.prevent_default();
+            state_cloned.dispatch(RequirementAddItemAction::Close);
+        }
+    });
+
+    let select = use_node_ref();
+    let select_cloned = select.clone();
+    use_effect_with_deps(
+        move |mode| {
+            if *mode == RequirementAddItemMode::Open {
+                if let Some(select) = select_cloned.cast::<HtmlElement>() {
+                    select.focus().ok();
+                }
+            }
+        },
+        state.mode.clone(),
+    );
+
+    let language = context.settings.language;
     let state_cloned = state.clone();
     html! {
         if state_cloned.mode == RequirementAddItemMode::Collapsed {
             <button
                 onclick={on_add_clicked}>
-                {"Add ingredient"}
+                {language.t("ingredient.add")}
             </button>
         } else {
-            <tr key={"requirement_add"}>
+            <tr key={"requirement_add"} onkeydown={on_row_keydown}>
                 <td>
                     <select
+                        ref={select}
                         autocomplete="off"
                         onchange={on_ingredient_select}>
                         <option
                             hidden=true
                             disabled=true
                             selected={state_cloned.selected_ingredient.is_none()}>
-                            {"Ingredient"}
+                            {language.t("edit.ingredient")}
                         </option>
                         <option
                             value="new">
-                            {"Nouvel ingredient"}
+                            {language.t("edit.new_ingredient")}
                         </option>
                         {option_html}
                     </select>
@@ -212,9 +250,9 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
                 <td>
                     <input
                         type="text"
-                        placeholder="Quantity"
+                        placeholder={language.t("edit.quantity")}
                         value={state.quantity_buffer.clone()}
-                        onchange={on_quantity_edit}
+                        oninput={on_quantity_edit}
                     />
                 </td>
                 <td>
@@ -227,8 +265,8 @@ pub fn requirement_add_item(props: &RequirementAddItemProps) -> Html {
                 <td>
                     <button
                         disabled={state_cloned.selected_ingredient.is_none()}
-                        onclick={create_requirement}>
-                        {"Add"}
+                        onclick={create_requirement.reform(|_| ())}>
+                        {language.t("action.add")}
                     </button>
                 </td>
             </tr>
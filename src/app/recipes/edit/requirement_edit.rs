@@ -1,3 +1,4 @@
+use crate::app::AppContext;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -11,6 +12,9 @@ pub struct RequirementEditItemProps {
 
 #[function_component(RequirementEditItem)]
 pub fn requirement_edit_item(props: &RequirementEditItemProps) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+
     let props_cloned = props.clone();
     let on_quantity_edit = Callback::from(move |e: Event| {
         let quantity = e
@@ -50,7 +54,7 @@ pub fn requirement_edit_item(props: &RequirementEditItemProps) -> Html {
                 checked={props.requirement.optional}
                 onclick={on_optional_edit}
             /></td>
-            <td><button onclick={delete_requirement}>{"Delete"}</button></td>
+            <td><button onclick={delete_requirement}>{language.t("action.delete")}</button></td>
         </tr>
     }
 }
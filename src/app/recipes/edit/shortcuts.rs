@@ -0,0 +1,105 @@
+use crate::app::AppContext;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+
+// Keys and descriptions listed in the help overlay
+static SHORTCUTS: &[(&str, &str)] = &[
+    ("Ctrl+S", "shortcuts.save"),
+    ("Ctrl+Enter", "shortcuts.add_requirement"),
+    ("↑ ↓", "shortcuts.navigate"),
+    ("Esc", "shortcuts.close"),
+    ("?", "shortcuts.help"),
+    ("Ctrl+K", "shortcuts.palette"),
+];
+
+// Whether the key was typed in a field, where it belongs to the text
+pub fn is_typing(e: &KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|target| target.dyn_into::<Element>().ok())
+        .map(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+        .unwrap_or(false)
+}
+
+// Leave the focused field so that its pending edit is committed
+pub fn commit_focused_field() {
+    if let Some(element) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.active_element())
+        .and_then(|element| element.dyn_into::<HtmlElement>().ok())
+    {
+        element.blur().ok();
+    }
+}
+
+// Move the focus from the input of a table row to the same column of the
+// row above or below. Returns whether the focus moved.
+pub fn focus_neighbour_row(e: &KeyboardEvent, up: bool) -> bool {
+    let neighbour = || -> Option<HtmlElement> {
+        let target = e.target()?.dyn_into::<Element>().ok()?;
+        if target.tag_name() != "INPUT" {
+            return None;
+        }
+
+        let cell = target.closest("td").ok()??;
+        let mut column = 0;
+        let mut previous = cell.previous_element_sibling();
+        while let Some(sibling) = previous {
+            column += 1;
+            previous = sibling.previous_element_sibling();
+        }
+
+        let row = cell.parent_element()?;
+        let next_row = match up {
+            true => row.previous_element_sibling()?,
+            false => row.next_element_sibling()?,
+        };
+        let mut next_cell = next_row.first_element_child()?;
+        for _ in 0..column {
+            next_cell = next_cell.next_element_sibling()?;
+        }
+        next_cell
+            .query_selector("input, select, button")
+            .ok()??
+            .dyn_into::<HtmlElement>()
+            .ok()
+    };
+
+    match neighbour() {
+        Some(element) => element.focus().is_ok(),
+        None => false,
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ShortcutHelpProps {
+    pub on_close: Callback<()>,
+}
+
+#[function_component(ShortcutHelp)]
+pub fn shortcut_help(props: &ShortcutHelpProps) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+
+    let rows = SHORTCUTS
+        .iter()
+        .map(|(keys, description)| {
+            html! {
+                <tr>
+                    <td><kbd>{*keys}</kbd></td>
+                    <td>{language.t(*description)}</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="import-dialog shortcut-help" onclick={props.on_close.reform(|_| ())}>
+            <div class="import-dialog-content" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                <h3>{language.t("shortcuts.title")}</h3>
+                <table>{rows}</table>
+                <button onclick={props.on_close.reform(|_| ())}>{language.t("action.close")}</button>
+            </div>
+        </div>
+    }
+}
@@ -1,3 +1,4 @@
+use crate::app::AppContext;
 use std::ops::Deref;
 use wasm_bindgen::JsCast;
 use web_sys::{EventTarget, HtmlInputElement};
@@ -16,6 +17,8 @@ struct TagAddItemState {
 #[function_component(TagAddItem)]
 pub fn tag_add_item(props: &TagAddItemProps) -> Html {
     let state = use_state(TagAddItemState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
 
     let state_cloned = state.clone();
     let on_label_edit = Callback::from(move |e: Event| {
@@ -43,7 +46,7 @@ pub fn tag_add_item(props: &TagAddItemProps) -> Html {
                 value={(*state).label_name_buffer.clone()}
                 onchange={on_label_edit}
             />
-            <button onclick={create_tag}>{"Add"}</button>
+            <button onclick={create_tag}>{language.t("action.add")}</button>
         </li>
     }
 }
@@ -1,3 +1,4 @@
+use crate::app::AppContext;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
@@ -8,6 +9,9 @@ pub struct TagEditItemProps {
 
 #[function_component(TagEditItem)]
 pub fn tag_edit_item(props: &TagEditItemProps) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
+
     let props_cloned = props.clone();
     let on_tag_delete = Callback::from(move |_| {
         props_cloned
@@ -18,7 +22,7 @@ pub fn tag_edit_item(props: &TagEditItemProps) -> Html {
     html! {
         <li key={props.label.id.as_str()}>
             <span>{props.label.name.as_str()}</span>
-            <button onclick={on_tag_delete}>{"Delete"}</button>
+            <button onclick={on_tag_delete}>{language.t("action.delete")}</button>
         </li>
     }
 }
@@ -0,0 +1,423 @@
+use crate::app::i18n::Text;
+use crate::app::recipes::formats::{PortableDependency, PortableRecipe, PortableRequirement};
+
+// Cooklang (https://cooklang.org) support. Ingredients are written
+// `@name{quantity%unit}`, optional ones `@?name{}`, and other recipes are
+// referenced as `@./name{quantity}`. Steps only made of ingredients are used
+// to list requirements without adding to the directions. Markers meant as text
+// are preceded by a backslash, which Cooklang itself lacks.
+
+fn strip_comments(source: &str) -> String {
+    let mut out = String::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("[-") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find("-]") {
+            Some(end) => &rest[start + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+
+    out.lines()
+        .map(|line| match line.find("--") {
+            Some(index) => &line[..index],
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_quantity(raw: &str) -> String {
+    raw.split('%')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_quantity(quantity: &str) -> String {
+    let words: Vec<&str> = quantity.split_whitespace().collect();
+
+    match words.as_slice() {
+        [amount, unit] if amount.starts_with(|c: char| c.is_ascii_digit()) => {
+            format!("{}%{}", amount, unit)
+        }
+        _ => quantity.trim().to_string(),
+    }
+}
+
+// Characters a backslash turns back into text
+static ESCAPED: &str = "\\@#~->";
+
+// Escape what would be read as a component, a comment or metadata in text
+fn escape(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut line_start = true;
+
+    for (index, c) in chars.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|previous| chars[previous]);
+        let escaped = match c {
+            '\\' | '@' | '#' | '~' => true,
+            '-' => matches!(previous, Some('-' | '[')),
+            '>' => line_start && chars.get(index + 1) == Some(&'>'),
+            _ => false,
+        };
+        if escaped {
+            out.push('\\');
+        }
+        out.push(*c);
+
+        line_start = *c == '\n' || (line_start && c.is_whitespace());
+    }
+
+    out
+}
+
+enum Token {
+    Text(String),
+    Ingredient(String, String, bool),
+    Reference(String, String, bool),
+}
+
+// Read a component name and its optional quantity, `chars[start..]` being the
+// text right after the `@`, `#` or `~` marker
+fn read_component(chars: &[char], start: usize) -> (String, Option<String>, usize) {
+    let brace = chars[start..]
+        .iter()
+        .position(|c| matches!(c, '{' | '@' | '#' | '~' | '\n'))
+        .map(|offset| start + offset)
+        .filter(|index| chars[*index] == '{');
+
+    match brace {
+        Some(open) => {
+            let close = chars[open..]
+                .iter()
+                .position(|c| *c == '}')
+                .map(|offset| open + offset)
+                .unwrap_or(chars.len());
+            let name: String = chars[start..open].iter().collect();
+            let quantity: String = chars[open + 1..close.min(chars.len())].iter().collect();
+            (name.trim().to_string(), Some(quantity), close + 1)
+        }
+        None => {
+            let end = chars[start..]
+                .iter()
+                .position(|c| !(c.is_alphanumeric() || *c == '_' || *c == '-'))
+                .map(|offset| start + offset)
+                .unwrap_or(chars.len());
+            (chars[start..end].iter().collect(), None, end)
+        }
+    }
+}
+
+fn tokenize(step: &str) -> Vec<Token> {
+    let chars: Vec<char> = step.chars().collect();
+    let mut tokens = vec![];
+    let mut text = String::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        match chars[index] {
+            '@' => {
+                let mut start = index + 1;
+                let optional = chars.get(start) == Some(&'?');
+                if optional {
+                    start += 1;
+                }
+                let reference = chars[start..].starts_with(&['.', '/']);
+                if reference {
+                    start += 2;
+                }
+
+                let (name, quantity, end) = read_component(&chars, start);
+                // A lone `@`, as in "serve @ room temperature", is text
+                if name.is_empty() {
+                    text.push('@');
+                    index += 1;
+                    continue;
+                }
+                let quantity = parse_quantity(&quantity.unwrap_or_default());
+                tokens.push(Token::Text(std::mem::take(&mut text)));
+                tokens.push(match reference {
+                    true => Token::Reference(name, quantity, optional),
+                    false => Token::Ingredient(name, quantity, optional),
+                });
+                index = end;
+            }
+            '#' => {
+                let (name, _, end) = read_component(&chars, index + 1);
+                if name.is_empty() {
+                    text.push('#');
+                    index += 1;
+                    continue;
+                }
+                text.push_str(&name);
+                index = end;
+            }
+            '~' => {
+                let (_, quantity, end) = read_component(&chars, index + 1);
+                text.push_str(&parse_quantity(&quantity.unwrap_or_default()));
+                index = end;
+            }
+            '\\' if chars
+                .get(index + 1)
+                .map(|c| ESCAPED.contains(*c))
+                .unwrap_or(false) =>
+            {
+                text.push(chars[index + 1]);
+                index += 2;
+            }
+            c => {
+                text.push(c);
+                index += 1;
+            }
+        }
+    }
+
+    tokens.push(Token::Text(text));
+    tokens
+}
+
+pub fn parse(source: &str) -> Result<PortableRecipe, Text> {
+    let mut recipe = PortableRecipe::default();
+    let mut body = vec![];
+
+    let source = strip_comments(source);
+
+    for line in source.lines() {
+        match line.trim().strip_prefix(">>") {
+            Some(metadata) => {
+                let (key, value) = metadata.split_once(':').unwrap_or((metadata, ""));
+                let value = value.trim().to_string();
+                match key.trim().to_lowercase().as_str() {
+                    "title" | "name" => recipe.name = value,
+                    "author" | "source" => recipe.author = value,
+                    "tags" => {
+                        recipe.tags = value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|tag| !tag.is_empty())
+                            .map(String::from)
+                            .collect()
+                    }
+                    _ => (),
+                }
+            }
+            None => body.push(line.trim_end()),
+        }
+    }
+
+    let mut directions = vec![];
+    for step in body.join("\n").split("\n\n") {
+        let mut text = String::new();
+
+        for token in tokenize(step.trim()) {
+            match token {
+                Token::Text(string) => text.push_str(&string),
+                Token::Ingredient(name, quantity, optional) => {
+                    text.push_str(&name);
+                    if !recipe.requirements.iter().any(|r| r.ingredient == name) {
+                        recipe.requirements.push(PortableRequirement {
+                            ingredient: name,
+                            quantity,
+                            optional,
+                        });
+                    }
+                }
+                Token::Reference(name, quantity, optional) => {
+                    text.push_str(&name);
+                    if !recipe.dependencies.iter().any(|d| d.recipe == name) {
+                        recipe.dependencies.push(PortableDependency {
+                            recipe: name,
+                            quantity,
+                            optional,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Steps made only of ingredients just list requirements
+        let plain: String = tokenize(step.trim())
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Text(string) => Some(string),
+                _ => None,
+            })
+            .collect();
+
+        if !plain.trim().is_empty() {
+            directions.push(text.trim().to_string());
+        }
+    }
+
+    if recipe.name.is_empty() && recipe.requirements.is_empty() && directions.is_empty() {
+        return Err(Text::new("import.no_cooklang", &[]));
+    }
+
+    recipe.directions = directions.join("\n\n");
+    Ok(recipe)
+}
+
+pub fn serialize(recipe: &PortableRecipe) -> String {
+    let mut out = vec![format!(">> title: {}", recipe.name)];
+
+    if !recipe.author.is_empty() {
+        out.push(format!(">> author: {}", recipe.author));
+    }
+
+    if !recipe.tags.is_empty() {
+        out.push(format!(">> tags: {}", recipe.tags.join(", ")));
+    }
+
+    let components: Vec<String> = recipe
+        .dependencies
+        .iter()
+        .map(|dependency| {
+            format!(
+                "@{}./{}{{{}}}",
+                if dependency.optional { "?" } else { "" },
+                dependency.recipe,
+                format_quantity(&dependency.quantity)
+            )
+        })
+        .chain(recipe.requirements.iter().map(|requirement| {
+            format!(
+                "@{}{}{{{}}}",
+                if requirement.optional { "?" } else { "" },
+                requirement.ingredient,
+                format_quantity(&requirement.quantity)
+            )
+        }))
+        .collect();
+
+    if !components.is_empty() {
+        out.push(String::new());
+        out.push(components.join("\n"));
+    }
+
+    if !recipe.directions.trim().is_empty() {
+        out.push(String::new());
+        out.push(escape(recipe.directions.trim()));
+    }
+
+    out.push(String::new());
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> PortableRecipe {
+        PortableRecipe {
+            name: String::from("Tarte aux pommes"),
+            author: String::from("Mamie"),
+            directions: String::from("Étaler la pâte.\n\nDisposer les pommes et cuire 30 minutes."),
+            requirements: vec![
+                PortableRequirement {
+                    ingredient: String::from("pommes"),
+                    quantity: String::from("4"),
+                    optional: false,
+                },
+                PortableRequirement {
+                    ingredient: String::from("sucre roux"),
+                    quantity: String::from("50 g"),
+                    optional: false,
+                },
+                PortableRequirement {
+                    ingredient: String::from("cannelle"),
+                    quantity: String::new(),
+                    optional: true,
+                },
+            ],
+            dependencies: vec![
+                PortableDependency {
+                    recipe: String::from("Pâte brisée"),
+                    quantity: String::from("1"),
+                    optional: false,
+                },
+                PortableDependency {
+                    recipe: String::from("Crème anglaise"),
+                    quantity: String::from("20 cl"),
+                    optional: true,
+                },
+            ],
+            tags: vec![String::from("dessert"), String::from("automne")],
+        }
+    }
+
+    #[test]
+    fn round_trip_from_recipe() {
+        let recipe = sample();
+        assert_eq!(parse(&serialize(&recipe)), Ok(recipe));
+    }
+
+    #[test]
+    fn round_trip_with_markers_in_directions() {
+        let mut recipe = sample();
+        recipe.directions = String::from(
+            "Whisk -- do not stop --- ever.\n\n\
+Heat [-gently-] to 180 °C, @ home the #1 tip is ~5 min.\n\n\
+>> not metadata\n  >> nor this\nA \\ stays, as does \\@.",
+        );
+
+        assert_eq!(parse(&serialize(&recipe)), Ok(recipe));
+    }
+
+    #[test]
+    fn lone_markers_are_text() {
+        let recipe = parse("Serve @ room temperature, # 2 on the list.").unwrap();
+
+        assert!(recipe.requirements.is_empty());
+        assert_eq!(
+            recipe.directions,
+            "Serve @ room temperature, # 2 on the list."
+        );
+    }
+
+    #[test]
+    fn round_trip_from_source() {
+        let source = "\
+>> title: Crêpes
+>> tags: breakfast
+
+Mix @flour{250%g} with @eggs{3} in a #large bowl{}. -- no lumps
+Add @whole milk{50%cl} slowly.
+
+Rest for ~{1%hour}, then serve with @?./Caramel{}.
+";
+        let recipe = parse(source).unwrap();
+
+        assert_eq!(recipe.name, "Crêpes");
+        assert_eq!(recipe.tags, vec![String::from("breakfast")]);
+        assert_eq!(
+            recipe.directions,
+            "Mix flour with eggs in a large bowl.\nAdd whole milk slowly.\n\nRest for 1 hour, then serve with Caramel."
+        );
+        assert_eq!(
+            recipe.requirements[2],
+            PortableRequirement {
+                ingredient: String::from("whole milk"),
+                quantity: String::from("50 cl"),
+                optional: false,
+            }
+        );
+        assert_eq!(
+            recipe.dependencies,
+            vec![PortableDependency {
+                recipe: String::from("Caramel"),
+                quantity: String::new(),
+                optional: true,
+            }]
+        );
+
+        let reparsed = parse(&serialize(&recipe)).unwrap();
+        assert_eq!(reparsed.requirements, recipe.requirements);
+        assert_eq!(reparsed.dependencies, recipe.dependencies);
+        assert_eq!(reparsed.tags, recipe.tags);
+    }
+}
@@ -0,0 +1,335 @@
+use crate::app::i18n::Text;
+use crate::app::recipes::classifications::{get_classifications, Classifications};
+use crate::app::recipes::formats::{PortableRecipe, PortableRequirement};
+use ladle::models::Recipe;
+use serde_json::{json, Value};
+use wasm_bindgen::JsValue;
+
+static SCRIPT_ID: &str = "recipe-json-ld";
+
+// schema.org has no dairy-free diet, low lactose being a different promise
+fn diet(class: &Classifications) -> Option<&'static str> {
+    match class {
+        Classifications::Vegan => Some("https://schema.org/VeganDiet"),
+        Classifications::Vegetarian => Some("https://schema.org/VegetarianDiet"),
+        Classifications::DairyFree => None,
+        Classifications::GlutenFree => Some("https://schema.org/GlutenFreeDiet"),
+    }
+}
+
+fn steps(directions: &str) -> Vec<Value> {
+    directions
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .map(|step| json!({"@type": "HowToStep", "text": step}))
+        .collect()
+}
+
+// Build a schema.org Recipe from a recipe and the recipes it depends on.
+// `sections` lists every recipe to include in preparation order, the main
+// recipe being the last one. `ingredients` holds the quantity and name of the
+// ingredients used, and `classifications` is computed from them, the diets
+// being left out while it is unknown.
+pub fn to_json_ld(
+    main: &Recipe,
+    sections: &[&Recipe],
+    ingredients: &[(&str, &str)],
+    classifications: Option<&ladle::models::Classifications>,
+) -> Value {
+    let ingredients: Vec<String> = ingredients
+        .iter()
+        .map(|(quantity, name)| match quantity.trim().len() {
+            0 => name.to_string(),
+            _ => format!("{} {}", quantity.trim(), name),
+        })
+        .collect();
+
+    let instructions: Vec<Value> = match sections.len() {
+        0 | 1 => steps(&main.directions),
+        _ => sections
+            .iter()
+            .map(|recipe| {
+                json!({
+                    "@type": "HowToSection",
+                    "name": recipe.name,
+                    "itemListElement": steps(&recipe.directions),
+                })
+            })
+            .collect(),
+    };
+
+    let keywords = main
+        .tags
+        .iter()
+        .map(|label| label.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut value = json!({
+        "@context": "https://schema.org",
+        "@type": "Recipe",
+        "name": main.name,
+        "author": {"@type": "Person", "name": main.author},
+        "keywords": keywords,
+        "recipeIngredient": ingredients,
+        "recipeInstructions": instructions,
+    });
+
+    if let Some(classifications) = classifications {
+        let diets: Vec<&str> = get_classifications(classifications)
+            .iter()
+            .filter_map(diet)
+            .collect();
+        value["suitableForDiet"] = json!(diets);
+    }
+
+    value
+}
+
+// Add the JSON-LD description to the page head, replacing any previous one
+pub fn embed(value: &Value) -> Result<web_sys::Element, JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or(JsValue::from_str("No document available"))?;
+    let head = document
+        .head()
+        .ok_or(JsValue::from_str("No head in document"))?;
+
+    if let Some(previous) = document.get_element_by_id(SCRIPT_ID) {
+        previous.remove();
+    }
+
+    let script = document.create_element("script")?;
+    script.set_id(SCRIPT_ID);
+    script.set_attribute("type", "application/ld+json")?;
+    script.set_text_content(Some(&value.to_string()));
+    head.append_child(&script)?;
+
+    Ok(script)
+}
+
+static UNITS: &[&str] = &[
+    "g", "gr", "kg", "mg", "ml", "cl", "dl", "l", "tsp", "tbsp", "cup", "cups", "oz", "lb", "lbs",
+    "pinch", "cas", "cac", "c.", "cs", "cc", "pincee", "sachet", "sachets", "tranche", "tranches",
+    "gousse", "gousses",
+];
+
+fn is_amount(word: &str) -> bool {
+    word.chars()
+        .next()
+        .map(|c| c.is_ascii_digit() || "½¼¾⅓⅔".contains(c))
+        .unwrap_or(false)
+}
+
+// Split an ingredient line such as "200 g de farine" into its quantity and
+// the name of the ingredient
+pub fn split_ingredient_line(line: &str) -> PortableRequirement {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let mut split = 0;
+
+    while split < words.len() && is_amount(words[split]) {
+        split += 1;
+    }
+
+    if split > 0 && split < words.len() {
+        let unit = unidecode::unidecode(words[split]).to_lowercase();
+        if UNITS.contains(&unit.as_str()) {
+            split += 1;
+        }
+    }
+
+    let mut name = words[split..].join(" ");
+    for article in ["de ", "d'", "of "] {
+        if let Some(stripped) = name.strip_prefix(article) {
+            name = stripped.to_string();
+        }
+    }
+
+    PortableRequirement {
+        ingredient: name.trim().to_string(),
+        quantity: words[..split].join(" "),
+        optional: false,
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.trim().to_string()),
+        Value::Object(map) => map.get("name").and_then(text),
+        Value::Array(list) => list.iter().find_map(text),
+        _ => None,
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match value.get("@type") {
+        Some(Value::String(kind)) => kind == expected,
+        Some(Value::Array(kinds)) => kinds.iter().any(|kind| kind == expected),
+        _ => false,
+    }
+}
+
+fn find_recipe(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Object(_) if has_type(value, "Recipe") => Some(value),
+        Value::Object(map) => map.get("@graph").and_then(find_recipe),
+        Value::Array(list) => list.iter().find_map(find_recipe),
+        _ => None,
+    }
+}
+
+fn instructions(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(string) => vec![string.trim().to_string()],
+        Value::Array(list) => list.iter().flat_map(instructions).collect(),
+        Value::Object(map) if has_type(value, "HowToSection") => {
+            let mut out = vec![];
+            if let Some(name) = map.get("name").and_then(text) {
+                out.push(format!("### {}", name));
+            }
+            if let Some(steps) = map.get("itemListElement") {
+                out.extend(instructions(steps));
+            }
+            out
+        }
+        Value::Object(map) => map.get("text").and_then(text).into_iter().collect(),
+        _ => vec![],
+    }
+}
+
+// Extract the JSON-LD blocks embedded in an HTML page
+fn extract_scripts(html: &str) -> Vec<&str> {
+    let mut out = vec![];
+    let mut rest = html;
+
+    while let Some(start) = rest.find("application/ld+json") {
+        rest = &rest[start..];
+        let content = match rest.find('>') {
+            Some(index) => &rest[index + 1..],
+            None => break,
+        };
+        match content.find("</script>") {
+            Some(end) => {
+                out.push(&content[..end]);
+                rest = &content[end..];
+            }
+            None => break,
+        }
+    }
+
+    out
+}
+
+fn from_value(value: &Value) -> Option<PortableRecipe> {
+    let recipe = find_recipe(value)?;
+
+    let requirements = match recipe.get("recipeIngredient") {
+        Some(Value::Array(lines)) => lines
+            .iter()
+            .filter_map(text)
+            .filter(|line| !line.is_empty())
+            .map(|line| split_ingredient_line(&line))
+            .collect(),
+        _ => vec![],
+    };
+
+    let tags = match recipe.get("keywords") {
+        Some(Value::String(keywords)) => keywords
+            .split(',')
+            .map(str::trim)
+            .filter(|keyword| !keyword.is_empty())
+            .map(String::from)
+            .collect(),
+        Some(Value::Array(keywords)) => keywords.iter().filter_map(text).collect(),
+        _ => vec![],
+    };
+
+    Some(PortableRecipe {
+        name: recipe.get("name").and_then(text).unwrap_or_default(),
+        author: recipe.get("author").and_then(text).unwrap_or_default(),
+        directions: recipe
+            .get("recipeInstructions")
+            .map(instructions)
+            .unwrap_or_default()
+            .join("\n\n"),
+        requirements,
+        dependencies: vec![],
+        tags,
+    })
+}
+
+// Read a recipe from either a JSON-LD document or an HTML page embedding one
+pub fn from_json_ld(source: &str) -> Result<PortableRecipe, Text> {
+    let trimmed = source.trim();
+
+    let blocks = match trimmed.starts_with('{') || trimmed.starts_with('[') {
+        true => vec![trimmed],
+        false => extract_scripts(trimmed),
+    };
+
+    if blocks.is_empty() {
+        return Err(Text::new("import.no_json_ld", &[]));
+    }
+
+    blocks
+        .iter()
+        .filter_map(|block| serde_json::from_str::<Value>(block).ok())
+        .find_map(|value| from_value(&value))
+        .ok_or(Text::new("import.no_recipe", &[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> (String, String) {
+        let requirement = split_ingredient_line(line);
+        (requirement.quantity, requirement.ingredient)
+    }
+
+    #[test]
+    fn split_quantity_and_unit() {
+        assert_eq!(
+            split("200 g de farine"),
+            (String::from("200 g"), String::from("farine"))
+        );
+        assert_eq!(
+            split("1 ½ cup of milk"),
+            (String::from("1 ½ cup"), String::from("milk"))
+        );
+        assert_eq!(
+            split("2 gousses d'ail"),
+            (String::from("2 gousses"), String::from("ail"))
+        );
+    }
+
+    #[test]
+    fn split_without_unit_or_quantity() {
+        assert_eq!(split("3 eggs"), (String::from("3"), String::from("eggs")));
+        assert_eq!(split("sel"), (String::new(), String::from("sel")));
+        assert_eq!(split("12"), (String::from("12"), String::new()));
+    }
+
+    #[test]
+    fn extract_embedded_blocks() {
+        let html = r#"<html><head>
+<script src="app.js"></script>
+<script type="application/ld+json">{"@type": "WebSite"}</script>
+<script type='application/ld+json'>
+{"@type": "Recipe"}
+</script>
+</head></html>"#;
+
+        assert_eq!(
+            extract_scripts(html),
+            vec![r#"{"@type": "WebSite"}"#, "\n{\"@type\": \"Recipe\"}\n"]
+        );
+        assert!(extract_scripts("<p>No data</p>").is_empty());
+    }
+
+    #[test]
+    fn extract_unterminated_block() {
+        assert!(extract_scripts(r#"<script type="application/ld+json">{}"#).is_empty());
+    }
+}
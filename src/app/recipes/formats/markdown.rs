@@ -0,0 +1,248 @@
+use crate::app::i18n::Language;
+use crate::app::recipes::classifications::get_classifications;
+use ladle::models::{Classifications, Recipe};
+
+// Escape the characters that would break a table cell
+fn cell(value: &str) -> String {
+    value.trim().replace('|', "\\|").replace('\n', " ")
+}
+
+// Requirement as exported, once exclusions and substitutions are applied
+pub struct Line<'a> {
+    pub quantity: &'a str,
+    pub name: &'a str,
+    pub optional: bool,
+}
+
+fn requirement_table(
+    recipe: &Recipe,
+    lines: &[Line],
+    included: &[&str],
+    language: Language,
+) -> Option<String> {
+    let mut out = format!(
+        "| {} | {} |\n| --- | --- |\n",
+        language.t("markdown.ingredient"),
+        language.t("markdown.quantity")
+    );
+    let optional = language.t("markdown.optional");
+    let mut rows = 0;
+
+    for line in lines.iter() {
+        let name = match line.optional {
+            true => format!("{} {}", cell(line.name), optional),
+            false => cell(line.name),
+        };
+        out.push_str(&format!("| {} | {} |\n", name, cell(line.quantity)));
+        rows += 1;
+    }
+
+    // Optional dependencies left out are not part of the sections
+    for dependency in recipe.dependencies.iter().filter(|dependency| {
+        !dependency.optional || included.contains(&dependency.recipe.id.as_str())
+    }) {
+        let name = match dependency.optional {
+            true => format!("*{}* {}", cell(&dependency.recipe.name), optional),
+            false => format!("*{}*", cell(&dependency.recipe.name)),
+        };
+        out.push_str(&format!("| {} | {} |\n", name, cell(&dependency.quantity)));
+        rows += 1;
+    }
+
+    (rows > 0).then_some(out)
+}
+
+// Build a self-contained Markdown document from a recipe and the recipes it
+// depends on. `sections` lists every recipe to include in preparation order
+// along with the requirements to list, the main recipe being the last one.
+// Classifications are left out when unknown. The text around the recipe is
+// written in `language`.
+pub fn to_markdown(
+    main: &Recipe,
+    sections: &[(&Recipe, Vec<Line>)],
+    classifications: Option<&Classifications>,
+    language: Language,
+) -> String {
+    let mut out = format!("# {}\n\n", main.name.trim());
+
+    if !main.author.trim().is_empty() {
+        out.push_str(&format!(
+            "{}\n\n",
+            language.tf("markdown.author", &[&main.author.trim()])
+        ));
+    }
+
+    if !main.tags.is_empty() {
+        let tags = main
+            .tags
+            .iter()
+            .map(|label| format!("`{}`", label.name))
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push_str(&format!("{}\n\n", tags));
+    }
+
+    let badges = classifications
+        .map(get_classifications)
+        .unwrap_or_default()
+        .iter()
+        .map(|class| format!("**{}**", class.name(language)))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    if !badges.is_empty() {
+        out.push_str(&format!("{}\n\n", badges));
+    }
+
+    let titled = sections.len() > 1;
+    let included: Vec<&str> = sections
+        .iter()
+        .map(|(recipe, _)| recipe.id.as_str())
+        .collect();
+
+    out.push_str(&format!("## {}\n\n", language.t("recipe.ingredients")));
+    for (recipe, lines) in sections.iter() {
+        let table = match requirement_table(recipe, lines, &included, language) {
+            Some(table) => table,
+            None => continue,
+        };
+        if titled {
+            out.push_str(&format!("### {}\n\n", recipe.name.trim()));
+        }
+        out.push_str(&table);
+        out.push('\n');
+    }
+
+    out.push_str(&format!("## {}\n\n", language.t("recipe.directions")));
+    for (recipe, _) in sections.iter() {
+        if recipe.directions.trim().is_empty() {
+            continue;
+        }
+        if titled {
+            out.push_str(&format!("### {}\n\n", recipe.name.trim()));
+        }
+        out.push_str(recipe.directions.trim());
+        out.push_str("\n\n");
+    }
+
+    format!("{}\n", out.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn recipe(id: &str, name: &str, directions: &str, dependencies: serde_json::Value) -> Recipe {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": name,
+            "author": "",
+            "directions": directions,
+            "information": "",
+            "requirements": [],
+            "dependencies": dependencies,
+            "tags": [],
+            // What the server computed, not to be trusted by the export
+            "classifications": {
+                "dairy": false,
+                "meat": false,
+                "gluten": false,
+                "animal_product": false,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn dependency(id: &str, name: &str, optional: bool) -> serde_json::Value {
+        json!({
+            "recipe": {"id": id, "name": name},
+            "quantity": "1",
+            "optional": optional,
+        })
+    }
+
+    fn line<'a>(quantity: &'a str, name: &'a str, optional: bool) -> Line<'a> {
+        Line {
+            quantity,
+            name,
+            optional,
+        }
+    }
+
+    #[test]
+    fn lists_the_given_requirements() {
+        let main = recipe("1", "Crêpes", "Mix | cook", json!([]));
+        let sections = vec![(
+            &main,
+            vec![line("1 l", "Oat milk", false), line("", "Sugar", true)],
+        )];
+
+        let markdown = to_markdown(&main, &sections, None, Language::English);
+        assert_eq!(
+            markdown,
+            "# Crêpes\n\n## Ingredients\n\n\
+             | Ingredient | Quantity |\n| --- | --- |\n\
+             | Oat milk | 1 l |\n| Sugar (optional) |  |\n\n\
+             ## Directions\n\nMix | cook\n"
+        );
+    }
+
+    #[test]
+    fn badges_come_from_the_given_classifications() {
+        let main = recipe("1", "Crêpes", "", json!([]));
+        let sections = vec![(&main, vec![])];
+        let dairy = Classifications {
+            dairy: true,
+            animal_product: true,
+            ..Classifications::default()
+        };
+
+        let markdown = to_markdown(&main, &sections, Some(&dairy), Language::English);
+        assert!(markdown.contains("**Gluten-free** · **Vegetarian**\n"));
+        // Unknown classifications are left out rather than taken from the server
+        let markdown = to_markdown(&main, &sections, None, Language::English);
+        assert!(!markdown.contains("**"));
+    }
+
+    #[test]
+    fn excluded_dependencies_are_left_out() {
+        let dough = recipe("2", "Dough", "Knead", json!([]));
+        let cream = recipe("3", "Cream", "Whip", json!([]));
+        let pie = recipe(
+            "1",
+            "Pie",
+            "Bake",
+            json!([
+                dependency("2", "Dough", false),
+                dependency("3", "Cream", true)
+            ]),
+        );
+        let sections = vec![
+            (&dough, vec![line("200 g", "Flour", false)]),
+            (&pie, vec![]),
+        ];
+
+        let markdown = to_markdown(&pie, &sections, None, Language::English);
+        assert!(markdown.contains("| *Dough* | 1 |"));
+        assert!(!markdown.contains("Cream"));
+        assert!(markdown.contains("### Dough\n\nKnead"));
+        assert!(markdown.contains("### Pie\n\nBake"));
+    }
+
+    #[test]
+    fn text_follows_the_language() {
+        let main = recipe("1", "Crêpes", "", json!([]));
+        let sections = vec![(&main, vec![line("", "Sucre", true)])];
+
+        let markdown = to_markdown(&main, &sections, None, Language::French);
+        assert!(markdown.contains(&format!(
+            "| {} | {} |",
+            Language::French.t("markdown.ingredient"),
+            Language::French.t("markdown.quantity")
+        )));
+        assert!(markdown.contains(&format!(
+            "Sucre {}",
+            Language::French.t("markdown.optional")
+        )));
+    }
+}
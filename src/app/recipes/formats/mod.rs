@@ -0,0 +1,58 @@
+pub mod cooklang;
+pub mod jsonld;
+pub mod markdown;
+
+// Recipe as read from or written to an external format, referring to
+// ingredients and other recipes by name rather than by id
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PortableRecipe {
+    pub name: String,
+    pub author: String,
+    pub directions: String,
+    pub requirements: Vec<PortableRequirement>,
+    pub dependencies: Vec<PortableDependency>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PortableRequirement {
+    pub ingredient: String,
+    pub quantity: String,
+    pub optional: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PortableDependency {
+    pub recipe: String,
+    pub quantity: String,
+    pub optional: bool,
+}
+
+impl From<&ladle::models::Recipe> for PortableRecipe {
+    fn from(recipe: &ladle::models::Recipe) -> Self {
+        PortableRecipe {
+            name: recipe.name.clone(),
+            author: recipe.author.clone(),
+            directions: recipe.directions.clone(),
+            requirements: recipe
+                .requirements
+                .iter()
+                .map(|requirement| PortableRequirement {
+                    ingredient: requirement.ingredient.name.clone(),
+                    quantity: requirement.quantity.clone(),
+                    optional: requirement.optional,
+                })
+                .collect(),
+            dependencies: recipe
+                .dependencies
+                .iter()
+                .map(|dependency| PortableDependency {
+                    recipe: dependency.recipe.name.clone(),
+                    quantity: dependency.quantity.clone(),
+                    optional: dependency.optional,
+                })
+                .collect(),
+            tags: recipe.tags.iter().map(|label| label.name.clone()).collect(),
+        }
+    }
+}
@@ -1,61 +1,66 @@
-use crate::app::{status_bar::Message, AppContext, Route};
+use crate::app::{status_bar::Message, sync::CacheChange, AppContext, Route};
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-static RECIPE_NAME_PROMPT: &str = "Recipe name:";
-
 #[derive(Clone, Default)]
 struct RecipeCreateState {}
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct RecipeCreateProps {}
 
-#[function_component(RecipeCreateButton)]
-pub fn recipe_create_button(_props: &RecipeCreateProps) -> Html {
-    let _state = use_state(RecipeCreateState::default);
-    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
-    let navigator = use_navigator().unwrap();
+// Ask for a name, then create the recipe and open it
+pub fn create_recipe_prompt(context: &AppContext, navigator: &Navigator) {
+    let name = match web_sys::window()
+        .unwrap()
+        .prompt_with_message(context.settings.language.t("recipe.name_prompt"))
+    {
+        Ok(Some(name)) => name,
+        Ok(None) => return,
+        Err(error) => {
+            context.status.emit(Message::Error(
+                error.as_string().unwrap_or(String::default()),
+                chrono::Utc::now(),
+            ));
+            return;
+        }
+    };
 
     let context_cloned = context.clone();
-    let name_submit = Callback::from(move |name: String| {
-        let context_cloned = context_cloned.clone();
-        let nc = navigator.clone();
-        wasm_bindgen_futures::spawn_local(async move {
-            match ladle::recipe_create(
-                context_cloned.settings.server_url.as_str(),
-                name.as_str(),
-                "",
-                "",
-                "",
-            )
+    let nc = navigator.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        match context_cloned
+            .backend
+            .recipe_create(name.as_str(), "", "", "")
             .await
-            {
-                Ok(recipe) => nc.push(&Route::ShowRecipe { id: recipe.id }),
-                Err(error) => context_cloned
-                    .status
-                    .emit(Message::Error(error.to_string(), chrono::Utc::now())),
-            }
-        });
-    });
-
-    let context_cloned = context.clone();
-    let name_prompt = Callback::from(move |_| {
-        match web_sys::window()
-            .unwrap()
-            .prompt_with_message(RECIPE_NAME_PROMPT)
         {
-            Ok(Some(name)) => name_submit.emit(name),
-            Ok(None) => (),
+            Ok(recipe) => {
+                nc.push(&Route::ShowRecipe {
+                    id: recipe.id.clone(),
+                });
+                context_cloned
+                    .update_cache
+                    .emit(CacheChange::RecipeIndexed(recipe));
+            }
             Err(error) => context_cloned.status.emit(Message::Error(
-                error.as_string().unwrap_or(String::default()),
+                error.describe(context_cloned.settings.language),
                 chrono::Utc::now(),
             )),
         }
     });
+}
+
+#[function_component(RecipeCreateButton)]
+pub fn recipe_create_button(_props: &RecipeCreateProps) -> Html {
+    let _state = use_state(RecipeCreateState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let navigator = use_navigator().unwrap();
+
+    let context_cloned = context.clone();
+    let name_prompt = Callback::from(move |_| create_recipe_prompt(&context_cloned, &navigator));
 
     html! {
         <button class="create-item create-recipe" onclick={name_prompt}>
-            {"Add recipe"}
+            {context.settings.language.t("recipe.add")}
         </button>
     }
 }
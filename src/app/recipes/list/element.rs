@@ -1,4 +1,5 @@
-use crate::app::Route;
+use crate::app::profile::recipe_warnings;
+use crate::app::{AppContext, Route};
 use ladle::models::RecipeIndex;
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -14,11 +15,25 @@ pub fn recipe_element(
         item: RecipeIndex { id, name },
     }: &RecipeElementProps,
 ) -> Html {
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+
+    let language = context.settings.language;
+    let warning = recipe_warnings(
+        language,
+        &context.settings.profile,
+        &context.recipe_details,
+        id,
+    )
+    .describe(language);
+
     html! {
         <li class={"recipe-item"} key={id.as_str()}>
             <Link<Route> to={Route::ShowRecipe {id:id.clone()}}>
                 {name}
             </Link<Route>>
+            if let Some(warning) = warning {
+                <span class={"diet-warning"}>{warning}</span>
+            }
             <span class={"knife-id"}>{id.as_str()}</span>
         </li>
     }
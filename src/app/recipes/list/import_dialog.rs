@@ -0,0 +1,647 @@
+use crate::app::backend::Backend;
+use crate::app::i18n::{Language, Text};
+use crate::app::ingredients::matching::{best_match, match_ingredient, normalize};
+use crate::app::recipes::formats::{cooklang, jsonld, PortableRecipe, PortableRequirement};
+use crate::app::{status_bar::Message, sync::CacheChange, AppContext, Route};
+use futures::future::join_all;
+use ladle::models::{IngredientIndex, RecipeIndex};
+use std::rc::Rc;
+use unidecode::unidecode;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ImportFormat {
+    JsonLd,
+    Cooklang,
+}
+
+impl ImportFormat {
+    fn parse(&self, source: &str) -> Result<PortableRecipe, Text> {
+        match self {
+            ImportFormat::JsonLd => jsonld::from_json_ld(source),
+            ImportFormat::Cooklang => cooklang::parse(source),
+        }
+    }
+
+    fn label(&self, language: Language) -> &'static str {
+        match self {
+            ImportFormat::JsonLd => language.t("import.json_ld"),
+            ImportFormat::Cooklang => language.t("import.cooklang"),
+        }
+    }
+
+    fn placeholder(&self, language: Language) -> &'static str {
+        match self {
+            ImportFormat::JsonLd => language.t("import.json_ld_placeholder"),
+            ImportFormat::Cooklang => language.t("import.cooklang_placeholder"),
+        }
+    }
+
+    fn extensions(&self) -> &'static str {
+        match self {
+            ImportFormat::JsonLd => ".json,.jsonld,.html,.htm",
+            ImportFormat::Cooklang => ".cook",
+        }
+    }
+}
+
+// What to do with an ingredient of the imported recipe
+#[derive(PartialEq, Clone, Debug)]
+enum IngredientChoice {
+    Skip,
+    Create(String),
+    Existing(IngredientIndex),
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct RecipeImportButtonProps {
+    pub format: ImportFormat,
+}
+
+enum RecipeImportAction {
+    Open,
+    Close,
+    SetSource(String, Option<String>),
+    Parsed(
+        PortableRecipe,
+        Vec<IngredientChoice>,
+        Vec<Option<RecipeIndex>>,
+    ),
+    ChooseIngredient(usize, IngredientChoice),
+    ChooseRecipe(usize, Option<RecipeIndex>),
+    SetBusy(bool),
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct RecipeImportState {
+    shown: bool,
+    source: String,
+    file_name: Option<String>,
+    recipe: Option<PortableRecipe>,
+    ingredient_choices: Vec<IngredientChoice>,
+    recipe_choices: Vec<Option<RecipeIndex>>,
+    busy: bool,
+}
+
+impl Reducible for RecipeImportState {
+    type Action = RecipeImportAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut new_state: Self = (*self).clone();
+
+        match action {
+            RecipeImportAction::Open => new_state.shown = true,
+            RecipeImportAction::Close => new_state = RecipeImportState::default(),
+            RecipeImportAction::SetSource(source, file_name) => {
+                new_state.source = source;
+                new_state.file_name = file_name;
+            }
+            RecipeImportAction::Parsed(recipe, ingredients, recipes) => {
+                new_state.recipe = Some(recipe);
+                new_state.ingredient_choices = ingredients;
+                new_state.recipe_choices = recipes;
+            }
+            RecipeImportAction::ChooseIngredient(index, choice) => {
+                if let Some(slot) = new_state.ingredient_choices.get_mut(index) {
+                    *slot = choice;
+                }
+            }
+            RecipeImportAction::ChooseRecipe(index, choice) => {
+                if let Some(slot) = new_state.recipe_choices.get_mut(index) {
+                    *slot = choice;
+                }
+            }
+            RecipeImportAction::SetBusy(busy) => new_state.busy = busy,
+        }
+
+        new_state.into()
+    }
+}
+
+// Requirements of the imported recipe grouped by the ingredient they use, as
+// a recipe requires each ingredient once. Quantities of a group are added up.
+fn merge_requirements(
+    requirements: &[PortableRequirement],
+    choices: &[IngredientChoice],
+) -> Vec<(IngredientChoice, String, bool)> {
+    let mut merged: Vec<(IngredientChoice, String, bool)> = vec![];
+
+    for (requirement, choice) in requirements.iter().zip(choices.iter()) {
+        let same = |other: &IngredientChoice| match (choice, other) {
+            (IngredientChoice::Existing(lhs), IngredientChoice::Existing(rhs)) => lhs.id == rhs.id,
+            (IngredientChoice::Create(lhs), IngredientChoice::Create(rhs)) => {
+                normalize(lhs) == normalize(rhs)
+            }
+            _ => false,
+        };
+
+        if *choice == IngredientChoice::Skip {
+            continue;
+        }
+
+        match merged.iter_mut().find(|(other, _, _)| same(other)) {
+            Some((_, quantity, optional)) => {
+                if !requirement.quantity.is_empty() {
+                    if !quantity.is_empty() {
+                        quantity.push_str(" + ");
+                    }
+                    quantity.push_str(&requirement.quantity);
+                }
+                *optional = *optional && requirement.optional;
+            }
+            None => merged.push((
+                choice.clone(),
+                requirement.quantity.clone(),
+                requirement.optional,
+            )),
+        }
+    }
+
+    merged
+}
+
+// Create the imported recipe on the server, returning its id and the parts
+// that could not be added to it. What was created is reported to
+// `update_cache`.
+async fn create_recipe(
+    backend: &dyn Backend,
+    update_cache: &Callback<CacheChange>,
+    recipe: &PortableRecipe,
+    ingredient_choices: &[IngredientChoice],
+    recipe_choices: &[Option<RecipeIndex>],
+    language: Language,
+) -> Result<(String, Vec<String>), String> {
+    let created = backend
+        .recipe_create(&recipe.name, "", "", "")
+        .await
+        .map_err(|e| e.describe(language))?;
+
+    backend
+        .recipe_update(
+            &created.id,
+            Some(&recipe.name),
+            Some(&recipe.author),
+            Some(&recipe.directions),
+            None,
+        )
+        .await
+        .map_err(|e| e.describe(language))?;
+
+    let mut failures: Vec<String> = vec![];
+
+    for (choice, quantity, optional) in merge_requirements(&recipe.requirements, ingredient_choices)
+    {
+        let (name, ingredient_id) = match choice {
+            IngredientChoice::Skip => continue,
+            IngredientChoice::Existing(ingredient) => (ingredient.name, ingredient.id),
+            IngredientChoice::Create(name) => {
+                match backend
+                    .ingredient_create(&name, false, false, false, false)
+                    .await
+                {
+                    Ok(ingredient) => {
+                        update_cache.emit(CacheChange::IngredientSaved(ingredient.clone()));
+                        (name, ingredient.id)
+                    }
+                    Err(message) => {
+                        failures.push(format!("{}: {}", name, message.describe(language)));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if let Err(message) = backend
+            .requirement_create(&created.id, &ingredient_id, &quantity, optional)
+            .await
+        {
+            failures.push(format!("{}: {}", name, message.describe(language)));
+        }
+    }
+
+    let dependencies: Vec<_> = recipe
+        .dependencies
+        .iter()
+        .zip(recipe_choices.iter())
+        .filter_map(|(dependency, choice)| choice.as_ref().map(|index| (dependency, index)))
+        .collect();
+
+    let requests = dependencies.iter().map(|(dependency, index)| {
+        backend.dependency_create(
+            &created.id,
+            &index.id,
+            &dependency.quantity,
+            dependency.optional,
+        )
+    });
+
+    for ((_, index), response) in dependencies.iter().zip(join_all(requests).await) {
+        if let Err(message) = response {
+            failures.push(format!("{}: {}", index.name, message.describe(language)));
+        }
+    }
+
+    let requests = recipe
+        .tags
+        .iter()
+        .map(|tag| backend.recipe_tag(&created.id, tag));
+
+    for (tag, response) in recipe.tags.iter().zip(join_all(requests).await) {
+        if let Err(message) = response {
+            failures.push(format!("{}: {}", tag, message.describe(language)));
+        }
+    }
+
+    update_cache.emit(match backend.recipe_get(&created.id).await {
+        Ok(saved) => CacheChange::RecipeSaved(saved),
+        Err(_) => CacheChange::RecipeIndexed(RecipeIndex {
+            id: created.id.clone(),
+            name: recipe.name.clone(),
+        }),
+    });
+
+    Ok((created.id, failures))
+}
+
+#[function_component(RecipeImportButton)]
+pub fn recipe_import_button(props: &RecipeImportButtonProps) -> Html {
+    let state = use_reducer(RecipeImportState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let navigator = use_navigator().unwrap();
+
+    let state_cloned = state.clone();
+    let on_open = Callback::from(move |_| state_cloned.dispatch(RecipeImportAction::Open));
+
+    let state_cloned = state.clone();
+    let on_close = Callback::from(move |_| state_cloned.dispatch(RecipeImportAction::Close));
+
+    let state_cloned = state.clone();
+    let on_source_edit = Callback::from(move |e: Event| {
+        let source = e
+            .target()
+            .expect("")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        state_cloned.dispatch(RecipeImportAction::SetSource(source, None));
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_file_select = Callback::from(move |e: Event| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let file = e
+            .target()
+            .expect("")
+            .unchecked_into::<HtmlInputElement>()
+            .files()
+            .and_then(|files| files.get(0));
+
+        if let Some(file) = file {
+            wasm_bindgen_futures::spawn_local(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(contents) => state_cloned.dispatch(RecipeImportAction::SetSource(
+                        contents.as_string().unwrap_or_default(),
+                        Some(file.name()),
+                    )),
+                    Err(error) => context_cloned.status.emit(Message::Error(
+                        error.as_string().unwrap_or(String::default()),
+                        chrono::Utc::now(),
+                    )),
+                }
+            });
+        }
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let format = props.format;
+    let on_parse = Callback::from(move |_| match format.parse(&state_cloned.source) {
+        Ok(mut recipe) => {
+            if recipe.name.is_empty() {
+                recipe.name = match &state_cloned.file_name {
+                    Some(name) => name
+                        .rsplit_once('.')
+                        .map(|(stem, _)| stem)
+                        .unwrap_or(name)
+                        .to_string(),
+                    None => context_cloned
+                        .settings
+                        .language
+                        .t("import.default_name")
+                        .to_string(),
+                };
+            }
+
+            let ingredients = recipe
+                .requirements
+                .iter()
+                .map(|requirement| {
+                    match match_ingredient(
+                        &requirement.ingredient,
+                        context_cloned.ingredient_cache.iter(),
+                    ) {
+                        Some(ingredient) => IngredientChoice::Existing(ingredient.clone()),
+                        None => IngredientChoice::Create(requirement.ingredient.clone()),
+                    }
+                })
+                .collect();
+
+            let recipes = recipe
+                .dependencies
+                .iter()
+                .map(|dependency| {
+                    best_match(
+                        &dependency.recipe,
+                        context_cloned.recipe_cache.iter(),
+                        |index| index.name.as_str(),
+                    )
+                    .cloned()
+                })
+                .collect();
+
+            state_cloned.dispatch(RecipeImportAction::Parsed(recipe, ingredients, recipes));
+        }
+        Err(message) => context_cloned.status.emit(Message::Error(
+            message.translate(context_cloned.settings.language),
+            chrono::Utc::now(),
+        )),
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_import = Callback::from(move |_| {
+        let state_cloned = state_cloned.clone();
+        let context_cloned = context_cloned.clone();
+        let nc = navigator.clone();
+
+        let recipe = match &state_cloned.recipe {
+            Some(recipe) => recipe.clone(),
+            None => return,
+        };
+
+        state_cloned.dispatch(RecipeImportAction::SetBusy(true));
+        wasm_bindgen_futures::spawn_local(async move {
+            match create_recipe(
+                &*context_cloned.backend,
+                &context_cloned.update_cache,
+                &recipe,
+                &state_cloned.ingredient_choices,
+                &state_cloned.recipe_choices,
+                context_cloned.settings.language,
+            )
+            .await
+            {
+                Ok((id, failures)) => {
+                    if !failures.is_empty() {
+                        context_cloned.status.emit(Message::Error(
+                            context_cloned.settings.language.plural(
+                                "import.partial",
+                                failures.len(),
+                                &[&failures.join(", ")],
+                            ),
+                            chrono::Utc::now(),
+                        ))
+                    }
+                    state_cloned.dispatch(RecipeImportAction::Close);
+                    nc.push(&Route::ShowRecipe { id });
+                }
+                Err(message) => {
+                    state_cloned.dispatch(RecipeImportAction::SetBusy(false));
+                    context_cloned
+                        .status
+                        .emit(Message::Error(message, chrono::Utc::now()))
+                }
+            }
+        });
+    });
+
+    let mut ingredients: Vec<IngredientIndex> = context.ingredient_cache.iter().cloned().collect();
+    ingredients.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)));
+
+    let mut recipes: Vec<RecipeIndex> = context.recipe_cache.iter().cloned().collect();
+    recipes.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)));
+
+    let language = context.settings.language;
+    let review = match &state.recipe {
+        None => html! {
+            <>
+                <input
+                    type="file"
+                    accept={props.format.extensions()}
+                    onchange={on_file_select}
+                />
+                <textarea
+                    class="import-source"
+                    placeholder={props.format.placeholder(language)}
+                    value={state.source.clone()}
+                    onchange={on_source_edit}
+                />
+                <button onclick={on_parse}>{language.t("action.next")}</button>
+            </>
+        },
+        Some(recipe) => {
+            let requirement_rows = recipe
+                .requirements
+                .iter()
+                .zip(state.ingredient_choices.iter())
+                .enumerate()
+                .map(|(index, (requirement, choice))| {
+                    let state_cloned = state.clone();
+                    let ingredients_cloned = ingredients.clone();
+                    let name = requirement.ingredient.clone();
+                    let on_choice = Callback::from(move |e: Event| {
+                        let value = e
+                            .target()
+                            .expect("")
+                            .unchecked_into::<HtmlInputElement>()
+                            .value();
+
+                        let choice = match value.as_str() {
+                            "skip" => IngredientChoice::Skip,
+                            "new" => IngredientChoice::Create(name.clone()),
+                            id => match ingredients_cloned.iter().find(|i| i.id == id) {
+                                Some(ingredient) => IngredientChoice::Existing(ingredient.clone()),
+                                None => IngredientChoice::Skip,
+                            },
+                        };
+                        state_cloned.dispatch(RecipeImportAction::ChooseIngredient(index, choice));
+                    });
+
+                    let options = ingredients
+                        .iter()
+                        .map(|ingredient| {
+                            let selected = choice == &IngredientChoice::Existing(ingredient.clone());
+                            html! {
+                                <option {selected} value={ingredient.id.clone()}>
+                                    {ingredient.name.clone()}
+                                </option>
+                            }
+                        })
+                        .collect::<Html>();
+
+                    html! {
+                        <tr key={format!("requirement-{}", index)}>
+                            <td>{requirement.quantity.clone()}</td>
+                            <td>{requirement.ingredient.clone()}</td>
+                            <td>
+                                <select
+                                    autocomplete="off"
+                                    class={classes!(
+                                        matches!(choice, IngredientChoice::Existing(_)).then_some("matched")
+                                    )}
+                                    onchange={on_choice}>
+                                    <option
+                                        selected={choice == &IngredientChoice::Skip}
+                                        value="skip">
+                                        {language.t("import.skip")}
+                                    </option>
+                                    <option
+                                        selected={matches!(choice, IngredientChoice::Create(_))}
+                                        value="new">
+                                        {language.tf("import.new_ingredient", &[&requirement.ingredient])}
+                                    </option>
+                                    {options}
+                                </select>
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>();
+
+            let dependency_rows = recipe
+                .dependencies
+                .iter()
+                .zip(state.recipe_choices.iter())
+                .enumerate()
+                .map(|(index, (dependency, choice))| {
+                    let state_cloned = state.clone();
+                    let recipes_cloned = recipes.clone();
+                    let on_choice = Callback::from(move |e: Event| {
+                        let value = e
+                            .target()
+                            .expect("")
+                            .unchecked_into::<HtmlInputElement>()
+                            .value();
+
+                        let choice = recipes_cloned.iter().find(|r| r.id == value).cloned();
+                        state_cloned.dispatch(RecipeImportAction::ChooseRecipe(index, choice));
+                    });
+
+                    let options = recipes
+                        .iter()
+                        .map(|index| {
+                            let selected = choice.as_ref() == Some(index);
+                            html! {
+                                <option {selected} value={index.id.clone()}>
+                                    {index.name.clone()}
+                                </option>
+                            }
+                        })
+                        .collect::<Html>();
+
+                    html! {
+                        <tr key={format!("dependency-{}", index)}>
+                            <td>{dependency.quantity.clone()}</td>
+                            <td>{dependency.recipe.clone()}</td>
+                            <td>
+                                <select
+                                    autocomplete="off"
+                                    class={classes!(choice.is_some().then_some("matched"))}
+                                    onchange={on_choice}>
+                                    <option selected={choice.is_none()} value="skip">
+                                        {language.t("import.skip")}
+                                    </option>
+                                    {options}
+                                </select>
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>();
+
+            html! {
+                <>
+                    <h2>{recipe.name.as_str()}</h2>
+                    <div class="recipe-author">{recipe.author.as_str()}</div>
+                    <table class="import-requirements">
+                        {dependency_rows}
+                        {requirement_rows}
+                    </table>
+                    <button disabled={state.busy} onclick={on_import}>{language.t("import.import")}</button>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <>
+            <button class="create-item import-recipe" onclick={on_open}>
+                {props.format.label(language)}
+            </button>
+            if state.shown {
+                <div class="import-dialog">
+                    <div class="import-dialog-content">
+                        {review}
+                        <button onclick={on_close}>{language.t("action.cancel")}</button>
+                    </div>
+                </div>
+            }
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(ingredient: &str, quantity: &str, optional: bool) -> PortableRequirement {
+        PortableRequirement {
+            ingredient: String::from(ingredient),
+            quantity: String::from(quantity),
+            optional,
+        }
+    }
+
+    #[test]
+    fn merge_requirements_of_the_same_ingredient() {
+        let butter = IngredientIndex {
+            id: String::from("1"),
+            name: String::from("Beurre"),
+        };
+        let requirements = [
+            requirement("beurre", "50 g", false),
+            requirement("sucre", "20 g", true),
+            requirement("beurre doux", "10 g", true),
+            requirement("Sucres", "", true),
+            requirement("sel", "1 pincée", false),
+        ];
+        let choices = [
+            IngredientChoice::Existing(butter.clone()),
+            IngredientChoice::Create(String::from("sucre")),
+            IngredientChoice::Existing(butter.clone()),
+            IngredientChoice::Create(String::from("Sucres")),
+            IngredientChoice::Skip,
+        ];
+
+        assert_eq!(
+            merge_requirements(&requirements, &choices),
+            vec![
+                (
+                    IngredientChoice::Existing(butter),
+                    String::from("50 g + 10 g"),
+                    false
+                ),
+                (
+                    IngredientChoice::Create(String::from("sucre")),
+                    String::from("20 g"),
+                    true
+                ),
+            ]
+        );
+    }
+}
@@ -1,11 +1,16 @@
-mod create_button;
+pub mod create_button;
 mod element;
-mod filters;
+pub mod filters;
+mod import_dialog;
 mod search_pane;
 
+use crate::app::backend::Backend;
+use crate::app::i18n::Language;
+use crate::app::profile::recipe_warnings;
 use crate::app::recipes::list::create_button::RecipeCreateButton;
 use crate::app::recipes::list::element::RecipeElement;
 use crate::app::recipes::list::filters::Filters;
+use crate::app::recipes::list::import_dialog::{ImportFormat, RecipeImportButton};
 use crate::app::recipes::list::search_pane::SearchPane;
 use crate::app::set_title;
 use crate::app::status_bar::Message;
@@ -16,16 +21,17 @@ use std::rc::Rc;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-fn simplify_name(original: &str) -> String {
+pub fn simplify_name(original: &str) -> String {
     unidecode::unidecode(original).to_lowercase()
 }
 
 async fn fetch_recipes_label_union(
-    server: &str,
+    backend: &dyn Backend,
     labels: HashSet<ladle::models::LabelIndex>,
     status: Callback<Message>,
+    language: Language,
 ) -> Vec<ladle::models::RecipeIndex> {
-    let fetches = labels.iter().map(|l| ladle::label_get(server, &l.id));
+    let fetches = labels.iter().map(|l| backend.label_get(&l.id));
 
     let recipes: HashSet<ladle::models::RecipeIndex> = join_all(fetches)
         .await
@@ -33,7 +39,10 @@ async fn fetch_recipes_label_union(
         .filter_map(|r| match r {
             Ok(label) => Some(label.tagged_recipes.iter()),
             Err(message) => {
-                status.emit(Message::Error(message.to_string(), chrono::Utc::now()));
+                status.emit(Message::Error(
+                    message.describe(language),
+                    chrono::Utc::now(),
+                ));
                 None
             }
         })
@@ -46,11 +55,12 @@ async fn fetch_recipes_label_union(
 
 /*
 async fn fetch_recipes_label_intersection(
-    server: &str,
+    backend: &dyn Backend,
     labels: HashSet<ladle::models::LabelIndex>,
     status: Callback<Message>,
+    language: Language,
 ) -> Vec<ladle::models::RecipeIndex> {
-    let fetches = labels.iter().map(|l| ladle::label_get(server, &l.id));
+    let fetches = labels.iter().map(|l| backend.label_get(&l.id));
 
     let recipes: Option<HashSet<ladle::models::RecipeIndex>> = join_all(fetches)
         .await
@@ -58,7 +68,7 @@ async fn fetch_recipes_label_intersection(
         .filter_map(|r| match r {
             Ok(label) => Some(label.tagged_recipes.iter().cloned().collect()),
             Err(message) => {
-                status.emit(Message::Error(message.to_string(), chrono::Utc::now()));
+                status.emit(Message::Error(message.describe(language), chrono::Utc::now()));
                 None
             }
         })
@@ -74,17 +84,21 @@ async fn fetch_recipes_label_intersection(
 */
 
 async fn fetch_recipes_index(
-    server: &str,
+    backend: &dyn Backend,
     _: HashSet<ladle::models::LabelIndex>,
     status: Callback<Message>,
+    language: Language,
 ) -> Vec<ladle::models::RecipeIndex> {
-    match ladle::recipe_index(server, "").await {
+    match backend.recipe_index("").await {
         Ok(mut index) => {
             index.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
             index
         }
         Err(message) => {
-            status.emit(Message::Error(message.to_string(), chrono::Utc::now()));
+            status.emit(Message::Error(
+                message.describe(language),
+                chrono::Utc::now(),
+            ));
             vec![]
         }
     }
@@ -132,7 +146,7 @@ impl Reducible for RecipeListState {
 }
 
 #[function_component(RecipeList)]
-pub fn recipe_list(_: &RecipeListProps) -> Html {
+pub fn recipe_list(_props: &RecipeListProps) -> Html {
     let state = use_reducer(RecipeListState::default);
     let context = use_context::<AppContext>().unwrap_or(AppContext::default());
 
@@ -150,7 +164,10 @@ pub fn recipe_list(_: &RecipeListProps) -> Html {
         cloned_state.dispatch(RecipeListAction::UpdatePattern(pattern))
     });
 
-    set_title("Recipes - spoon");
+    set_title(&format!(
+        "{} - spoon",
+        context.settings.language.t("header.recipes")
+    ));
 
     let cloned_state = state.clone();
     let context_cloned = context.clone();
@@ -163,17 +180,19 @@ pub fn recipe_list(_: &RecipeListProps) -> Html {
             let mut fetched_recipes = match labels.len() {
                 0 => {
                     fetch_recipes_index(
-                        context_cloned.settings.server_url.as_str(),
+                        &*context_cloned.backend,
                         labels.clone(),
                         context_cloned.status,
+                        context_cloned.settings.language,
                     )
                     .await
                 }
                 _ => {
                     fetch_recipes_label_union(
-                        context_cloned.settings.server_url.as_str(),
+                        &*context_cloned.backend,
                         labels.clone(),
                         context_cloned.status,
+                        context_cloned.settings.language,
                     )
                     .await
                 }
@@ -192,8 +211,7 @@ pub fn recipe_list(_: &RecipeListProps) -> Html {
         let cloned_state = cloned_state.clone();
         let context_cloned = context_cloned.clone();
         wasm_bindgen_futures::spawn_local(async move {
-            let fetched_labels =
-                ladle::label_index(context_cloned.settings.server_url.as_str(), "").await;
+            let fetched_labels = context_cloned.backend.label_index("").await;
 
             let labels = match fetched_labels {
                 Ok(mut index) => {
@@ -201,9 +219,10 @@ pub fn recipe_list(_: &RecipeListProps) -> Html {
                     HashSet::from_iter(index.iter().cloned())
                 }
                 Err(message) => {
-                    context_cloned
-                        .status
-                        .emit(Message::Error(message.to_string(), chrono::Utc::now()));
+                    context_cloned.status.emit(Message::Error(
+                        message.describe(context_cloned.settings.language),
+                        chrono::Utc::now(),
+                    ));
                     HashSet::new()
                 }
             };
@@ -212,17 +231,29 @@ pub fn recipe_list(_: &RecipeListProps) -> Html {
         });
     });
 
-    use_effect_with_deps(move |_| refresh_recipes.emit(()), selected_labels);
-
+    // Reload when switching between the server and the browser storage too
+    let data_source = context.settings.data_source().to_string();
     use_effect_with_deps(
-        move |_| refresh_labels.emit(()),
-        context.settings.server_url.clone(),
+        move |_| refresh_recipes.emit(()),
+        (selected_labels, data_source.clone()),
     );
 
+    use_effect_with_deps(move |_| refresh_labels.emit(()), data_source);
+
     let items = state
         .recipes
         .iter()
         .filter(|recipe| simplify_name(&recipe.name).contains(&state.pattern))
+        .filter(|recipe| {
+            !context.settings.profile.hide_incompatible
+                || recipe_warnings(
+                    context.settings.language,
+                    &context.settings.profile,
+                    &context.recipe_details,
+                    &recipe.id,
+                )
+                .is_compatible()
+        })
         .map(|recipe| {
             html! {
                 <RecipeElement
@@ -244,6 +275,12 @@ pub fn recipe_list(_: &RecipeListProps) -> Html {
             </ul>
             <div class={"options"}>
                 <RecipeCreateButton />
+                <RecipeImportButton
+                    format={ImportFormat::JsonLd}
+                />
+                <RecipeImportButton
+                    format={ImportFormat::Cooklang}
+                />
             </div>
         </div>
     }
@@ -1,5 +1,5 @@
 use crate::app::recipes::list::Filters;
-use crate::app::Route;
+use crate::app::{AppContext, Route};
 use std::collections::HashSet;
 use std::ops::Deref;
 use wasm_bindgen::JsCast;
@@ -25,6 +25,8 @@ pub fn search_pane(props: &SearchPaneProps) -> Html {
     let navigator = use_navigator().unwrap();
     let location = use_location().unwrap();
     let parameters = location.query::<Filters>().unwrap_or(Filters::default());
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let language = context.settings.language;
 
     let cloned_state = state.clone();
     let toggle_tray = Callback::from(move |_| {
@@ -109,25 +111,25 @@ pub fn search_pane(props: &SearchPaneProps) -> Html {
                 <input
                     type="search"
                     class="search-bar"
-                    placeholder="Search recipes ..."
+                    placeholder={language.t("search.placeholder")}
                     oninput={on_pattern_change} />
-                <button class="label-tray-toggle" onclick={toggle_tray}>{"labels"}</button>
+                <button class="label-tray-toggle" onclick={toggle_tray}>{language.t("search.labels")}</button>
                 <div class="restrictions">
                     <span>
                         <input type="checkbox" name="vegetarian" value="0" />
-                        <label for="vegetarian">{"Vegetarian"}</label>
+                        <label for="vegetarian">{language.t("classification.vegetarian")}</label>
                     </span>
                     <span>
                         <input type="checkbox" name="vegan" value="0" />
-                        <label for="vegan">{"Vegan"}</label>
+                        <label for="vegan">{language.t("classification.vegan")}</label>
                     </span>
                     <span>
                         <input type="checkbox" name="dairy-free" value="0" />
-                        <label for="dairy-free">{"Dairy-Free"}</label>
+                        <label for="dairy-free">{language.t("classification.dairy_free")}</label>
                     </span>
                     <span>
                         <input type="checkbox" name="gluten-free" value="0" />
-                        <label for="gluten-free">{"Gluten-Free"}</label>
+                        <label for="gluten-free">{language.t("classification.gluten_free")}</label>
                     </span>
                 </div>
             </div>
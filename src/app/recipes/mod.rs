@@ -1,3 +1,6 @@
+pub mod classifications;
+pub mod dependents;
 pub mod edit;
+pub mod formats;
 pub mod list;
 pub mod window;
@@ -1,45 +1,25 @@
+use crate::app::download::{download, file_name};
+use crate::app::i18n::Language;
+use crate::app::ingredients::substitutions::{substitutions_for, Substitution};
+use crate::app::profile::{describe_warnings, DietProfile};
+use crate::app::recipes::classifications::{get_classifications, Classifications};
+use crate::app::recipes::dependents::used_by;
+use crate::app::recipes::formats::{cooklang, jsonld, markdown, PortableRecipe};
+use crate::app::tasks::use_task_scope;
 use crate::app::{set_title, status_bar::Message, AppContext, Route};
 use futures::future::join_all;
 use pulldown_cmark::{html::push_html, Options, Parser};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-#[derive(Debug)]
-enum Classifications {
-    Vegan,
-    Vegetarian,
-    DairyFree,
-    GlutenFree,
-}
-
-fn get_classifications(data: &ladle::models::Classifications) -> Vec<Classifications> {
-    let mut out = vec![];
-
-    if !data.dairy {
-        out.push(Classifications::DairyFree);
-    }
-
-    if !data.gluten {
-        out.push(Classifications::GlutenFree);
-    }
-
-    if !data.meat {
-        out.push(Classifications::Vegetarian);
-    }
-
-    if !data.meat && !data.animal_product && !data.dairy {
-        out.push(Classifications::Vegan);
-    }
-
-    out
-}
-
-fn render_classifications(list: &Vec<Classifications>) -> Html {
+fn render_classifications(list: &Vec<Classifications>, language: Language) -> Html {
     let items = list
         .iter()
-        .map(|class| html! {<li>{format!("{:?}", class)}</li>})
+        .map(|class| html! {<li>{class.name(language)}</li>})
         .collect::<Html>();
 
     html! {
@@ -61,24 +41,153 @@ fn parse_text(value: &str) -> String {
 enum RecipeElement<'a> {
     MainRecipe(&'a ladle::models::Recipe),
     DependencyRecipe(&'a ladle::models::Dependency, &'a ladle::models::Recipe),
+    ExcludedRecipe(&'a ladle::models::Dependency, &'a ladle::models::Recipe),
+}
+
+// Optional items that can be left out of the recipe being viewed
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum OptionalItem {
+    Requirement(String, String),
+    Dependency(String),
 }
 
-fn render_requirements(element: &RecipeElement) -> Html {
+fn render_optional_toggle(
+    item: OptionalItem,
+    included: bool,
+    toggle: &Callback<OptionalItem>,
+    language: Language,
+) -> Html {
+    let toggle = toggle.clone();
+    html! {
+        <label class="optional-toggle">
+            <input
+                type="checkbox"
+                checked={included}
+                onclick={Callback::from(move |_| toggle.emit(item.clone()))}
+            />
+            {language.t("recipe.optional")}
+        </label>
+    }
+}
+
+// Key of a requirement in the recipe being viewed: (recipe id, ingredient id)
+type RequirementKey = (String, String);
+
+fn render_substitution_select(
+    key: RequirementKey,
+    candidates: Vec<&Substitution>,
+    applied: Option<&Substitution>,
+    substitute: &Callback<(RequirementKey, Option<Substitution>)>,
+    language: Language,
+) -> Html {
+    if candidates.is_empty() {
+        return html! {};
+    }
+
+    let options = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| {
+            html! {
+                <option
+                    selected={applied == Some(*candidate)}
+                    value={index.to_string()}>
+                    {candidate.describe()}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    let substitute = substitute.clone();
+    let candidates: Vec<Substitution> = candidates.into_iter().cloned().collect();
+    let on_select = Callback::from(move |e: Event| {
+        let value = e
+            .target()
+            .expect("")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        let choice = value
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| candidates.get(index))
+            .cloned();
+
+        substitute.emit((key.clone(), choice));
+    });
+
+    html! {
+        <select class="requirement-substitution" autocomplete="off" onchange={on_select}>
+            <option value="" selected={applied.is_none()}>{language.t("recipe.no_substitution")}</option>
+            {options}
+        </select>
+    }
+}
+
+fn render_requirements(
+    element: &RecipeElement,
+    data: &RecipeWindowState,
+    catalogue: &[Substitution],
+    toggle: &Callback<OptionalItem>,
+    substitute: &Callback<(RequirementKey, Option<Substitution>)>,
+    language: Language,
+) -> Html {
     let recipe = match element {
         RecipeElement::MainRecipe(recipe) => recipe,
         RecipeElement::DependencyRecipe(_, recipe) => recipe,
+        RecipeElement::ExcludedRecipe(_, recipe) => recipe,
     };
 
     let requirements = recipe
         .requirements
         .iter()
         .map(|requirement| {
+            let item =
+                OptionalItem::Requirement(recipe.id.clone(), requirement.ingredient.id.clone());
+            let included = !data.excluded.contains(&item);
+            let key = (recipe.id.clone(), requirement.ingredient.id.clone());
+            let applied = data.substitutions.get(&key);
+
+            let (ingredient, quantity) = match applied {
+                Some(substitution) => (
+                    html! {
+                        <span title={substitution.note.clone()}>
+                            <s>{requirement.ingredient.name.clone()}</s>
+                            {format!(" {}", substitution.replacement.name)}
+                        </span>
+                    },
+                    match substitution.ratio == 1.0 {
+                        true => requirement.quantity.clone(),
+                        false => format!("{} (x{})", requirement.quantity, substitution.ratio),
+                    },
+                ),
+                None => (
+                    html! {<>{requirement.ingredient.name.clone()}</>},
+                    requirement.quantity.clone(),
+                ),
+            };
+
             html! {
-                <tr class="requirement" key={requirement.ingredient.id.clone()}>
-                    <td class="requirement-ingredient">{requirement.ingredient.name.clone()}</td>
-                    <td class="requirement-quantity">{requirement.quantity.clone()}</td>
+                <tr
+                    class={classes!("requirement", (!included).then_some("excluded"))}
+                    key={requirement.ingredient.id.clone()}>
+                    <td class="requirement-ingredient">{ingredient}</td>
+                    <td class="requirement-quantity">{quantity}</td>
+                    <td class="requirement-substitutions">{
+                        render_substitution_select(
+                            key.clone(),
+                            substitutions_for(catalogue, &requirement.ingredient.id),
+                            applied,
+                            substitute,
+                            language,
+                        )
+                    }</td>
                     <td class="requirement-optional"> {
-                        if requirement.optional {"Optional"} else {""}
+                        if requirement.optional {
+                            render_optional_toggle(item, included, toggle, language)
+                        } else {
+                            html! {}
+                        }
                     } </td>
                 </tr>
             }
@@ -90,17 +199,28 @@ fn render_requirements(element: &RecipeElement) -> Html {
             html! {<h3 class="dependency-subtitle">{recipe.name.clone()}</h3>}
         }
         RecipeElement::DependencyRecipe(dependency, recipe) if dependency.optional => {
-            html! {<h3 class="dependency-subtitle">{format!("{} - Optional", recipe.name.clone())}</h3>}
+            html! {<h3 class="dependency-subtitle">
+                {recipe.name.clone()}
+                {render_optional_toggle(OptionalItem::Dependency(recipe.id.clone()), true, toggle, language)}
+            </h3>}
         }
         RecipeElement::DependencyRecipe(_, recipe) => {
             html! {<h3 class="dependency-subtitle">{recipe.name.clone()}</h3>}
         }
+        RecipeElement::ExcludedRecipe(_, recipe) => {
+            html! {<h3 class="dependency-subtitle excluded">
+                {recipe.name.clone()}
+                {render_optional_toggle(OptionalItem::Dependency(recipe.id.clone()), false, toggle, language)}
+            </h3>}
+        }
     };
 
     html! {
         <li class="dependency-requirement" key={recipe.id.as_str()}>
             {subtitle}
-            <table class="requirement-list">{requirements}</table>
+            if !matches!(element, RecipeElement::ExcludedRecipe(_, _)) {
+                <table class="requirement-list">{requirements}</table>
+            }
         </li>
     }
 }
@@ -109,6 +229,7 @@ fn render_directions(element: &RecipeElement) -> Html {
     let data = match element {
         RecipeElement::MainRecipe(recipe) => recipe,
         RecipeElement::DependencyRecipe(_, recipe) => recipe,
+        RecipeElement::ExcludedRecipe(_, _) => return html! {},
     };
 
     let parse_html = parse_text(&data.directions);
@@ -133,7 +254,11 @@ fn get_recipe_order<'a>(data: &'a RecipeWindowState) -> Vec<RecipeElement> {
     };
 
     while let Some(dependency) = dependency_fifo.pop_front() {
+        let item = OptionalItem::Dependency(dependency.recipe.id.clone());
         match data.dependencies.get(&dependency.recipe.id) {
+            Some(recipe) if dependency.optional && data.excluded.contains(&item) => {
+                elements.push(RecipeElement::ExcludedRecipe(dependency, recipe));
+            }
             Some(recipe) => {
                 elements.push(RecipeElement::DependencyRecipe(dependency, recipe));
                 dependency_fifo.extend(recipe.dependencies.iter())
@@ -145,7 +270,147 @@ fn get_recipe_order<'a>(data: &'a RecipeWindowState) -> Vec<RecipeElement> {
     elements.iter().rev().cloned().collect()
 }
 
-fn render(data: &RecipeWindowState) -> Html {
+// Ingredient actually used for a requirement, once substitutions are applied
+fn effective_ingredient<'a>(
+    data: &'a RecipeWindowState,
+    recipe: &ladle::models::Recipe,
+    requirement: &'a ladle::models::Requirement,
+) -> &'a ladle::models::IngredientIndex {
+    match data
+        .substitutions
+        .get(&(recipe.id.clone(), requirement.ingredient.id.clone()))
+    {
+        Some(substitution) => &substitution.replacement,
+        None => &requirement.ingredient,
+    }
+}
+
+// Recipes of the elements, minus the optional dependencies left out
+fn included_recipes<'a>(elements: &[RecipeElement<'a>]) -> Vec<&'a ladle::models::Recipe> {
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            RecipeElement::MainRecipe(recipe) => Some(*recipe),
+            RecipeElement::DependencyRecipe(_, recipe) => Some(*recipe),
+            RecipeElement::ExcludedRecipe(_, _) => None,
+        })
+        .collect()
+}
+
+// Requirements of a recipe along with the ingredient they use, minus the
+// optional ones left out
+fn recipe_requirements<'a>(
+    data: &'a RecipeWindowState,
+    recipe: &'a ladle::models::Recipe,
+) -> Vec<(
+    &'a ladle::models::Requirement,
+    &'a ladle::models::IngredientIndex,
+)> {
+    recipe
+        .requirements
+        .iter()
+        .filter(|requirement| {
+            !data.excluded.contains(&OptionalItem::Requirement(
+                recipe.id.clone(),
+                requirement.ingredient.id.clone(),
+            ))
+        })
+        .map(|requirement| (requirement, effective_ingredient(data, recipe, requirement)))
+        .collect()
+}
+
+// Requirements of the recipe elements along with the ingredient they use,
+// minus the optional ones left out
+fn included_requirements<'a>(
+    data: &'a RecipeWindowState,
+    elements: &[RecipeElement<'a>],
+) -> Vec<(
+    &'a ladle::models::Requirement,
+    &'a ladle::models::IngredientIndex,
+)> {
+    included_recipes(elements)
+        .into_iter()
+        .flat_map(|recipe| recipe_requirements(data, recipe))
+        .collect()
+}
+
+fn included_ingredients<'a>(
+    data: &'a RecipeWindowState,
+    elements: &[RecipeElement<'a>],
+) -> Vec<&'a ladle::models::IngredientIndex> {
+    included_requirements(data, elements)
+        .into_iter()
+        .map(|(_, ingredient)| ingredient)
+        .collect()
+}
+
+// Whether every dependency left in the recipe has been fetched, as the
+// ingredients of the missing ones are unknown
+fn dependencies_loaded(data: &RecipeWindowState, elements: &[RecipeElement]) -> bool {
+    included_recipes(elements)
+        .iter()
+        .flat_map(|recipe| recipe.dependencies.iter())
+        .all(|dependency| {
+            let item = OptionalItem::Dependency(dependency.recipe.id.clone());
+            (dependency.optional && data.excluded.contains(&item))
+                || data.dependencies.contains_key(&dependency.recipe.id)
+        })
+}
+
+// Classifications of the displayed version of the recipe, or None if some
+// dependencies or ingredients are still being fetched or failed to be
+fn compute_classifications(
+    data: &RecipeWindowState,
+    elements: &[RecipeElement],
+) -> Option<ladle::models::Classifications> {
+    if !dependencies_loaded(data, elements) {
+        return None;
+    }
+
+    let mut out = ladle::models::Classifications::default();
+
+    for ingredient in included_ingredients(data, elements) {
+        let classifications = data.ingredient_classifications.get(&ingredient.id)?;
+        out.dairy |= classifications.dairy;
+        out.meat |= classifications.meat;
+        out.gluten |= classifications.gluten;
+        out.animal_product |= classifications.animal_product;
+    }
+
+    Some(out)
+}
+
+fn render_used_in(used_in: &[ladle::models::RecipeIndex], language: Language) -> Html {
+    let recipes = used_in
+        .iter()
+        .map(|r| {
+            html! {
+                <li key={r.id.clone()}>
+                    <Link<Route> to={Route::ShowRecipe{id:r.id.clone()}}>
+                        {r.name.as_str()}
+                    </Link<Route>>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        if !used_in.is_empty() {
+            <h2 class="recipe-used-in-label">{language.t("recipe.used_in")}</h2>
+            <ul class="recipe-used-in">{recipes}</ul>
+        }
+    }
+}
+
+fn render(
+    data: &RecipeWindowState,
+    used_in: &[ladle::models::RecipeIndex],
+    catalogue: &[Substitution],
+    toggle: &Callback<OptionalItem>,
+    substitute: &Callback<(RequirementKey, Option<Substitution>)>,
+    profile: &DietProfile,
+    language: Language,
+) -> Html {
     if data.main_recipe.is_none() {
         return html! {};
     }
@@ -153,12 +418,21 @@ fn render(data: &RecipeWindowState) -> Html {
     let main_recipe = data.main_recipe.as_ref().unwrap();
     let ordered_items = get_recipe_order(&data);
 
+    let displayed_classifications = compute_classifications(data, &ordered_items)
+        .unwrap_or(main_recipe.classifications.clone());
+
     let classifications =
-        render_classifications(&get_classifications(&main_recipe.classifications));
+        render_classifications(&get_classifications(&displayed_classifications), language);
+
+    let warnings = profile.warnings(
+        language,
+        &displayed_classifications,
+        included_ingredients(data, &ordered_items).into_iter(),
+    );
 
     let requirements = ordered_items
         .iter()
-        .map(render_requirements)
+        .map(|element| render_requirements(element, data, catalogue, toggle, substitute, language))
         .collect::<Html>();
 
     let directions = ordered_items
@@ -180,20 +454,137 @@ fn render(data: &RecipeWindowState) -> Html {
 
     html! {
             <>
+            if !warnings.is_empty() {
+                <div class="diet-warning">{describe_warnings(language, &warnings)}</div>
+            }
             <div class="recipe-header">
                 <h1 class="recipe-name">{main_recipe.name.as_str()}</h1>
                 <div class="recipe-author">{main_recipe.author.as_str()}</div>
             </div>
             <ul class="recipe-tags">{tags}</ul>
             {classifications}
-            <h2 class="recipe-ingredients-label">{"Ingrédients"}</h2>
+            {render_explanations(data, language)}
+            <h2 class="recipe-ingredients-label">{language.t("recipe.ingredients")}</h2>
             <ul class="recipe-ingredients">{requirements}</ul>
-            <h2 class="recipe-directions-label">{"Préparation"}</h2>
+            <h2 class="recipe-directions-label">{language.t("recipe.directions")}</h2>
             <div class="recipe-directions">{directions}</div>
+            {render_used_in(used_in, language)}
             </>
     }
 }
 
+// Ingredient of a recipe breaking a restriction, along with the names of the
+// dependencies through which it is brought in
+struct Offender {
+    ingredient: String,
+    path: Vec<String>,
+}
+
+fn find_offenders(data: &RecipeWindowState, restriction: Classifications) -> Vec<Offender> {
+    let mut offenders = vec![];
+    let mut stack: Vec<(&ladle::models::Recipe, Vec<String>)> = match &data.main_recipe {
+        Some(recipe) => vec![(recipe, vec![])],
+        None => vec![],
+    };
+
+    while let Some((recipe, path)) = stack.pop() {
+        for requirement in recipe.requirements.iter() {
+            let item =
+                OptionalItem::Requirement(recipe.id.clone(), requirement.ingredient.id.clone());
+            if data.excluded.contains(&item) {
+                continue;
+            }
+
+            let ingredient = effective_ingredient(data, recipe, requirement);
+            match data.ingredient_classifications.get(&ingredient.id) {
+                Some(classifications) if restriction.violated_by(classifications) => offenders
+                    .push(Offender {
+                        ingredient: ingredient.name.clone(),
+                        path: path.clone(),
+                    }),
+                _ => (),
+            }
+        }
+
+        for dependency in recipe.dependencies.iter() {
+            let item = OptionalItem::Dependency(dependency.recipe.id.clone());
+            if dependency.optional && data.excluded.contains(&item) {
+                continue;
+            }
+
+            if let Some(dependency_recipe) = data.dependencies.get(&dependency.recipe.id) {
+                // Guard against dependency cycles
+                if path.contains(&dependency_recipe.name) {
+                    continue;
+                }
+
+                let mut dependency_path = path.clone();
+                dependency_path.push(dependency_recipe.name.clone());
+                stack.push((dependency_recipe, dependency_path));
+            }
+        }
+    }
+
+    offenders
+}
+
+fn render_explanations(data: &RecipeWindowState, language: Language) -> Html {
+    let items = Classifications::ALL
+        .iter()
+        .filter_map(|restriction| {
+            let offenders: BTreeSet<String> = find_offenders(data, *restriction)
+                .iter()
+                .map(|offender| match offender.path.len() {
+                    0 => offender.ingredient.clone(),
+                    _ => language.tf(
+                        "recipe.offender_via",
+                        &[&offender.ingredient, &offender.path.join(" > ")],
+                    ),
+                })
+                .collect();
+
+            match offenders.len() {
+                0 => None,
+                _ => Some(html! {
+                    <li key={restriction.label()}>
+                        {language.tf(
+                            "recipe.not_classified",
+                            &[
+                                &restriction.name(language),
+                                &offenders.into_iter().collect::<Vec<_>>().join("; "),
+                            ],
+                        )}
+                    </li>
+                }),
+            }
+        })
+        .collect::<Vec<Html>>();
+
+    if items.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <details class="recipe-explanations">
+            <summary>{language.t("recipe.restrictions")}</summary>
+            <ul>{items.into_iter().collect::<Html>()}</ul>
+        </details>
+    }
+}
+
+fn calc_missing_ingredients(data: &RecipeWindowState) -> Vec<String> {
+    let recipes = data.main_recipe.iter().chain(data.dependencies.values());
+
+    let ids: HashSet<&String> = recipes
+        .flat_map(|recipe| recipe.requirements.iter())
+        .map(|requirement| &requirement.ingredient.id)
+        .chain(data.substitutions.values().map(|s| &s.replacement.id))
+        .filter(|id| !data.ingredient_classifications.contains_key(*id))
+        .collect();
+
+    ids.into_iter().cloned().collect()
+}
+
 fn calc_missing(data: &RecipeWindowState) -> Vec<String> {
     let mut fifo = match &data.main_recipe {
         Some(recipe) => vec![recipe.clone()],
@@ -229,14 +620,22 @@ pub struct RecipeWindowProps {
 }
 
 enum RecipeWindowActions {
+    // Forget everything about the previous recipe
+    Reset,
     UpdateRecipe(ladle::models::Recipe),
     UpdateDependency(ladle::models::Recipe),
+    UpdateIngredient(ladle::models::Ingredient),
+    ToggleOptional(OptionalItem),
+    Substitute(RequirementKey, Option<Substitution>),
 }
 
 #[derive(Clone, Default, PartialEq)]
 struct RecipeWindowState {
     main_recipe: Option<ladle::models::Recipe>,
     dependencies: HashMap<String, ladle::models::Recipe>,
+    ingredient_classifications: HashMap<String, ladle::models::Classifications>,
+    excluded: HashSet<OptionalItem>,
+    substitutions: HashMap<RequirementKey, Substitution>,
 }
 
 impl Reducible for RecipeWindowState {
@@ -245,6 +644,9 @@ impl Reducible for RecipeWindowState {
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
         let mut new_state: Self = (*self).clone();
         match action {
+            RecipeWindowActions::Reset => {
+                new_state = Self::default();
+            }
             RecipeWindowActions::UpdateRecipe(main) => {
                 new_state.main_recipe = Some(main.clone());
             }
@@ -253,6 +655,22 @@ impl Reducible for RecipeWindowState {
                     .dependencies
                     .insert(dependency.id.clone(), dependency);
             }
+            RecipeWindowActions::UpdateIngredient(ingredient) => {
+                new_state
+                    .ingredient_classifications
+                    .insert(ingredient.id.clone(), ingredient.classifications);
+            }
+            RecipeWindowActions::ToggleOptional(item) => {
+                if !new_state.excluded.remove(&item) {
+                    new_state.excluded.insert(item);
+                }
+            }
+            RecipeWindowActions::Substitute(key, Some(substitution)) => {
+                new_state.substitutions.insert(key, substitution);
+            }
+            RecipeWindowActions::Substitute(key, None) => {
+                new_state.substitutions.remove(&key);
+            }
         };
 
         new_state.into()
@@ -264,60 +682,199 @@ pub fn recipe_window(props: &RecipeWindowProps) -> Html {
     let navigator = use_navigator().unwrap();
     let state = use_reducer(RecipeWindowState::default);
     let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+    let tasks = use_task_scope();
+
+    // Dependencies and ingredients being fetched, so that each one is
+    // requested once at a time
+    let requested = use_mut_ref(HashSet::<String>::new);
+    let requested_ingredients = use_mut_ref(HashSet::<String>::new);
 
     let state_cloned = state.clone();
     let props_cloned = props.clone();
     let context_cloned = context.clone();
+    let tasks_cloned = tasks.clone();
+    let requested_cloned = requested.clone();
+    let requested_ingredients_cloned = requested_ingredients.clone();
     use_effect_with_deps(
         move |_| {
+            requested_cloned.borrow_mut().clear();
+            requested_ingredients_cloned.borrow_mut().clear();
+            // Exclusions and substitutions only apply to the recipe they were
+            // chosen on
+            state_cloned.dispatch(RecipeWindowActions::Reset);
+
             let state_cloned = state_cloned.clone();
             let id = props_cloned.recipe_id.clone();
-            wasm_bindgen_futures::spawn_local(async move {
+            let task = tasks_cloned.spawn(async move {
                 if let Some(id) = id {
-                    match ladle::recipe_get(
-                        context_cloned.settings.server_url.as_str(),
-                        id.as_str(),
-                    )
-                    .await
-                    {
+                    match context_cloned.backend.recipe_get(id.as_str()).await {
                         Ok(recipe) => {
                             state_cloned.dispatch(RecipeWindowActions::UpdateRecipe(recipe.clone()))
                         }
-                        Err(message) => context_cloned
-                            .status
-                            .emit(Message::Error(message.to_string(), chrono::Utc::now())),
+                        Err(message) => context_cloned.status.emit(Message::Error(
+                            message.describe(context_cloned.settings.language),
+                            chrono::Utc::now(),
+                        )),
                     }
                 }
-            })
+            });
+
+            // The previous recipe must not replace the one asked for next
+            move || task.abort()
         },
         props.recipe_id.clone(),
     );
 
-    let state_cloned = state.clone();
+    let missing: BTreeSet<String> = calc_missing(&state)
+        .into_iter()
+        .filter(|id| !requested.borrow().contains(id))
+        .collect();
+    let missing_ingredients: BTreeSet<String> = calc_missing_ingredients(&state)
+        .into_iter()
+        .filter(|id| !requested_ingredients.borrow().contains(id))
+        .collect();
+
+    let dispatcher = state.dispatcher();
     let context_cloned = context.clone();
     use_effect_with_deps(
-        move |_| {
-            let state_cloned = state_cloned.clone();
-            wasm_bindgen_futures::spawn_local(async move {
-                let missing = calc_missing(&state_cloned);
-
+        move |(_, missing, missing_ingredients)| {
+            requested.borrow_mut().extend(missing.iter().cloned());
+            requested_ingredients
+                .borrow_mut()
+                .extend(missing_ingredients.iter().cloned());
+
+            let missing = missing.clone();
+            let missing_ingredients = missing_ingredients.clone();
+            tasks.spawn(async move {
                 let fetches = missing
                     .iter()
-                    .map(|id| ladle::recipe_get(context_cloned.settings.server_url.as_str(), id));
+                    .map(|id| context_cloned.backend.recipe_get(id));
+
+                // Failed ids are dropped as well, to be tried again on the next
+                // render
+                for (id, response) in missing.iter().zip(join_all(fetches).await) {
+                    requested.borrow_mut().remove(id);
+                    if let Ok(recipe) = response {
+                        dispatcher.dispatch(RecipeWindowActions::UpdateDependency(recipe));
+                    }
+                }
 
-                join_all(fetches)
-                    .await
+                let fetches = missing_ingredients
                     .iter()
-                    .for_each(|recipe_opt| match recipe_opt {
-                        Ok(recipe) => state_cloned
-                            .dispatch(RecipeWindowActions::UpdateDependency(recipe.clone())),
-                        Err(_) => (),
-                    });
+                    .map(|id| context_cloned.backend.ingredient_get(id));
+
+                for (id, response) in missing_ingredients.iter().zip(join_all(fetches).await) {
+                    requested_ingredients.borrow_mut().remove(id);
+                    if let Ok(ingredient) = response {
+                        dispatcher.dispatch(RecipeWindowActions::UpdateIngredient(ingredient));
+                    }
+                }
             });
         },
-        state.clone(),
+        (props.recipe_id.clone(), missing, missing_ingredients),
+    );
+
+    // Describe the recipe to search engines while it is displayed. The diets
+    // and the ingredients listed both leave out the excluded optional items.
+    let json_ld = state.main_recipe.as_ref().map(|main| {
+        let elements = get_recipe_order(&state);
+        let ingredients: Vec<(&str, &str)> = included_requirements(&state, &elements)
+            .into_iter()
+            .map(|(requirement, ingredient)| {
+                (requirement.quantity.as_str(), ingredient.name.as_str())
+            })
+            .collect();
+        jsonld::to_json_ld(
+            main,
+            &included_recipes(&elements),
+            &ingredients,
+            compute_classifications(&state, &elements).as_ref(),
+        )
+    });
+    use_effect_with_deps(
+        move |json_ld: &Option<serde_json::Value>| {
+            let script = json_ld.as_ref().and_then(|value| jsonld::embed(value).ok());
+
+            move || {
+                if let Some(script) = script {
+                    script.remove();
+                }
+            }
+        },
+        json_ld.clone(),
     );
 
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_json_ld_download = Callback::from(move |_| {
+        if let (Some(recipe), Some(value)) = (&state_cloned.main_recipe, &json_ld) {
+            let contents = serde_json::to_string_pretty(value).unwrap_or_default();
+            if let Err(error) = download(
+                &file_name(&recipe.name, "json"),
+                "application/ld+json",
+                &contents,
+            ) {
+                context_cloned.status.emit(Message::Error(
+                    error.as_string().unwrap_or(String::default()),
+                    chrono::Utc::now(),
+                ))
+            }
+        }
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_cooklang_download = Callback::from(move |_| {
+        if let Some(recipe) = &state_cloned.main_recipe {
+            let contents = cooklang::serialize(&PortableRecipe::from(recipe));
+            if let Err(error) = download(&file_name(&recipe.name, "cook"), "text/plain", &contents)
+            {
+                context_cloned.status.emit(Message::Error(
+                    error.as_string().unwrap_or(String::default()),
+                    chrono::Utc::now(),
+                ))
+            }
+        }
+    });
+
+    let state_cloned = state.clone();
+    let context_cloned = context.clone();
+    let on_markdown_download = Callback::from(move |_| {
+        if let Some(recipe) = &state_cloned.main_recipe {
+            // Same version of the recipe as the view and the JSON-LD
+            let elements = get_recipe_order(&state_cloned);
+            let sections: Vec<(&ladle::models::Recipe, Vec<markdown::Line>)> =
+                included_recipes(&elements)
+                    .into_iter()
+                    .map(|section| {
+                        let lines = recipe_requirements(&state_cloned, section)
+                            .into_iter()
+                            .map(|(requirement, ingredient)| markdown::Line {
+                                quantity: &requirement.quantity,
+                                name: &ingredient.name,
+                                optional: requirement.optional,
+                            })
+                            .collect();
+                        (section, lines)
+                    })
+                    .collect();
+            let contents = markdown::to_markdown(
+                recipe,
+                &sections,
+                compute_classifications(&state_cloned, &elements).as_ref(),
+                context_cloned.settings.language,
+            );
+            if let Err(error) = download(&file_name(&recipe.name, "md"), "text/markdown", &contents)
+            {
+                context_cloned.status.emit(Message::Error(
+                    error.as_string().unwrap_or(String::default()),
+                    chrono::Utc::now(),
+                ))
+            }
+        }
+    });
+
+    let language = context.settings.language;
     let class;
     let recipe_html;
     let options;
@@ -326,30 +883,67 @@ pub fn recipe_window(props: &RecipeWindowProps) -> Html {
     if props.recipe_id.is_none() {
         class = "recipe-display empty";
         recipe_html = html! {
-                <span>{"No data"}</span>
+                <span>{language.t("recipe.no_data")}</span>
         };
         options = html! {};
     } else {
         let name = match &state.main_recipe {
             Some(data) => data.name.as_str(),
-            None => "Loading",
+            None => language.t("recipe.loading"),
         };
         set_title(&format!("{} - spoon", name));
 
         class = "recipe-display filled";
-        recipe_html = render(&state);
+        let used_in = used_by(
+            &context.recipe_details,
+            props.recipe_id.as_deref().unwrap_or_default(),
+        );
+        let state_cloned = state.clone();
+        let toggle = Callback::from(move |item: OptionalItem| {
+            state_cloned.dispatch(RecipeWindowActions::ToggleOptional(item))
+        });
+        let state_cloned = state.clone();
+        let substitute = Callback::from(
+            move |(key, substitution): (RequirementKey, Option<Substitution>)| {
+                state_cloned.dispatch(RecipeWindowActions::Substitute(key, substitution))
+            },
+        );
+        recipe_html = render(
+            &state,
+            &used_in,
+            &context.substitutions,
+            &toggle,
+            &substitute,
+            &context.settings.profile,
+            language,
+        );
         options = html! {<div class="options">
             <Link<Route>
                 classes={classes!("recipe-edit")}
                 to={Route::EditRecipe{id: props.recipe_id.clone().unwrap()}}>
-                {"Edit"}
+                {language.t("action.edit")}
             </Link<Route>>
+            <button
+                class={classes!("recipe-export")}
+                onclick={on_json_ld_download}>
+                {"JSON-LD"}
+            </button>
+            <button
+                class={classes!("recipe-export")}
+                onclick={on_cooklang_download}>
+                {".cook"}
+            </button>
+            <button
+                class={classes!("recipe-export")}
+                onclick={on_markdown_download}>
+                {"Markdown"}
+            </button>
             <button
                 class={classes!("recipe-deselect")}
                 onclick={Callback::from(move |_| {
                     nc.back();
                 })}>
-                {"Close"}
+                {language.t("action.close")}
             </button>
         </div>};
     };
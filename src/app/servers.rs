@@ -0,0 +1,210 @@
+use crate::app::connection::validate_url;
+use crate::app::settings::AppSettings;
+use crate::app::{status_bar::Message, AppContext};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+// A knife server the user can switch to
+#[derive(Default, Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct ServerProfile {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ServerSwitcherProps {
+    pub current: AppSettings,
+    pub update_settings: Callback<AppSettings>,
+}
+
+#[function_component(ServerSwitcher)]
+pub fn server_switcher(props: &ServerSwitcherProps) -> Html {
+    if props.current.servers.len() < 2 {
+        return html! {};
+    }
+
+    let props_cloned = props.clone();
+    let on_select = Callback::from(move |e: Event| {
+        let url = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+
+        props_cloned.update_settings.emit(AppSettings {
+            server_url: url,
+            ..props_cloned.current.clone()
+        })
+    });
+
+    let options = props
+        .current
+        .servers
+        .iter()
+        .map(|server| {
+            html! {
+                <option
+                    selected={server.url == props.current.server_url}
+                    value={server.url.clone()}>
+                    {server.name.as_str()}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <select class="server-switcher" autocomplete="off" onchange={on_select}>
+            if props.current.active_server().is_none() {
+                <option hidden=true disabled=true selected=true value="">
+                    {props.current.server_url.as_str()}
+                </option>
+            }
+            {options}
+        </select>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ServerProfilesProps {
+    pub current: AppSettings,
+    pub update_settings: Callback<AppSettings>,
+}
+
+#[derive(PartialEq, Clone, Default, Debug)]
+struct ServerProfilesState {
+    name: String,
+    url: String,
+}
+
+#[function_component(ServerProfiles)]
+pub fn server_profiles(props: &ServerProfilesProps) -> Html {
+    let state = use_state(ServerProfilesState::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
+
+    let input_value = |e: InputEvent| {
+        e.target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value()
+    };
+
+    let state_cloned = state.clone();
+    let on_name_edit = Callback::from(move |e: InputEvent| {
+        state_cloned.set(ServerProfilesState {
+            name: input_value(e),
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let on_url_edit = Callback::from(move |e: InputEvent| {
+        state_cloned.set(ServerProfilesState {
+            url: input_value(e),
+            ..(*state_cloned).clone()
+        })
+    });
+
+    let state_cloned = state.clone();
+    let props_cloned = props.clone();
+    let on_add = Callback::from(move |_: MouseEvent| {
+        let name = state_cloned.name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        let url = match state_cloned.url.trim() {
+            "" => Ok(props_cloned.current.server_url.clone()),
+            url => validate_url(url),
+        };
+        let url = match url {
+            Ok(url) => url,
+            Err(message) => {
+                return context.status.emit(Message::Error(
+                    message.translate(props_cloned.current.language),
+                    chrono::Utc::now(),
+                ))
+            }
+        };
+
+        let mut servers = props_cloned.current.servers.clone();
+        servers.retain(|server| server.name != name);
+        servers.push(ServerProfile { name, url });
+
+        props_cloned.update_settings.emit(AppSettings {
+            servers,
+            ..props_cloned.current.clone()
+        });
+        state_cloned.set(ServerProfilesState::default());
+    });
+
+    let language = props.current.language;
+    let rows = props
+        .current
+        .servers
+        .iter()
+        .enumerate()
+        .map(|(index, server)| {
+            let props_use = props.clone();
+            let url = server.url.clone();
+            let on_use = Callback::from(move |_| {
+                props_use.update_settings.emit(AppSettings {
+                    server_url: url.clone(),
+                    ..props_use.current.clone()
+                })
+            });
+
+            let props_delete = props.clone();
+            let on_delete = Callback::from(move |_| {
+                let mut servers = props_delete.current.servers.clone();
+                servers.remove(index);
+                props_delete.update_settings.emit(AppSettings {
+                    servers,
+                    ..props_delete.current.clone()
+                })
+            });
+
+            let active = server.url == props.current.server_url;
+            html! {
+                <tr key={server.name.as_str()} class={classes!(active.then_some("active"))}>
+                    <td>{server.name.as_str()}</td>
+                    <td>{server.url.as_str()}</td>
+                    <td>
+                        <button disabled={active} onclick={on_use}>{language.t("servers.use")}</button>
+                    </td>
+                    <td>
+                        <button onclick={on_delete}>{language.t("action.delete")}</button>
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <table class="items servers">
+            {rows}
+            <tr key={"server_add"}>
+                <td>
+                    <input type="text"
+                        name="server_name"
+                        placeholder={language.t("servers.name")}
+                        oninput={on_name_edit}
+                        value={state.name.clone()}
+                    />
+                </td>
+                <td>
+                    <input type="text"
+                        name="server_url"
+                        placeholder={props.current.server_url.clone()}
+                        oninput={on_url_edit}
+                        value={state.url.clone()}
+                    />
+                </td>
+                <td>
+                    <button onclick={on_add}>{language.t("action.add")}</button>
+                </td>
+            </tr>
+        </table>
+    }
+}
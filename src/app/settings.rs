@@ -1,34 +1,99 @@
-use crate::app::set_title;
+use crate::app::auth::{Credentials, CredentialsForm};
+use crate::app::backend::retry::RequestPolicy;
+use crate::app::backup::{BackupPanel, PushPanel};
+use crate::app::connection::{probe, validate_url, ConnectionStatus, ProbeError};
+use crate::app::i18n::Language;
+use crate::app::profile::DietProfile;
+use crate::app::servers::{ServerProfile, ServerProfiles};
+use crate::app::theme::Theme;
+use crate::app::{set_title, AppContext, Route};
+use ladle::models::IngredientIndex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use unidecode::unidecode;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
+use yew_router::prelude::*;
+
+pub static LOCAL_SOURCE: &str = "local";
 
 #[derive(Default, Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct AppSettings {
     pub server_url: String,
+    #[serde(default)]
+    pub profile: DietProfile,
+    #[serde(default)]
+    pub servers: Vec<ServerProfile>,
+    // Credentials of each server, by url
+    #[serde(default)]
+    pub credentials: HashMap<String, Credentials>,
+    // Keep credentials in session storage rather than on disk
+    #[serde(default)]
+    pub session_only: bool,
+    // Keep the data in the browser instead of on a knife server
+    #[serde(default)]
+    pub local: bool,
+    #[serde(default)]
+    pub requests: RequestPolicy,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub theme: Theme,
+}
+
+impl AppSettings {
+    // Server profile pointing to the server in use, if any
+    pub fn active_server(&self) -> Option<&ServerProfile> {
+        self.servers
+            .iter()
+            .find(|server| server.url == self.server_url)
+    }
+
+    // Key identifying where the data comes from, used to separate caches
+    pub fn data_source(&self) -> &str {
+        match self.local {
+            true => LOCAL_SOURCE,
+            false => &self.server_url,
+        }
+    }
+
+    pub fn server_credentials(&self) -> Credentials {
+        self.credentials
+            .get(&self.server_url)
+            .cloned()
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct SettingsProps {
     pub current: AppSettings,
     pub update_settings: Callback<AppSettings>,
+    pub ingredient_cache_refresh: Callback<()>,
+    pub recipe_cache_refresh: Callback<()>,
 }
 
 #[derive(PartialEq, Clone, Default, Debug)]
 struct SettingsState {
     server_field_contents: String,
+    force: bool,
 }
 
 #[function_component(Settings)]
 pub fn settings(props: &SettingsProps) -> Html {
     let state = use_state(|| SettingsState {
         server_field_contents: props.current.server_url.clone(),
+        force: false,
     });
+    let connection = use_state(ConnectionStatus::default);
+    let context = use_context::<AppContext>().unwrap_or(AppContext::default());
 
-    set_title("Settings - spoon");
+    let language = props.current.language;
+    set_title(&format!("{} - spoon", language.t("header.settings")));
 
     let state_cloned = state.clone();
+    let connection_cloned = connection.clone();
     let on_server_edit = Callback::from(move |e: InputEvent| {
         let new_url = e
             .target()
@@ -38,23 +103,356 @@ pub fn settings(props: &SettingsProps) -> Html {
 
         state_cloned.set(SettingsState {
             server_field_contents: new_url.clone(),
+            ..(*state_cloned).clone()
         });
+        connection_cloned.set(ConnectionStatus::Unknown);
+    });
+
+    let state_cloned = state.clone();
+    let on_force_toggle = Callback::from(move |_| {
+        state_cloned.set(SettingsState {
+            force: !state_cloned.force,
+            ..(*state_cloned).clone()
+        })
     });
 
     let state_cloned = state.clone();
     let props_cloned = props.clone();
+    let connection_cloned = connection.clone();
+    let on_test = Callback::from(move |_: MouseEvent| {
+        let connection_cloned = connection_cloned.clone();
+        let url = match validate_url(&state_cloned.server_field_contents) {
+            Ok(url) => url,
+            Err(message) => {
+                return connection_cloned
+                    .set(ConnectionStatus::Unreachable(message.translate(language)))
+            }
+        };
+
+        let credentials = props_cloned
+            .current
+            .credentials
+            .get(&url)
+            .cloned()
+            .unwrap_or_default();
+        connection_cloned.set(ConnectionStatus::Testing);
+        wasm_bindgen_futures::spawn_local(async move {
+            connection_cloned.set(match probe(&url, &credentials, language).await {
+                Ok(report) => ConnectionStatus::Reachable(report),
+                Err(ProbeError::Unauthorized) => ConnectionStatus::AuthRequired(url),
+                Err(ProbeError::Failed(message)) => ConnectionStatus::Unreachable(message),
+            })
+        });
+    });
+
+    let state_cloned = state.clone();
+    let props_cloned = props.clone();
+    let connection_cloned = connection.clone();
     let on_server_submit = Callback::from(move |_: MouseEvent| {
+        let props_cloned = props_cloned.clone();
+        let connection_cloned = connection_cloned.clone();
+        let url = match validate_url(&state_cloned.server_field_contents) {
+            Ok(url) => url,
+            Err(message) => {
+                return connection_cloned
+                    .set(ConnectionStatus::Unreachable(message.translate(language)))
+            }
+        };
+
+        if state_cloned.force {
+            return props_cloned.update_settings.emit(AppSettings {
+                server_url: url,
+                ..props_cloned.current.clone()
+            });
+        }
+
+        // Only save urls of servers that answer
+        let credentials = props_cloned
+            .current
+            .credentials
+            .get(&url)
+            .cloned()
+            .unwrap_or_default();
+        connection_cloned.set(ConnectionStatus::Testing);
+        wasm_bindgen_futures::spawn_local(async move {
+            match probe(&url, &credentials, language).await {
+                Ok(report) => {
+                    connection_cloned.set(ConnectionStatus::Reachable(report));
+                    props_cloned.update_settings.emit(AppSettings {
+                        server_url: url,
+                        ..props_cloned.current.clone()
+                    })
+                }
+                Err(ProbeError::Unauthorized) => {
+                    connection_cloned.set(ConnectionStatus::AuthRequired(url))
+                }
+                Err(ProbeError::Failed(message)) => connection_cloned.set(
+                    ConnectionStatus::Unreachable(language.tf("settings.unreachable", &[&message])),
+                ),
+            }
+        });
+    });
+
+    let connection_html = match &*connection {
+        ConnectionStatus::Unknown => html! {},
+        ConnectionStatus::Testing => html! {
+            <div class="connection-status">{language.t("settings.testing")}</div>
+        },
+        ConnectionStatus::Reachable(report) => html! {
+            <div class="connection-status success">{report.describe(language)}</div>
+        },
+        ConnectionStatus::Unreachable(message) => html! {
+            <div class="connection-status error">{message}</div>
+        },
+        // Once saved, the credentials are used by the next test
+        ConnectionStatus::AuthRequired(url) => {
+            let connection_cloned = connection.clone();
+            html! {
+                <>
+                    <div class="connection-status error">{language.t("auth.required")}</div>
+                    <CredentialsForm
+                        key={url.clone()}
+                        server_url={Some(url.clone())}
+                        current={props.current.clone()}
+                        update_settings={props.update_settings.clone()}
+                        on_saved={Callback::from(move |_| connection_cloned.set(ConnectionStatus::Unknown))}
+                    />
+                </>
+            }
+        }
+    };
+
+    let props_cloned = props.clone();
+    let on_language_select = Callback::from(move |e: Event| {
+        let code = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        if let Some(language) = Language::all()
+            .into_iter()
+            .find(|language| language.code() == code)
+        {
+            props_cloned.update_settings.emit(AppSettings {
+                language,
+                ..props_cloned.current.clone()
+            })
+        }
+    });
+
+    let languages = Language::all()
+        .into_iter()
+        .map(|option| {
+            html! {
+                <option value={option.code()} selected={option == language}>
+                    {option.name()}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    let props_cloned = props.clone();
+    let on_theme_select = Callback::from(move |e: Event| {
+        let code = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        if let Some(theme) = Theme::all().into_iter().find(|theme| theme.code() == code) {
+            props_cloned.update_settings.emit(AppSettings {
+                theme,
+                ..props_cloned.current.clone()
+            })
+        }
+    });
+
+    let themes = Theme::all()
+        .into_iter()
+        .map(|option| {
+            html! {
+                <option value={option.code()} selected={option == props.current.theme}>
+                    {option.name(language)}
+                </option>
+            }
+        })
+        .collect::<Html>();
+
+    let props_cloned = props.clone();
+    let on_local_toggle = Callback::from(move |_| {
         props_cloned.update_settings.emit(AppSettings {
-            server_url: state_cloned.server_field_contents.clone(),
+            local: !props_cloned.current.local,
+            ..props_cloned.current.clone()
         })
     });
 
+    let props_cloned = props.clone();
+    let update_requests = move |e: Event, apply: fn(&mut RequestPolicy, u32)| {
+        let value = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>()
+            .value();
+        if let Ok(value) = value.trim().parse::<u32>() {
+            let mut requests = props_cloned.current.requests.clone();
+            apply(&mut requests, value);
+            props_cloned.update_settings.emit(AppSettings {
+                requests,
+                ..props_cloned.current.clone()
+            })
+        }
+    };
+    let update_timeout = update_requests.clone();
+    let on_timeout_edit = Callback::from(move |e: Event| {
+        update_timeout(e, |requests, seconds| {
+            requests.timeout_ms = seconds.max(1).saturating_mul(1000)
+        })
+    });
+    let update_attempts = update_requests.clone();
+    let on_attempts_edit = Callback::from(move |e: Event| {
+        update_attempts(e, |requests, attempts| requests.attempts = attempts.max(1))
+    });
+    let on_cache_edit = Callback::from(move |e: Event| {
+        update_requests(e, |requests, seconds| {
+            requests.cache_ttl_ms = seconds.saturating_mul(1000)
+        })
+    });
+
+    let props_cloned = props.clone();
+    let update_profile = Callback::from(move |profile: DietProfile| {
+        props_cloned.update_settings.emit(AppSettings {
+            profile,
+            ..props_cloned.current.clone()
+        })
+    });
+
+    let profile = &props.current.profile;
+    let restriction_toggle =
+        |name: &'static str, label: &'static str, checked: bool, toggle: fn(&mut DietProfile)| {
+            let update_profile = update_profile.clone();
+            let profile = profile.clone();
+            html! {
+                <tr>
+                    <td>
+                        <input type="checkbox"
+                            name={name}
+                            checked={checked}
+                            onclick={Callback::from(move |_| {
+                                let mut profile = profile.clone();
+                                toggle(&mut profile);
+                                update_profile.emit(profile);
+                            })}
+                        />
+                    </td>
+                    <td>
+                        <label for={name}>{label}</label>
+                    </td>
+                </tr>
+            }
+        };
+
+    let avoided_ingredients = profile
+        .avoided_ingredients
+        .iter()
+        .map(|ingredient| {
+            let update_profile = update_profile.clone();
+            let profile = profile.clone();
+            let id = ingredient.id.clone();
+            html! {
+                <li key={ingredient.id.as_str()}>
+                    <span>{ingredient.name.as_str()}</span>
+                    <button onclick={Callback::from(move |_| {
+                        let mut profile = profile.clone();
+                        profile.avoided_ingredients.retain(|i| i.id != id);
+                        update_profile.emit(profile);
+                    })}>{language.t("action.delete")}</button>
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    let mut options: Vec<IngredientIndex> = context
+        .ingredient_cache
+        .iter()
+        .filter(|idx| !profile.avoided_ingredients.contains(idx))
+        .cloned()
+        .collect();
+    options.sort_by(|lhs, rhs| unidecode(&lhs.name).cmp(&unidecode(&rhs.name)));
+    let option_html = options
+        .iter()
+        .map(|opt| {
+            html! {
+                <option value={opt.id.clone()}>{opt.name.clone()}</option>
+            }
+        })
+        .collect::<Html>();
+
+    let update_profile_cloned = update_profile.clone();
+    let profile_cloned = profile.clone();
+    let context_cloned = context.clone();
+    let on_avoided_select = Callback::from(move |e: Event| {
+        let target = e
+            .target()
+            .expect("Intercepted event with no target")
+            .unchecked_into::<HtmlInputElement>();
+
+        if let Some(ingredient) = context_cloned
+            .ingredient_cache
+            .iter()
+            .find(|index| index.id == target.value())
+        {
+            let mut profile = profile_cloned.clone();
+            profile.avoided_ingredients.push(ingredient.clone());
+            update_profile_cloned.emit(profile);
+        }
+
+        target.set_value("");
+    });
+
     html! {
         <div class="settings">
+            <table class="items language">
+                <tr>
+                    <td>
+                        <label for="language">{language.t("settings.language")}</label>
+                    </td>
+                    <td>
+                        <select name="language" onchange={on_language_select}>
+                            {languages}
+                        </select>
+                    </td>
+                </tr>
+                <tr>
+                    <td>
+                        <label for="theme">{language.t("settings.theme")}</label>
+                    </td>
+                    <td>
+                        <select name="theme" onchange={on_theme_select}>
+                            {themes}
+                        </select>
+                    </td>
+                </tr>
+            </table>
+            <table class="items storage">
+                <tr>
+                    <td>
+                        <input type="checkbox"
+                            name="local"
+                            checked={props.current.local}
+                            onclick={on_local_toggle}
+                        />
+                    </td>
+                    <td>
+                        <label for="local">{language.t("settings.local")}</label>
+                    </td>
+                </tr>
+            </table>
+            if props.current.local {
+                <PushPanel server_url={props.current.server_url.clone()}/>
+            }
             <table class="items">
                 <tr>
                     <td>
-                        <label for="server">{"Knife server url:"}</label>
+                        <label for="server">{language.t("settings.server_url")}</label>
                     </td>
                     <td>
                         <input type="text"
@@ -66,12 +464,115 @@ pub fn settings(props: &SettingsProps) -> Html {
                     <td>
                         <button
                             name="server_submit"
+                            disabled={*connection == ConnectionStatus::Testing}
                             onclick={on_server_submit}>
-                            {"Submit"}
+                            {language.t("action.submit")}
                         </button>
                     </td>
+                    <td>
+                        <button
+                            name="server_test"
+                            disabled={*connection == ConnectionStatus::Testing}
+                            onclick={on_test}>
+                            {language.t("settings.test")}
+                        </button>
+                    </td>
+                    <td>
+                        <input type="checkbox"
+                            name="server_force"
+                            checked={state.force}
+                            onclick={on_force_toggle}
+                        />
+                        <label for="server_force">{language.t("settings.force")}</label>
+                    </td>
+                </tr>
+            </table>
+            {connection_html}
+            <CredentialsForm
+                key={props.current.server_url.clone()}
+                current={props.current.clone()}
+                update_settings={props.update_settings.clone()}
+            />
+            <h3>{language.t("settings.requests")}</h3>
+            <table class="items requests">
+                <tr>
+                    <td><label for="timeout">{language.t("settings.timeout")}</label></td>
+                    <td>
+                        <input type="number"
+                            name="timeout"
+                            min="1"
+                            value={(props.current.requests.timeout_ms / 1000).to_string()}
+                            onchange={on_timeout_edit}
+                        />
+                    </td>
                 </tr>
+                <tr>
+                    <td><label for="attempts">{language.t("settings.attempts")}</label></td>
+                    <td>
+                        <input type="number"
+                            name="attempts"
+                            min="1"
+                            value={props.current.requests.attempts.to_string()}
+                            onchange={on_attempts_edit}
+                        />
+                    </td>
+                </tr>
+                <tr>
+                    <td><label for="cache_ttl">{language.t("settings.cache_ttl")}</label></td>
+                    <td>
+                        <input type="number"
+                            name="cache_ttl"
+                            min="0"
+                            value={(props.current.requests.cache_ttl_ms / 1000).to_string()}
+                            onchange={on_cache_edit}
+                        />
+                    </td>
+                </tr>
+            </table>
+            <h3>{language.t("settings.servers")}</h3>
+            <ServerProfiles
+                current={props.current.clone()}
+                update_settings={props.update_settings.clone()}
+            />
+            <h3>{language.t("settings.profile")}</h3>
+            <table class="items profile">
+                {restriction_toggle("avoid_dairy", language.t("settings.avoid_dairy"), profile.dairy, |p| p.dairy = !p.dairy)}
+                {restriction_toggle("avoid_gluten", language.t("settings.avoid_gluten"), profile.gluten, |p| p.gluten = !p.gluten)}
+                {restriction_toggle("avoid_meat", language.t("settings.avoid_meat"), profile.meat, |p| p.meat = !p.meat)}
+                {restriction_toggle(
+                    "avoid_animal_product",
+                    language.t("settings.avoid_animal_product"),
+                    profile.animal_product,
+                    |p| p.animal_product = !p.animal_product
+                )}
+                {restriction_toggle(
+                    "hide_incompatible",
+                    language.t("settings.hide_incompatible"),
+                    profile.hide_incompatible,
+                    |p| p.hide_incompatible = !p.hide_incompatible
+                )}
             </table>
+            <ul class="avoided-ingredients">
+                {avoided_ingredients}
+                <li key={"avoided_add"}>
+                    <select
+                        autocomplete="off"
+                        onchange={on_avoided_select}>
+                        <option hidden=true disabled=true selected=true value="">
+                            {language.t("settings.avoid_ingredient")}
+                        </option>
+                        {option_html}
+                    </select>
+                </li>
+            </ul>
+            <h3>{language.t("settings.backup")}</h3>
+            <BackupPanel
+                ingredient_cache_refresh={props.ingredient_cache_refresh.clone()}
+                recipe_cache_refresh={props.recipe_cache_refresh.clone()}
+            />
+            <Link<Route> to={Route::Migrate}>
+                {language.t("migration.title")}
+            </Link<Route>>
         </div>
     }
 }
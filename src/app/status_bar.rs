@@ -1,7 +1,14 @@
+use crate::app::i18n::Language;
 use chrono::{DateTime, Utc};
+use std::rc::Rc;
 use yew::prelude::*;
 use yew_hooks::prelude::*;
 
+// Past messages kept for the history panel
+const HISTORY_LENGTH: usize = 100;
+// Messages on screen at once, the oldest leaving early past that
+const TOAST_LIMIT: usize = 5;
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum Message {
     None,
@@ -10,65 +17,326 @@ pub enum Message {
     Error(String, DateTime<Utc>),
 }
 
+impl Message {
+    // Time a toast stays on screen, errors being left longer to be read
+    fn duration_ms(&self) -> u32 {
+        match self {
+            Message::None => 0,
+            Message::Success(_, _) => 3000,
+            Message::Info(_, _) => 5000,
+            Message::Error(_, _) => 10000,
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Message::None => "",
+            Message::Success(_, _) => "success",
+            Message::Info(_, _) => "info",
+            Message::Error(_, _) => "error",
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            Message::None => "",
+            Message::Success(text, _) | Message::Info(text, _) | Message::Error(text, _) => text,
+        }
+    }
+
+    // Whether both say the same thing, whenever they were sent
+    fn repeats(&self, other: &Message) -> bool {
+        self.class() == other.class() && self.text() == other.text()
+    }
+
+    fn time(&self) -> Option<&DateTime<Utc>> {
+        match self {
+            Message::None => None,
+            Message::Success(_, time) | Message::Info(_, time) | Message::Error(_, time) => {
+                Some(time)
+            }
+        }
+    }
+}
+
+// Button offered along a message, such as "Retry" or "Undo"
+#[derive(PartialEq, Clone, Debug)]
+pub struct Action {
+    pub label: String,
+    pub run: Callback<()>,
+}
+
+impl Action {
+    pub fn new(label: &str, run: Callback<()>) -> Self {
+        Action {
+            label: label.to_string(),
+            run,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Notification {
+    id: u32,
+    message: Message,
+    action: Option<Action>,
+    // Times the message was received in a row
+    count: u32,
+}
+
+pub enum NotificationAction {
+    Push(Message, Option<Action>),
+    Dismiss(u32),
+    ClearHistory,
+}
+
+// Messages on screen, and all the messages received, newest first
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct Notifications {
+    next_id: u32,
+    pub toasts: Vec<Notification>,
+    pub history: Vec<Notification>,
+}
+
+impl Reducible for Notifications {
+    type Action = NotificationAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut new_state: Self = (*self).clone();
+
+        match action {
+            NotificationAction::Push(Message::None, _) => (),
+            NotificationAction::Push(message, action) => {
+                // A message already on screen is counted instead of stacked
+                match new_state
+                    .toasts
+                    .iter_mut()
+                    .find(|toast| toast.message.repeats(&message))
+                {
+                    Some(toast) => toast.count += 1,
+                    None => {
+                        new_state.toasts.insert(
+                            0,
+                            Notification {
+                                id: new_state.next_id,
+                                message: message.clone(),
+                                action: action.clone(),
+                                count: 1,
+                            },
+                        );
+                        new_state.toasts.truncate(TOAST_LIMIT);
+                    }
+                }
+
+                match new_state.history.first_mut() {
+                    Some(last) if last.message.repeats(&message) => {
+                        last.count += 1;
+                        last.message = message;
+                    }
+                    _ => {
+                        new_state.history.insert(
+                            0,
+                            Notification {
+                                id: new_state.next_id,
+                                message,
+                                action,
+                                count: 1,
+                            },
+                        );
+                        new_state.history.truncate(HISTORY_LENGTH);
+                    }
+                }
+                new_state.next_id = new_state.next_id.wrapping_add(1);
+            }
+            NotificationAction::Dismiss(id) => new_state.toasts.retain(|toast| toast.id != id),
+            NotificationAction::ClearHistory => new_state.history.clear(),
+        }
+
+        new_state.into()
+    }
+}
+
 #[derive(Properties, PartialEq, Clone)]
-pub struct StatusBarProps {
-    pub current: Message,
+struct ToastProps {
+    language: Language,
+    notification: Notification,
+    dismiss: Callback<u32>,
+}
+
+#[function_component(Toast)]
+fn toast(props: &ToastProps) -> Html {
+    let id = props.notification.id;
+
+    let dismiss = props.dismiss.clone();
+    use_timeout(
+        move || dismiss.emit(id),
+        props.notification.message.duration_ms(),
+    );
+
+    let dismiss = props.dismiss.clone();
+    let on_dismiss = Callback::from(move |_| dismiss.emit(id));
+
+    let action = props.notification.action.clone().map(|action| {
+        let dismiss = props.dismiss.clone();
+        let onclick = Callback::from(move |_| {
+            action.run.emit(());
+            dismiss.emit(id);
+        });
+        html! {
+            <button class="toast-action" {onclick}>{action.label.as_str()}</button>
+        }
+    });
+
+    html! {
+        <div class={classes!("toast", props.notification.message.class())}>
+            <span class="toast-text">{props.notification.message.text()}</span>
+            if props.notification.count > 1 {
+                <span class="toast-count">{format!("×{}", props.notification.count)}</span>
+            }
+            {for action}
+            <button class="toast-dismiss" title={props.language.t("notifications.dismiss")} onclick={on_dismiss}>{"✕"}</button>
+        </div>
+    }
 }
 
 #[derive(Properties, PartialEq, Clone)]
-pub struct StatusBarState {
-    pub shown: bool,
+pub struct StatusBarProps {
+    pub language: Language,
+    pub toasts: Vec<Notification>,
+    pub dismiss: Callback<u32>,
 }
 
+// Stack of the messages on screen, each leaving after its own delay
 #[function_component(StatusBar)]
 pub fn status_bar(props: &StatusBarProps) -> Html {
-    let state = use_state(|| StatusBarState { shown: false });
-
-    {
-        let state = state.clone();
-        let props_cloned = props.clone();
-        use_effect_with_deps(
-            move |_| {
-                match props_cloned.current {
-                    Message::Error(_, _) | Message::Info(_, _) | Message::Success(_, _) => {
-                        state.set(StatusBarState { shown: true })
-                    }
-                    _ => (),
-                };
-            },
-            props.clone(),
-        )
+    let toasts = props.toasts.iter().map(|notification| {
+        html! {
+            <Toast
+                key={notification.id}
+                language={props.language}
+                notification={notification.clone()}
+                dismiss={props.dismiss.clone()}
+            />
+        }
+    });
+
+    html! {
+        <div class="status">
+            {for toasts}
+        </div>
     }
+}
 
-    {
-        let state = state.clone();
-        use_debounce_effect_with_deps(
-            move || {
-                state.set(StatusBarState { shown: false });
-            },
-            2000,
-            props.clone(),
-        );
-    };
+#[derive(Properties, PartialEq, Clone)]
+pub struct NotificationHistoryProps {
+    pub language: Language,
+    pub history: Vec<Notification>,
+    pub clear: Callback<()>,
+}
 
-    let class = format!(
-        "status {}",
-        match state.shown {
-            true => "",
-            false => "hidden",
-        }
-    );
+#[function_component(NotificationHistory)]
+pub fn notification_history(props: &NotificationHistoryProps) -> Html {
+    let open = use_state(|| false);
+
+    let open_cloned = open.clone();
+    let on_toggle = Callback::from(move |_| open_cloned.set(!*open_cloned));
 
-    match &props.current {
-        Message::None => html! {},
-        Message::Success(message, _) => {
-            html! {<div class={format!("{} success", class)}>{message.as_str()}</div>}
+    let errors = props
+        .history
+        .iter()
+        .filter(|notification| matches!(notification.message, Message::Error(_, _)))
+        .count();
+
+    let entries = props.history.iter().map(|notification| {
+        let time = notification
+            .message
+            .time()
+            .map(|time| props.language.date(time))
+            .unwrap_or_default();
+        html! {
+            <li class={notification.message.class()} key={notification.id}>
+                <span class="history-time">{time}</span>
+                <span class="history-text">{notification.message.text()}</span>
+                if notification.count > 1 {
+                    <span class="history-repeats">{format!("×{}", notification.count)}</span>
+                }
+            </li>
         }
-        Message::Info(message, _) => {
-            html! {<div class={format!("{} info", class)}>{message.as_str()}</div>}
+    });
+
+    html! {
+        <div class="notification-history">
+            <button class="history-toggle" title={props.language.t("notifications.title")} onclick={on_toggle}>
+                {"🔔"}
+                if errors > 0 {
+                    <span class="history-count">{errors}</span>
+                }
+            </button>
+            if *open {
+                <div class="history-panel">
+                    if props.history.is_empty() {
+                        <p>{props.language.t("notifications.empty")}</p>
+                    } else {
+                        <ul>{for entries}</ul>
+                        <button onclick={props.clear.reform(|_| ())}>{props.language.t("notifications.clear")}</button>
+                    }
+                </div>
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(state: Rc<Notifications>, text: &str) -> Rc<Notifications> {
+        state.reduce(NotificationAction::Push(
+            Message::Error(text.to_string(), Utc::now()),
+            None,
+        ))
+    }
+
+    #[test]
+    fn toasts_are_capped() {
+        let mut state = Rc::new(Notifications::default());
+        for index in 0..TOAST_LIMIT + 3 {
+            state = push(state, &format!("Error {}", index));
         }
-        Message::Error(message, _) => {
-            html! {<div class={format!("{} error", class)}>{message.as_str()}</div>}
+
+        assert_eq!(state.toasts.len(), TOAST_LIMIT);
+        // The newest are kept
+        assert_eq!(
+            state.toasts[0].message.text(),
+            format!("Error {}", TOAST_LIMIT + 2)
+        );
+        assert_eq!(state.history.len(), TOAST_LIMIT + 3);
+    }
+
+    #[test]
+    fn repeated_messages_are_folded() {
+        let mut state = Rc::new(Notifications::default());
+        for _ in 0..3 {
+            state = push(state, "Network error");
         }
+        state = push(state, "Timeout");
+        state = push(state, "Network error");
+
+        let toasts: Vec<(&str, u32)> = state
+            .toasts
+            .iter()
+            .map(|toast| (toast.message.text(), toast.count))
+            .collect();
+        assert_eq!(toasts, vec![("Timeout", 1), ("Network error", 4)]);
+
+        let history: Vec<(&str, u32)> = state
+            .history
+            .iter()
+            .map(|entry| (entry.message.text(), entry.count))
+            .collect();
+        assert_eq!(
+            history,
+            vec![("Network error", 1), ("Timeout", 1), ("Network error", 3)]
+        );
     }
 }
@@ -0,0 +1,304 @@
+use crate::app::recipes::dependents::reverse_dependencies;
+use ladle::models::{IngredientIndex, Recipe, RecipeIndex};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::rc::Rc;
+use yew::prelude::*;
+
+// Delay between two comparisons of the caches with the server. Knife has no
+// query for the changes since a date, so each comparison downloads the
+// indexes: keep it rare, and skip it while the page is hidden.
+pub const SYNC_INTERVAL_MS: i32 = 30 * 60 * 1000;
+
+// A change made from this client, applied to the caches directly instead of
+// reloading them from the server
+#[derive(Clone, Debug, PartialEq)]
+pub enum CacheChange {
+    IngredientSaved(IngredientIndex),
+    IngredientRemoved(String),
+    RecipeSaved(Recipe),
+    // Recipe created or modified, whose details are not at hand
+    RecipeIndexed(RecipeIndex),
+    RecipeRemoved(String),
+}
+
+// Collections modified by a change, so that only those are stored again
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct Touched {
+    pub ingredients: bool,
+    pub recipes: bool,
+    pub details: bool,
+}
+
+// Drop the details of `ids` and of the recipes depending on them, directly or
+// not, as the classifications the server computed for them are outdated.
+// Returns whether any was dropped.
+fn evict_with_dependents(details: &mut HashMap<String, Recipe>, ids: Vec<String>) -> bool {
+    let reverse = reverse_dependencies(details);
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut pending = ids;
+
+    while let Some(id) = pending.pop() {
+        if visited.insert(id.clone()) {
+            if let Some(users) = reverse.get(&id) {
+                pending.extend(users.iter().map(|user| user.id.clone()));
+            }
+        }
+    }
+
+    let count = details.len();
+    details.retain(|id, _| !visited.contains(id));
+    details.len() != count
+}
+
+// Recipes requiring an ingredient directly
+fn users_of(details: &HashMap<String, Recipe>, ingredient_id: &str) -> Vec<String> {
+    details
+        .values()
+        .filter(|recipe| {
+            recipe
+                .requirements
+                .iter()
+                .any(|requirement| requirement.ingredient.id == ingredient_id)
+        })
+        .map(|recipe| recipe.id.clone())
+        .collect()
+}
+
+impl CacheChange {
+    pub fn apply(
+        self,
+        ingredients: &mut HashSet<IngredientIndex>,
+        recipes: &mut HashSet<RecipeIndex>,
+        details: &mut HashMap<String, Recipe>,
+    ) -> Touched {
+        match self {
+            CacheChange::IngredientSaved(ingredient) => {
+                // The classifications may have changed, which the index does
+                // not show
+                let users = users_of(details, &ingredient.id);
+                let evicted = evict_with_dependents(details, users);
+
+                let changed = !ingredients.contains(&ingredient);
+                if changed {
+                    ingredients.retain(|index| index.id != ingredient.id);
+                    ingredients.insert(ingredient);
+                }
+                Touched {
+                    ingredients: changed,
+                    details: evicted,
+                    ..Touched::default()
+                }
+            }
+            CacheChange::IngredientRemoved(id) => {
+                let count = ingredients.len();
+                ingredients.retain(|index| index.id != id);
+                Touched {
+                    ingredients: ingredients.len() != count,
+                    ..Touched::default()
+                }
+            }
+            CacheChange::RecipeSaved(recipe) => {
+                let index = RecipeIndex {
+                    id: recipe.id.clone(),
+                    name: recipe.name.clone(),
+                };
+                let changed = !recipes.contains(&index);
+                if changed {
+                    recipes.retain(|index| index.id != recipe.id);
+                    recipes.insert(index);
+                }
+                evict_with_dependents(details, vec![recipe.id.clone()]);
+                details.insert(recipe.id.clone(), recipe);
+                Touched {
+                    recipes: changed,
+                    details: true,
+                    ..Touched::default()
+                }
+            }
+            CacheChange::RecipeIndexed(recipe) => {
+                recipes.retain(|index| index.id != recipe.id);
+                // Fetched on the next sync
+                evict_with_dependents(details, vec![recipe.id.clone()]);
+                recipes.insert(recipe);
+                Touched {
+                    recipes: true,
+                    details: true,
+                    ..Touched::default()
+                }
+            }
+            CacheChange::RecipeRemoved(id) => {
+                recipes.retain(|index| index.id != id);
+                evict_with_dependents(details, vec![id]);
+                Touched {
+                    recipes: true,
+                    details: true,
+                    ..Touched::default()
+                }
+            }
+        }
+    }
+}
+
+// Entries to add to and remove from a cached index to match the server. A
+// renamed entry appears in both.
+pub struct IndexDiff<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+}
+
+impl<T: Eq + Hash + Clone> IndexDiff<T> {
+    pub fn new(cached: &HashSet<T>, fresh: &HashSet<T>) -> Self {
+        IndexDiff {
+            added: fresh.difference(cached).cloned().collect(),
+            removed: cached.difference(fresh).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+// Counter bumped to get an effect to run from callbacks holding an outdated
+// copy of the context
+#[derive(Default, PartialEq, Clone, Copy, Debug)]
+pub struct Revision(pub u32);
+
+impl Reducible for Revision {
+    type Action = ();
+
+    fn reduce(self: Rc<Self>, _: Self::Action) -> Rc<Self> {
+        Revision(self.0.wrapping_add(1)).into()
+    }
+}
+
+// Caches of the data source in use. They are only changed through actions,
+// so that each update starts from the current state instead of a copy taken
+// before a request.
+#[derive(Clone, Default, Debug)]
+pub struct Caches {
+    // Data source the caches belong to, answers for another one are dropped
+    pub source: String,
+    pub ingredients: HashSet<IngredientIndex>,
+    pub recipes: HashSet<RecipeIndex>,
+    pub details: HashMap<String, Recipe>,
+    // Time at which each of the details was fetched, in milliseconds
+    pub fetched: HashMap<String, f64>,
+    // Bumped on each change of the matching collection, to store it
+    pub ingredients_revision: u32,
+    pub recipes_revision: u32,
+    pub details_revision: u32,
+}
+
+pub enum CacheAction {
+    // Switch to the caches of another data source
+    Load(Caches),
+    ReplaceIngredients(String, HashSet<IngredientIndex>),
+    // Details of the recipes removed or renamed are dropped along
+    ReplaceRecipes(String, HashSet<RecipeIndex>),
+    MergeDetails(String, Vec<Recipe>),
+    // Drop the details fetched before the given time, changes made on the
+    // server to existing recipes not showing in the index
+    ExpireDetails(String, f64),
+    ClearDetails(String),
+    // Change made while the given data source was in use
+    Apply(String, CacheChange),
+}
+
+impl Reducible for Caches {
+    type Action = CacheAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let source = match &action {
+            CacheAction::Load(_) => None,
+            CacheAction::ReplaceIngredients(source, _)
+            | CacheAction::ReplaceRecipes(source, _)
+            | CacheAction::MergeDetails(source, _)
+            | CacheAction::ExpireDetails(source, _)
+            | CacheAction::ClearDetails(source)
+            | CacheAction::Apply(source, _) => Some(source),
+        };
+        if source.map_or(false, |source| *source != self.source) {
+            return self;
+        }
+
+        let mut new_state: Self = (*self).clone();
+
+        match action {
+            CacheAction::Load(caches) => return caches.into(),
+            CacheAction::ReplaceIngredients(_, ingredients) => {
+                // Leave the state alone when nothing changed, to skip a render
+                if IndexDiff::new(&self.ingredients, &ingredients).is_empty() {
+                    return self;
+                }
+                new_state.ingredients = ingredients;
+                new_state.ingredients_revision = new_state.ingredients_revision.wrapping_add(1);
+            }
+            CacheAction::ReplaceRecipes(_, recipes) => {
+                let diff = IndexDiff::new(&self.recipes, &recipes);
+                if diff.is_empty() {
+                    return self;
+                }
+                // Renamed recipes appear on both sides, their details are outdated
+                for recipe in diff.removed.iter().chain(diff.added.iter()) {
+                    new_state.details.remove(&recipe.id);
+                }
+                new_state.recipes = recipes;
+                new_state.recipes_revision = new_state.recipes_revision.wrapping_add(1);
+                new_state.details_revision = new_state.details_revision.wrapping_add(1);
+            }
+            CacheAction::MergeDetails(_, recipes) => {
+                if recipes.is_empty() {
+                    return self;
+                }
+                let now = js_sys::Date::now();
+                for recipe in recipes {
+                    new_state.fetched.insert(recipe.id.clone(), now);
+                    new_state.details.insert(recipe.id.clone(), recipe);
+                }
+                new_state.details_revision = new_state.details_revision.wrapping_add(1);
+            }
+            CacheAction::ExpireDetails(_, before) => {
+                let fetched = &self.fetched;
+                new_state
+                    .details
+                    .retain(|id, _| fetched.get(id).map_or(false, |time| *time >= before));
+                if new_state.details.len() == self.details.len() {
+                    return self;
+                }
+                let details = &new_state.details;
+                new_state.fetched.retain(|id, _| details.contains_key(id));
+                new_state.details_revision = new_state.details_revision.wrapping_add(1);
+            }
+            CacheAction::ClearDetails(_) => {
+                new_state.details.clear();
+                new_state.fetched.clear();
+                new_state.details_revision = new_state.details_revision.wrapping_add(1);
+            }
+            CacheAction::Apply(_, change) => {
+                if let CacheChange::RecipeSaved(recipe) = &change {
+                    new_state
+                        .fetched
+                        .insert(recipe.id.clone(), js_sys::Date::now());
+                }
+                let touched = change.apply(
+                    &mut new_state.ingredients,
+                    &mut new_state.recipes,
+                    &mut new_state.details,
+                );
+                if touched.ingredients {
+                    new_state.ingredients_revision = new_state.ingredients_revision.wrapping_add(1);
+                }
+                if touched.recipes {
+                    new_state.recipes_revision = new_state.recipes_revision.wrapping_add(1);
+                }
+                if touched.details {
+                    new_state.details_revision = new_state.details_revision.wrapping_add(1);
+                }
+            }
+        }
+
+        new_state.into()
+    }
+}
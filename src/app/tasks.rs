@@ -0,0 +1,59 @@
+use futures::future::{AbortHandle, Abortable};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::rc::Rc;
+use yew::prelude::*;
+
+// Futures spawned on behalf of a component, dropped when it unmounts so that
+// pending requests do not update a component that is gone
+#[derive(Clone, Default)]
+pub struct TaskScope(Rc<RefCell<Tasks>>);
+
+// Handles of the tasks still running, by order of spawning
+#[derive(Default)]
+struct Tasks {
+    next: usize,
+    running: HashMap<usize, AbortHandle>,
+}
+
+impl TaskScope {
+    // The returned handle aborts this task alone, e.g. once what it fetches
+    // is no longer displayed
+    pub fn spawn(&self, task: impl Future<Output = ()> + 'static) -> AbortHandle {
+        let (handle, registration) = AbortHandle::new_pair();
+        let key = {
+            let mut tasks = self.0.borrow_mut();
+            let key = tasks.next;
+            tasks.next += 1;
+            tasks.running.insert(key, handle.clone());
+            key
+        };
+
+        let tasks = self.0.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = Abortable::new(task, registration).await;
+            tasks.borrow_mut().running.remove(&key);
+        });
+
+        handle
+    }
+
+    fn abort_all(&self) {
+        self.0
+            .borrow_mut()
+            .running
+            .drain()
+            .for_each(|(_, handle)| handle.abort());
+    }
+}
+
+#[hook]
+pub fn use_task_scope() -> TaskScope {
+    let scope = use_state(TaskScope::default);
+
+    let scope_cloned = (*scope).clone();
+    use_effect_with_deps(move |_| move || scope_cloned.abort_all(), ());
+
+    (*scope).clone()
+}
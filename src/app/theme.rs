@@ -0,0 +1,53 @@
+use crate::app::i18n::Language;
+use serde::{Deserialize, Serialize};
+
+// Colors of the interface. The palettes are CSS variables in index.scss,
+// selected by the `data-theme` attribute of the root element.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Theme {
+    Light,
+    Dark,
+    // Light or dark depending on the preference of the browser
+    #[default]
+    System,
+    HighContrast,
+}
+
+impl Theme {
+    pub fn all() -> [Theme; 4] {
+        [
+            Theme::System,
+            Theme::Light,
+            Theme::Dark,
+            Theme::HighContrast,
+        ]
+    }
+
+    // Value of the `data-theme` attribute
+    pub fn code(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::System => "system",
+            Theme::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn name(self, language: Language) -> &'static str {
+        match self {
+            Theme::Light => language.t("theme.light"),
+            Theme::Dark => language.t("theme.dark"),
+            Theme::System => language.t("theme.system"),
+            Theme::HighContrast => language.t("theme.high_contrast"),
+        }
+    }
+}
+
+pub fn apply_theme(theme: Theme) {
+    if let Some(root) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.document_element())
+    {
+        root.set_attribute("data-theme", theme.code()).ok();
+    }
+}